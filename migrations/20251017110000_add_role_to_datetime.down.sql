@@ -0,0 +1,36 @@
+-- Add down migration script here
+
+-- only the additional datetimes have a role, without it they can not be
+-- told apart from the primary ones, so they are dropped with their links
+UPDATE data_import_row_item
+SET datetime_uuid = NULL
+WHERE datetime_uuid IN (SELECT uuid FROM datetime WHERE role IS NOT NULL);
+
+DELETE FROM transaction_datetime
+WHERE rel_type = 'Additional';
+
+DELETE FROM datetime
+WHERE role IS NOT NULL;
+
+CREATE TABLE transaction_datetime_old (
+    transaction_uuid text not null unique,
+    datetime_uuid text not null,
+    rel_type text not null,
+    PRIMARY KEY (transaction_uuid, datetime_uuid),
+    FOREIGN KEY (transaction_uuid) REFERENCES "transaction" (uuid)
+        ON UPDATE RESTRICT ON DELETE CASCADE,
+    FOREIGN KEY (datetime_uuid) REFERENCES datetime (uuid)
+        ON UPDATE RESTRICT ON DELETE RESTRICT
+);
+
+INSERT INTO transaction_datetime_old
+SELECT transaction_uuid, datetime_uuid, rel_type
+FROM transaction_datetime;
+
+DROP TABLE transaction_datetime;
+
+ALTER TABLE transaction_datetime_old
+RENAME TO transaction_datetime;
+
+ALTER TABLE datetime
+DROP COLUMN role;
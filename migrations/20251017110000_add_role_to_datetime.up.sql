@@ -0,0 +1,26 @@
+-- Add up migration script here
+
+ALTER TABLE datetime
+ADD COLUMN role text;
+
+-- a transaction can have additional datetimes next to its primary one,
+-- so transaction_uuid can not stay unique on its own
+CREATE TABLE transaction_datetime_new (
+    transaction_uuid text not null,
+    datetime_uuid text not null,
+    rel_type text not null,
+    PRIMARY KEY (transaction_uuid, datetime_uuid),
+    FOREIGN KEY (transaction_uuid) REFERENCES "transaction" (uuid)
+        ON UPDATE RESTRICT ON DELETE CASCADE,
+    FOREIGN KEY (datetime_uuid) REFERENCES datetime (uuid)
+        ON UPDATE RESTRICT ON DELETE RESTRICT
+);
+
+INSERT INTO transaction_datetime_new
+SELECT transaction_uuid, datetime_uuid, rel_type
+FROM transaction_datetime;
+
+DROP TABLE transaction_datetime;
+
+ALTER TABLE transaction_datetime_new
+RENAME TO transaction_datetime;
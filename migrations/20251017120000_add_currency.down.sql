@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+ALTER TABLE movement
+DROP COLUMN currency;
+
+ALTER TABLE profile
+DROP COLUMN currency;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+
+ALTER TABLE movement
+ADD COLUMN currency text not null default 'EUR';
+
+ALTER TABLE profile
+ADD COLUMN currency text not null default '{"Fixed":"EUR"}';
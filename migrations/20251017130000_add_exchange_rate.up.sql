@@ -0,0 +1,8 @@
+-- Add up migration script here
+
+CREATE TABLE exchange_rate (
+    date text not null,
+    currency text not null,
+    rate integer not null,
+    PRIMARY KEY (date, currency)
+);
@@ -0,0 +1,4 @@
+-- Add up migration script here
+
+ALTER TABLE profile
+ADD COLUMN headers text not null default '{}';
@@ -0,0 +1,7 @@
+-- Add down migration script here
+
+ALTER TABLE profile
+DROP COLUMN previous_uuid;
+
+ALTER TABLE profile
+DROP COLUMN version;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+
+ALTER TABLE profile
+ADD COLUMN version integer not null default 1;
+
+ALTER TABLE profile
+ADD COLUMN previous_uuid blob references profile(uuid);
@@ -0,0 +1,3 @@
+-- Add down migration script here
+
+DROP INDEX IF EXISTS data_import_file_hash;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+
+CREATE INDEX IF NOT EXISTS data_import_file_hash
+ON data_import(file_hash);
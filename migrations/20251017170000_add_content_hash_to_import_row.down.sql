@@ -0,0 +1,6 @@
+-- Add down migration script here
+
+DROP INDEX IF EXISTS data_import_row_content_hash;
+
+ALTER TABLE data_import_row
+DROP COLUMN content_hash;
@@ -0,0 +1,7 @@
+-- Add up migration script here
+
+ALTER TABLE data_import_row
+ADD COLUMN content_hash blob;
+
+CREATE INDEX IF NOT EXISTS data_import_row_content_hash
+ON data_import_row(content_hash);
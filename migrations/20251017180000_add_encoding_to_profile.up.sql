@@ -0,0 +1,4 @@
+-- Add up migration script here
+
+ALTER TABLE profile
+ADD COLUMN encoding varchar(255) not null default 'Auto';
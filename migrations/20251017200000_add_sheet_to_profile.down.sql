@@ -0,0 +1,4 @@
+-- Add down migration script here
+
+ALTER TABLE profile
+DROP COLUMN sheet;
@@ -0,0 +1,4 @@
+-- Add up migration script here
+
+ALTER TABLE data_import
+ADD COLUMN balances text not null default '[]';
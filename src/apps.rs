@@ -18,7 +18,10 @@ use sea_orm::{ConnectOptions, Database};
 use tokio::sync::mpsc;
 use tracing::{info, warn};
 
-use crate::utils::{LoadingScreen, PromiseUtilities};
+use crate::{
+    db::query::data_import_query::hash_legacy_rows,
+    utils::{LoadingScreen, PromiseUtilities},
+};
 
 use self::{
     fileupload::FileUpload, profiles::Profiles, tableview::TableView,
@@ -45,6 +48,7 @@ pub struct State {
 
 pub struct BreadApp {
     state: State,
+    send_dropped_visualizations: mpsc::Sender<egui::DroppedFile>,
     send_dropped_file_upload: mpsc::Sender<egui::DroppedFile>,
     send_dropped_profiles: mpsc::Sender<egui::DroppedFile>,
     sending_files: Vec<ImmediateValuePromise<()>>,
@@ -64,11 +68,13 @@ enum Anchor {
 impl BreadApp {
     pub fn init() -> impl std::future::Future<Output = Self> + Send + 'static {
         async move {
+            let (tx_v, rx_v) = mpsc::channel::<egui::DroppedFile>(20);
             let (tx_f, rx_f) = mpsc::channel::<egui::DroppedFile>(20);
             let (tx_p, rx_p) = mpsc::channel::<egui::DroppedFile>(20);
 
             Self {
-                state: State::init(rx_f, rx_p).await,
+                state: State::init(rx_v, rx_f, rx_p).await,
+                send_dropped_visualizations: tx_v,
                 send_dropped_file_upload: tx_f,
                 send_dropped_profiles: tx_p,
                 sending_files: vec![],
@@ -156,7 +162,7 @@ impl BreadApp {
         use egui::{Align2, Color32, Id, LayerId, Order, TextStyle};
         use std::fmt::Write as _;
 
-        if ![Anchor::FileUpload, Anchor::Profiles]
+        if ![Anchor::Visualizations, Anchor::FileUpload, Anchor::Profiles]
             .contains(&self.state.selected_anchor)
         {
             return;
@@ -233,6 +239,9 @@ impl BreadApp {
         &self,
     ) -> Option<mpsc::Sender<egui::DroppedFile>> {
         match self.state.selected_anchor {
+            Anchor::Visualizations => {
+                Some(self.send_dropped_visualizations.clone())
+            }
             Anchor::FileUpload => Some(self.send_dropped_file_upload.clone()),
             Anchor::Profiles => Some(self.send_dropped_profiles.clone()),
             _ => None,
@@ -274,6 +283,7 @@ impl App for BreadApp {
 
 impl State {
     fn init(
+        rx_v: mpsc::Receiver<egui::DroppedFile>,
         rx_f: mpsc::Receiver<egui::DroppedFile>,
         rx_p: mpsc::Receiver<egui::DroppedFile>,
     ) -> impl std::future::Future<Output = Self> + Send + 'static {
@@ -296,12 +306,13 @@ impl State {
             }
 
             let db = Database::connect(connection_options).await.unwrap();
+            hash_legacy_rows(&db).await.unwrap();
 
             let messenger = Messenger::new(db).await;
 
             let factory = messenger.factory();
             Self {
-                visualizations: Visualizations::init(&factory).into(),
+                visualizations: Visualizations::init(rx_v, &factory).into(),
                 table_view: TableView::init(messenger.factory()).into(),
                 record_view: RecordView::init(messenger.factory()).into(),
                 //linking: Linking::init(messenger.factory()).into(),
@@ -23,6 +23,15 @@ impl App for FileUpload {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("Files:");
+            ui.add_enabled_ui(self.parsing_file.ready_for_parse(), |ui| {
+                if ui
+                    .button("parse all files")
+                    .on_hover_text(TOOL_TIP_PARSE_FILES)
+                    .clicked()
+                {
+                    self.parse_files();
+                }
+            });
             self.files_to_parse
                 .files_to_parse_list(&mut self.parsing_file, ui);
             self.parsed_records.ui_update(&mut self.parsing_file, ui);
@@ -45,13 +54,19 @@ impl FileUpload {
         }
     }
 
-    pub fn parse_files(&mut self) {}
+    pub fn parse_files(&mut self) {
+        let files = self.files_to_parse.take_batch();
+        if !files.is_empty() {
+            self.parsing_file.insert_batch(files);
+        }
+    }
 }
 
 enum ParsingFileState {
     Empty,
     Parsing,
     NewFile(Box<FileToParse>),
+    NewBatch(Vec<FileToParse>),
 }
 
 impl ParsingFileState {
@@ -63,6 +78,10 @@ impl ParsingFileState {
         matches!(self, ParsingFileState::NewFile(_))
     }
 
+    pub fn has_new_batch(&self) -> bool {
+        matches!(self, ParsingFileState::NewBatch(_))
+    }
+
     pub fn insert(&mut self, file: FileToParse) {
         assert!(matches!(self, ParsingFileState::Empty));
         let _ = mem::replace(self, ParsingFileState::NewFile(Box::new(file)));
@@ -77,10 +96,25 @@ impl ParsingFileState {
         *boxed
     }
 
+    pub fn insert_batch(&mut self, files: Vec<FileToParse>) {
+        assert!(matches!(self, ParsingFileState::Empty));
+        let _ = mem::replace(self, ParsingFileState::NewBatch(files));
+    }
+
+    pub fn start_batch(&mut self) -> Vec<FileToParse> {
+        let ParsingFileState::NewBatch(files) =
+            mem::replace(self, ParsingFileState::Parsing)
+        else {
+            unreachable!()
+        };
+        files
+    }
+
     pub fn finished_parsing(&mut self) {
         let _ = mem::replace(self, ParsingFileState::Empty);
     }
 }
 
 const TOOL_TIP_PARSE_FILES: &str = r#"Parse all the files for which you have selected a profile. 
-Will not save them yet, for that click 'Save parsed Data'."#;
+Overlaps with saved imports and between the files are left out. Will not save
+them yet, for that click 'save all'."#;
@@ -1,26 +1,45 @@
 mod margin_cutoff;
 
-use std::fs;
-
+use chrono::{DateTime, Local};
 use egui::{ComboBox, DroppedFile, Grid, Ui};
 use hermes::{
     container::{data::ImplData, manual},
     factory::Factory,
 };
+use itertools::Itertools;
+use lazy_async_promise::ImmediateValuePromise;
 use margin_cutoff::{margin_cutoff, CutOffMargins};
 use num_traits::Zero;
+use std::{collections::HashMap, sync::Arc};
 use tokio::sync::mpsc;
 use tracing::info;
 use uuid::Uuid;
 
-use crate::{db::query::profile_query::ProfileQuery, model::profiles::Profile};
+use crate::{
+    db::query::{
+        data_import_query::DataImportQuery, profile_query::ProfileQuery,
+    },
+    model::{
+        data_import::DataImport,
+        profiles::{
+            detection::Detection, encoding::FileEncoding, error::ProfileError,
+            Profile, ProfileUuid,
+        },
+    },
+    utils::PromiseUtilities,
+};
 
 use super::ParsingFileState;
 
 pub(super) struct FilesToParse {
     reciver: mpsc::Receiver<DroppedFile>,
     profiles: manual::Container<Profile>,
+    /// Every version of every profile, imports keep the version they used.
+    known_profiles: manual::Container<Profile>,
+    imports: manual::Container<DataImport>,
     files: Vec<FileToParse>,
+    /// Detections still reading their file, by the uuid of the file.
+    detecting: HashMap<Uuid, ImmediateValuePromise<Detection>>,
 }
 
 impl FilesToParse {
@@ -29,14 +48,21 @@ impl FilesToParse {
         factory: &Factory,
     ) -> impl std::future::Future<Output = Self> + Send + 'static {
         let mut profiles = factory.builder().file(file!()).manual();
+        let mut known_profiles = factory.builder().file(file!()).manual();
+        let mut imports = factory.builder().file(file!()).manual();
 
         async move {
             profiles.stored_query(ProfileQuery::all_active);
+            known_profiles.stored_query(ProfileQuery::all);
+            imports.stored_query(DataImportQuery::all);
 
             Self {
                 reciver,
                 profiles,
+                known_profiles,
+                imports,
                 files: vec![],
+                detecting: HashMap::new(),
             }
         }
     }
@@ -47,7 +73,11 @@ impl FilesToParse {
         ui: &mut Ui,
     ) {
         self.profiles.state_update(true);
+        self.known_profiles.state_update(true);
+        self.imports.state_update(true);
         self.recive_files();
+        self.detect_profiles();
+        self.collect_detections();
 
         if !self.files.is_empty() {
             egui::Grid::new("uploaded files table").show(ui, |ui| {
@@ -71,6 +101,8 @@ impl FilesToParse {
                     Self::parse_and_remove_button(
                         file_to_parse,
                         parsing_file,
+                        self.imports.data(),
+                        self.known_profiles.data(),
                         ui,
                     )
                 });
@@ -98,26 +130,54 @@ impl FilesToParse {
         profiles: &[Profile],
         ui: &mut Ui,
     ) {
-        ComboBox::from_id_salt(format!("select_profile_{:?}", file.uuid))
-            .selected_text({
-                file.profile
-                    .clone()
-                    .map_or(String::from("select profile"), |p| p.name)
-            })
-            .show_ui(ui, |ui| {
-                for profile in profiles.iter() {
-                    ui.selectable_value(
-                        &mut file.profile,
-                        Some(profile.clone()),
-                        profile.name.clone(),
+        ui.horizontal(|ui| {
+            ComboBox::from_id_salt(format!("select_profile_{:?}", file.uuid))
+                .selected_text({
+                    file.profile
+                        .clone()
+                        .map_or(String::from("select profile"), |p| p.name)
+                })
+                .show_ui(ui, |ui| {
+                    for profile in profiles.iter() {
+                        ui.selectable_value(
+                            &mut file.profile,
+                            Some(profile.clone()),
+                            profile.name.clone(),
+                        );
+                    }
+                });
+            if let Some(detection) = &file.detection {
+                let label = ui.weak(detection.to_string());
+                if let Detection::Ambiguous(candidates) = detection {
+                    label.on_hover_text(
+                        candidates
+                            .iter()
+                            .filter_map(|(uuid, score)| {
+                                profiles
+                                    .iter()
+                                    .find(|profile| profile.uuid == *uuid)
+                                    .map(|profile| {
+                                        format!(
+                                            "{} ({:.0}%)",
+                                            profile.name,
+                                            score * 100.
+                                        )
+                                    })
+                            })
+                            .join("\n"),
                     );
                 }
-            });
+            }
+        });
     }
 
+    /// A file that was imported before is only parsed after a second click,
+    /// before that no overlaps are searched.
     fn parse_and_remove_button(
         file_to_parse: &mut FileToParse,
         parsing_file: &mut ParsingFileState,
+        imports: &[DataImport],
+        profiles: &[Profile],
         ui: &mut Ui,
     ) -> bool {
         let mut to_remove = true;
@@ -127,19 +187,120 @@ impl FilesToParse {
         }
 
         ui.add_enabled_ui(
-            parsing_file.ready_for_parse() && file_to_parse.profile.is_some(),
+            parsing_file.ready_for_parse()
+                && matches!(file_to_parse.resolved_profile, Some(Ok(_))),
             |ui| {
-                if ui.button("parse file").clicked() {
-                    parsing_file.insert(file_to_parse.clone());
+                let confirmed = file_to_parse.same_file.is_some();
+                let label = if confirmed {
+                    "parse anyway"
+                } else {
+                    "parse file"
+                };
+                if ui.button(label).clicked() {
+                    if !confirmed {
+                        file_to_parse.same_file =
+                            SameFile::find(file_to_parse, imports, profiles);
+                    }
+                    if !confirmed && file_to_parse.same_file.is_some() {
+                        return;
+                    }
+                    let mut to_parse = file_to_parse.clone();
+                    to_parse.profile =
+                        to_parse.resolved_profile.take().and_then(Result::ok);
+                    parsing_file.insert(to_parse);
                     to_remove = false;
                 }
             },
         );
+        if let Some(Err(err)) = &file_to_parse.resolved_profile {
+            ui.colored_label(ui.visuals().error_fg_color, err.to_string());
+            if let ProfileError::Headers(_) = err {
+                ui.label("bind the profile to the headers of this file again");
+            }
+        }
+        if let Some(same_file) = &file_to_parse.same_file {
+            ui.colored_label(
+                ui.visuals().warn_fg_color,
+                format!(
+                    "this exact file was already imported on {} with profile {}",
+                    same_file.imported.format("%d.%m.%Y %H:%M"),
+                    same_file.profile
+                ),
+            );
+        }
 
         ui.end_row();
         to_remove
     }
 
+    /// Takes every file whose selected profile fits it, they are parsed
+    /// together as one batch.
+    pub(super) fn take_batch(&mut self) -> Vec<FileToParse> {
+        self.files
+            .extract_if(.., |file| matches!(file.resolved_profile, Some(Ok(_))))
+            .map(|mut file| {
+                file.profile =
+                    file.resolved_profile.take().and_then(Result::ok);
+                file
+            })
+            .collect()
+    }
+
+    /// Scores the active profiles against every new file, the files are
+    /// read in the background.
+    fn detect_profiles(&mut self) {
+        if self.profiles.data().is_empty() {
+            return;
+        }
+        for file in self.files.iter() {
+            if file.detection.is_some()
+                || self.detecting.contains_key(&file.uuid)
+            {
+                continue;
+            }
+            let profiles = Arc::clone(self.profiles.data());
+            let path = file.file.path.clone();
+            let future = async move {
+                path.map_or(Detection::NoMatch, |path| {
+                    Detection::detect(&profiles, &path)
+                })
+            };
+            self.detecting.insert(file.uuid, future.into());
+        }
+    }
+
+    /// Takes the finished detections and preselects the best profile if it
+    /// is a clear match.
+    fn collect_detections(&mut self) {
+        self.detecting
+            .retain(|uuid, _| self.files.iter().any(|file| file.uuid == *uuid));
+        for file in self.files.iter_mut() {
+            let Some(detecting) = self.detecting.get_mut(&file.uuid) else {
+                continue;
+            };
+            if !detecting.poll_and_check_finished() {
+                continue;
+            }
+            let detection = detecting.take_expect();
+            self.detecting.remove(&file.uuid);
+            info!(
+                msg = "Detected profile for dropped file",
+                detection = format!("{detection:?}")
+            );
+            if let (Detection::Match(uuid, _), None) =
+                (&detection, &file.profile)
+            {
+                file.profile = self
+                    .profiles
+                    .data()
+                    .iter()
+                    .find(|profile| profile.uuid == *uuid)
+                    .cloned();
+            }
+            file.detection = Some(detection);
+        }
+    }
+
     pub fn recive_files(&mut self) {
         while let Ok(file) = self.reciver.try_recv() {
             info!(
@@ -156,8 +317,47 @@ pub(super) struct FileToParse {
     uuid: Uuid,
     pub(super) file: DroppedFile,
     pub(super) profile: Option<Profile>,
-    profile_name: Option<String>,
+    /// The profile the margins and headers were last resolved for.
+    resolved_for: Option<ProfileUuid>,
+    /// The selected profile with its header bound columns moved to where
+    /// they are in this file.
+    resolved_profile: Option<Result<Profile, ProfileError>>,
+    detection: Option<Detection>,
     cut_off_margins: CutOffMargins,
+    same_file: Option<SameFile>,
+}
+
+/// An earlier import of exactly the content of a file.
+#[derive(Clone, Debug)]
+struct SameFile {
+    imported: DateTime<Local>,
+    profile: String,
+}
+
+impl SameFile {
+    fn find(
+        file: &FileToParse,
+        imports: &[DataImport],
+        profiles: &[Profile],
+    ) -> Option<Self> {
+        let path = file.file.path.as_ref()?;
+        let content = match &file.profile {
+            Some(profile) => profile.read_file(path),
+            None => FileEncoding::Auto.read(path),
+        }
+        .ok()?;
+        let import = DataImport::find_same_file(imports, &content)?;
+        let profile = profiles
+            .iter()
+            .find(|profile| profile.uuid == import.profile_uuid)
+            .map_or(String::from("an unknown profile"), |profile| {
+                format!("{} (version {})", profile.name, profile.version)
+            });
+        Some(Self {
+            imported: import.datetime_created,
+            profile,
+        })
+    }
 }
 
 impl FileToParse {
@@ -166,30 +366,40 @@ impl FileToParse {
             return;
         };
 
-        let reclac_margins = if let Some(profile_name) = &self.profile_name {
-            !profile.name.eq(profile_name)
-        } else {
-            true
-        };
+        let reclac_margins = self.resolved_for != Some(profile.uuid);
 
         if reclac_margins {
             self.cut_off_margins.clear();
 
-            let _ = self.profile_name.insert(profile.name.clone());
+            let _ = self.resolved_for.insert(profile.uuid);
             let path = self.file.path.as_ref().unwrap();
-            let str = fs::read_to_string(path).unwrap();
-            let len = str.lines().count();
+            let str = match profile.read_file(path) {
+                Ok(str) => str,
+                Err(err) => {
+                    self.resolved_profile = Some(Err(err));
+                    return;
+                }
+            };
+            self.resolved_profile = Some(profile.resolve_headers(&str));
+            let tokenizer = profile.tokenizer();
+            let records = tokenizer.records(&str);
+            let len = records.len();
+            let fields = |record: &String| {
+                tokenizer
+                    .fields(record)
+                    .unwrap_or_else(|_| vec![record.clone()])
+            };
 
-            str.lines().enumerate().for_each(|(index, line)| {
+            records.iter().enumerate().for_each(|(index, record)| {
                 let is_top = profile.is_top_margin(index);
                 #[allow(clippy::nonminimal_bool)]
                 if is_top || (!is_top && profile.is_top_margin(index - 1)) {
-                    self.cut_off_margins.push_top(line, profile.delimiter);
+                    self.cut_off_margins.push_top(fields(record));
                 }
                 let is_btm = profile.is_bottom_margin(index, len);
                 #[allow(clippy::nonminimal_bool)]
                 if is_btm || (!is_btm && profile.is_bottom_margin(index + 1, len)) {
-                    self.cut_off_margins.push_bottom(line, profile.delimiter);
+                    self.cut_off_margins.push_bottom(fields(record));
                 }
             });
         };
@@ -201,9 +411,12 @@ impl From<DroppedFile> for FileToParse {
         Self {
             uuid: Uuid::new_v4(),
             file: value,
-            profile_name: None,
+            resolved_for: None,
+            resolved_profile: None,
             profile: None,
+            detection: None,
             cut_off_margins: CutOffMargins::default(),
+            same_file: None,
         }
     }
 }
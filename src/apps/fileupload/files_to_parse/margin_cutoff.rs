@@ -29,7 +29,8 @@ pub fn margin_cutoff(file: &FileToParse, ui: &mut Ui) {
 
             ui.label(format!(
                 "Cut off margin for top is: {} and for the bottom {}.",
-                profile.margins.0, profile.margins.1
+                profile.effective_margins().0,
+                profile.effective_margins().1
             ));
             ui.label("Note that only the brigther text will be removed not the dark one");
             ui.separator();
@@ -106,13 +107,14 @@ pub struct CutOffMargins {
 
 impl CutOffMargins {
     const MAX_STR_WIDTH: usize = 30;
-    pub fn push_top(&mut self, str: &str, split: char) {
+    pub fn push_top(&mut self, fields: Vec<String>) {
         let top = self.top.get_or_insert_with(Vec::default);
         top.push(
-            str.split(split)
+            fields
+                .into_iter()
                 .map(|str| {
                     if str.len() < Self::MAX_STR_WIDTH {
-                        str.to_owned()
+                        str
                     } else {
                         format!(
                             "{}...",
@@ -123,13 +125,14 @@ impl CutOffMargins {
                 .collect(),
         );
     }
-    pub fn push_bottom(&mut self, str: &str, split: char) {
+    pub fn push_bottom(&mut self, fields: Vec<String>) {
         let bottom = self.bottom.get_or_insert_with(Vec::default);
         bottom.push(
-            str.split(split)
+            fields
+                .into_iter()
                 .map(|str| {
                     if str.len() < Self::MAX_STR_WIDTH {
-                        str.to_owned()
+                        str
                     } else {
                         format!(
                             "{}...",
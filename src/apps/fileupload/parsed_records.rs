@@ -1,10 +1,18 @@
+mod batch_import;
+mod find_duplicates;
 mod find_overlaps;
 mod import_parsing_state;
 mod results_with_overlaps;
 
 use crate::{
     apps::fileupload::parsed_records::{
-        find_overlaps::find_overlaps, results_with_overlaps::ImportOverlap,
+        batch_import::{
+            collect_batch_overlaps, load_batch_overlaps, read_batch,
+            show_batch, BatchState,
+        },
+        find_duplicates::{collect_duplicates, load_duplicates},
+        find_overlaps::{collect_overlaps, find_overlaps, load_overlaps},
+        results_with_overlaps::ImportOverlap,
     },
     components::expense_records::table::TransactsTable,
     db::query::{
@@ -14,14 +22,16 @@ use crate::{
     model::{
         data_import::{row::ImportRow, DataImport},
         group::ModelGroup,
-        transactions::Transaction,
+        profiles::error::RowError,
+        transactions::{properties::TransactionProperties, Transaction},
     },
 };
 use egui::{Grid, ScrollArea, Spinner, Ui};
 use hermes::{
     carrier::execute::ImplExecuteCarrier, container::manual, factory::Factory,
 };
-use import_parsing_state::ImportParsingState;
+use import_parsing_state::{ImportParsingState, ParsedImport};
+use itertools::Itertools;
 use results_with_overlaps::{
     overlap_control_buttons, ImportResultWithOverlap, RowSelectionStatus,
 };
@@ -34,6 +44,7 @@ pub(super) struct ParsedRecords {
     imports: manual::Container<DataImport>,
 
     import_state: ImportParsingState,
+    batch: BatchState,
     selected_overlay: usize,
     transacts_table: TransactsTable,
 }
@@ -44,13 +55,13 @@ impl ParsedRecords {
     ) -> impl std::future::Future<Output = Self> + Send + 'static {
         async move {
             let transactions = factory.builder().file(file!()).manual();
-            let mut imports = factory.builder().file(file!()).manual();
-            imports.stored_query(DataImportQuery::all);
+            let imports = factory.builder().file(file!()).manual();
 
             Self {
                 transactions,
                 imports,
                 import_state: ImportParsingState::None,
+                batch: BatchState::None,
                 selected_overlay: 0,
                 transacts_table: TransactsTable::default(),
             }
@@ -63,14 +74,29 @@ impl ParsedRecords {
         ui: &mut Ui,
     ) {
         self.import_state.try_resolve();
+        self.batch.try_resolve();
 
         self.transactions.state_update(false);
         self.imports.state_update(true);
+        load_overlaps(self);
+        collect_overlaps(self);
+        load_duplicates(self);
+        collect_duplicates(self);
+        load_batch_overlaps(self);
+        collect_batch_overlaps(self);
 
         if parsing_file.has_new_file() && self.import_state.ready_for_new() {
             let file_to_parse = parsing_file.start_parsing();
             find_overlaps(self, file_to_parse);
         }
+        if parsing_file.has_new_batch() && self.batch.ready_for_new() {
+            let files = parsing_file.start_batch();
+            read_batch(self, files);
+        }
+        if !self.batch.ready_for_new() {
+            show_batch(self, parsing_file, ui);
+            return;
+        }
         let mut clear_parse = false;
         let mut save_parse = false;
         ui.vertical_centered(|ui| match &mut self.import_state {
@@ -78,9 +104,20 @@ impl ParsedRecords {
                 ui.label(PARSED_RECORDS_EMPTY_TEXT);
             }
             ImportParsingState::Parsing(_)
-            | ImportParsingState::FindingOverlaps(_) => {
+            | ImportParsingState::ReadingFile(_)
+            | ImportParsingState::FileRead(_)
+            | ImportParsingState::LoadingOverlaps(..)
+            | ImportParsingState::Parsed(_)
+            | ImportParsingState::LoadingDuplicates(..) => {
                 ui.add(Spinner::new());
             }
+            ImportParsingState::Failed(err) => {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("The file cannot be parsed: {err}"),
+                );
+                clear_parse = ui.button("ok").clicked();
+            }
             ImportParsingState::OverlapsFound(overlaps) => {
                 if overlaps.overlaps.is_empty() {
                     ui.vertical(|ui| {
@@ -95,39 +132,95 @@ impl ParsedRecords {
                         show_overlaps(overlaps, &mut self.selected_overlay, ui);
                 }
             }
-            ImportParsingState::Finished(transactions, data_import, groups) => {
+            ImportParsingState::Finished(parsed) => {
                 ui.heading("Final Stats");
                 ui.label(format!(
                     "Num of Transactions: {}",
-                    transactions.len()
+                    parsed.transactions.len()
                 ));
                 ui.label(format!(
                     "Num of Rows in DataImport: {}",
-                    data_import.rows.len()
+                    parsed.import.rows.len()
+                ));
+                ui.label(format!("Number of Groups: {}", parsed.groups.len()));
+                ui.label(format!(
+                    "Num of failed Rows: {}",
+                    parsed.row_errors.len()
+                ));
+                ui.label(format!(
+                    "Num of possible Duplicates: {}",
+                    parsed.duplicates.len()
                 ));
-                ui.label(format!("Number of Groups: {}", groups.len()));
-                if ui.button("save").clicked() {
-                    save_parse = true;
+                ui.label(format!(
+                    "Num of excluded Rows: {}",
+                    parsed.excluded.len()
+                ));
+                ui.horizontal(|ui| {
+                    if ui
+                        .button("save")
+                        .on_hover_text(SAVE_PARSE_TEXT)
+                        .clicked()
+                    {
+                        save_parse = true;
+                    }
+                    if ui
+                        .button("nevermind")
+                        .on_hover_text(NEVERMIND_PARSE_TEXT)
+                        .clicked()
+                    {
+                        clear_parse = true;
+                    }
+                });
+                if !parsed.row_errors.is_empty() {
+                    row_errors_table(&parsed.row_errors, ui);
+                    ui.separator();
+                }
+                if !parsed.duplicates.is_empty() {
+                    duplicates_table(parsed, ui);
+                    ui.separator();
+                }
+                if !parsed.transactions.is_empty() {
+                    parsed_rows_table(parsed, ui);
+                    ui.separator();
                 }
-                self.transacts_table.show(transactions, ui);
+                self.transacts_table.show(&parsed.transactions, ui);
             }
         });
         if save_parse {
             self.save_parse();
+        } else if clear_parse {
+            self.import_state.clear();
+            parsing_file.finished_parsing();
         }
     }
 
     fn save_parse(&mut self) {
-        let ImportParsingState::Finished(transacts, import, groups) =
+        let ImportParsingState::Finished(mut parsed) =
             mem::replace(&mut self.import_state, ImportParsingState::None)
         else {
             unreachable!();
         };
+        parsed.remove_excluded();
+        self.save_imports(vec![parsed]);
+    }
+
+    /// Saves every import with its transactions and groups in one database
+    /// transaction.
+    fn save_imports(&mut self, parsed: Vec<ParsedImport>) {
+        let (transacts, groups, imports) = parsed.into_iter().fold(
+            (vec![], vec![], vec![]),
+            |(mut transacts, mut groups, mut imports), parsed| {
+                transacts.extend(parsed.transactions);
+                groups.extend(parsed.groups);
+                imports.push(parsed.import);
+                (transacts, groups, imports)
+            },
+        );
 
         let tr_q =
             manual::Container::<Transaction>::insert_many_queries(transacts);
         let (diq_1, diq_2, diq_3) =
-            manual::Container::<DataImport>::insert_queries(vec![import]);
+            manual::Container::<DataImport>::insert_queries(imports);
 
         self.transactions.execute_many(|transac| {
             transac.execute(
@@ -206,6 +299,128 @@ fn show_overlaps(
     clear_parse
 }
 
+fn row_errors_table(row_errors: &[RowError], ui: &mut Ui) {
+    ui.heading("Failed Rows");
+    ScrollArea::both()
+        .id_salt("row_errors_scroll")
+        .max_height(200.)
+        .show(ui, |ui| {
+            Grid::new("row_errors_grid").striped(true).show(ui, |ui| {
+                ui.label("row");
+                ui.label("column");
+                ui.label("parser");
+                ui.label("error");
+                ui.label("content");
+                ui.end_row();
+
+                for row_error in row_errors {
+                    ui.label(row_error.row_index.to_string());
+                    ui.label(
+                        row_error
+                            .column
+                            .map(|col| col.to_string())
+                            .unwrap_or_default(),
+                    );
+                    ui.label(row_error.parser.clone().unwrap_or_default());
+                    ui.label(row_error.error.to_string());
+                    ui.label(clamp_str(row_error.row_content.as_str()));
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+fn duplicates_table(parsed: &mut ParsedImport, ui: &mut Ui) {
+    ui.heading("Possible Duplicates")
+        .on_hover_text(DUPLICATES_TEXT);
+    ui.horizontal(|ui| {
+        if ui.button("include all").clicked() {
+            parsed.duplicates.iter_mut().for_each(|d| d.include = true);
+        }
+        if ui.button("exclude all").clicked() {
+            parsed.duplicates.iter_mut().for_each(|d| d.include = false);
+        }
+    });
+    ScrollArea::both()
+        .id_salt("duplicates_scroll")
+        .max_height(200.)
+        .show(ui, |ui| {
+            Grid::new("duplicates_grid").striped(true).show(ui, |ui| {
+                ui.label("Existing Records:");
+                ui.label("|");
+                ui.label("");
+                ui.label("New Records:");
+                ui.label("similarity");
+                ui.end_row();
+
+                for duplicate in parsed.duplicates.iter_mut() {
+                    ui.label(transaction_summary(&duplicate.existing));
+                    ui.label("|");
+                    ui.checkbox(&mut duplicate.include, "")
+                        .on_hover_text(DUPLICATE_CHECKBOX_TEXT);
+                    ui.label(transaction_summary(
+                        &parsed.transactions[duplicate.new],
+                    ));
+                    ui.label(format!("{:.0}%", duplicate.similarity * 100.));
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+fn parsed_rows_table(parsed: &mut ParsedImport, ui: &mut Ui) {
+    ui.heading("Parsed Rows");
+    ui.horizontal(|ui| {
+        if ui.button("include all").clicked() {
+            parsed.excluded.clear();
+        }
+        if ui.button("exclude all").clicked() {
+            parsed.excluded =
+                parsed.transactions.iter().map(|trx| trx.uuid).collect();
+        }
+    });
+    ScrollArea::both()
+        .id_salt("parsed_rows_scroll")
+        .max_height(200.)
+        .show(ui, |ui| {
+            Grid::new("parsed_rows_grid").striped(true).show(ui, |ui| {
+                for transaction in &parsed.transactions {
+                    let mut include =
+                        !parsed.excluded.contains(&transaction.uuid);
+                    if ui
+                        .checkbox(&mut include, "")
+                        .on_hover_text(PARSED_ROW_CHECKBOX_TEXT)
+                        .changed()
+                    {
+                        match include {
+                            true => parsed.excluded.remove(&transaction.uuid),
+                            false => parsed.excluded.insert(transaction.uuid),
+                        };
+                    }
+                    ui.label(transaction_summary(transaction));
+                    ui.end_row();
+                }
+            });
+        });
+}
+
+fn transaction_summary(transaction: &Transaction) -> String {
+    let texts = transaction
+        .properties
+        .iter()
+        .filter_map(|property| match property {
+            TransactionProperties::Text(text) => Some(text.content.as_str()),
+            _ => None,
+        })
+        .join(" ");
+    format!(
+        "{} {} {}",
+        transaction.datetime().format("%d.%m.%Y"),
+        transaction.amount_text(),
+        clamp_str(&texts)
+    )
+}
+
 fn header_row(ui: &mut Ui) {
     ui.label("");
     ui.label("Existing Records: ");
@@ -312,6 +527,34 @@ Drop in some files, select a profile and then click on 'Parse Files' to preview
 before clicking on 'Save parsed Data' to save them.
 "#;
 
+const SAVE_PARSE_TEXT: &str = r#"
+Saves all of the successfully parsed rows that are not excluded. Excluded rows
+and rows that failed to parse are kept in the DataImport but will not create
+any transactions.
+"#;
+
+const PARSED_ROW_CHECKBOX_TEXT: &str = r#"
+Uncheck it to leave this transaction out of the import, its row is still kept
+in the DataImport.
+"#;
+
+const NEVERMIND_PARSE_TEXT: &str = r#"
+Throws away this parse. Use this if too many rows failed and the profile needs
+to be fixed first.
+"#;
+
+const DUPLICATES_TEXT: &str = r#"
+Parsed transactions with the same amount and currency as a saved transaction
+of this origin, a date at most a few days apart and a similar description.
+Their rows differ from the saved rows, e.g. in a balance column or the
+formatting, so they are no overlap.
+"#;
+
+const DUPLICATE_CHECKBOX_TEXT: &str = r#"
+Check it to save this transaction anyway. Unchecked duplicates are left out,
+their rows are still kept in the DataImport.
+"#;
+
 const OVERLAP_CECKBOX_TEXT: &str = r#"
 With this checkbox you can decide if this row will be parsed or not. Check it
 to include it in the parse, Uncheck it to exclude it.
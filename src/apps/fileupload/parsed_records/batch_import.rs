@@ -0,0 +1,365 @@
+use std::{collections::HashSet, mem};
+
+use egui::{Grid, ScrollArea, Spinner, Ui};
+use itertools::Itertools;
+use lazy_async_promise::ImmediateValuePromise;
+
+use crate::{
+    apps::fileupload::{FileToParse, ParsingFileState},
+    db::query::data_import_query::DataImportQuery,
+    model::{data_import::DataImport, profiles::error::ProfileError},
+    utils::PromiseUtilities,
+};
+
+use super::{
+    find_overlaps::read_file,
+    import_parsing_state::{parse_rows, ParsedImport},
+    results_with_overlaps::ImportResultWithOverlap,
+    ParsedRecords,
+};
+
+/// Several files parsed side by side. Overlaps are not resolved row by row
+/// like for a single file, rows that are already saved or that are in a
+/// file earlier in the batch are left out unless a file keeps them. Files
+/// that cannot be read are reported and left out of the batch.
+pub enum BatchState {
+    None,
+    Reading(Vec<ImmediateValuePromise<ReadFile>>),
+    Read(Vec<ReadFile>),
+    LoadingOverlaps(
+        Vec<ImportResultWithOverlap>,
+        ImmediateValuePromise<Result<Vec<DataImport>, String>>,
+        Vec<ProfileError>,
+    ),
+    OverlapsFound(Vec<BatchFile>, Vec<ProfileError>),
+    Parsing(Vec<ImmediateValuePromise<ParsedImport>>),
+    Finished(Vec<ParsedImport>),
+}
+
+type ReadFile = Result<ImportResultWithOverlap, ProfileError>;
+
+pub struct BatchFile {
+    result: ImportResultWithOverlap,
+    /// Rows that are already saved with an earlier import.
+    stored_overlaps: Vec<usize>,
+    /// Rows that are also in a file earlier in the batch.
+    batch_overlaps: Vec<usize>,
+    skip_overlaps: bool,
+}
+
+impl BatchFile {
+    fn into_parsed(self) -> ImmediateValuePromise<ParsedImport> {
+        let Self {
+            mut result,
+            stored_overlaps,
+            batch_overlaps,
+            skip_overlaps,
+        } = self;
+        if skip_overlaps {
+            for index in stored_overlaps.into_iter().chain(batch_overlaps) {
+                result.rows[index].0.set(false);
+            }
+        }
+        let future = async move { parse_rows(result) };
+        future.into()
+    }
+}
+
+impl BatchState {
+    pub fn ready_for_new(&self) -> bool {
+        matches!(self, BatchState::None)
+    }
+
+    pub fn try_resolve(&mut self) {
+        if let Self::Reading(reading) = self {
+            reading
+                .iter_mut()
+                .all(PromiseUtilities::poll_and_check_finished)
+                .then(|| {
+                    reading.iter_mut().map(|p| p.take_expect()).collect_vec()
+                })
+        } else {
+            None
+        }
+        .map(|value| mem::replace(self, Self::Read(value)));
+
+        if let Self::Parsing(parsing) = self {
+            parsing
+                .iter_mut()
+                .all(PromiseUtilities::poll_and_check_finished)
+                .then(|| {
+                    parsing.iter_mut().map(|p| p.take_expect()).collect_vec()
+                })
+        } else {
+            None
+        }
+        .map(|value| mem::replace(self, Self::Finished(value)));
+    }
+
+    pub fn clear(&mut self) {
+        let _ = mem::replace(self, BatchState::None);
+    }
+}
+
+pub fn read_batch(view: &mut ParsedRecords, files: Vec<FileToParse>) {
+    assert!(view.batch.ready_for_new());
+    let reading = files.into_iter().map(read_file).collect_vec();
+    let _ = mem::replace(&mut view.batch, BatchState::Reading(reading));
+}
+
+/// One query for the stored overlaps of every file in the batch.
+pub fn load_batch_overlaps(view: &mut ParsedRecords) {
+    let BatchState::Read(_) = &view.batch else {
+        return;
+    };
+    let BatchState::Read(read) =
+        mem::replace(&mut view.batch, BatchState::None)
+    else {
+        unreachable!()
+    };
+    let (results, failed): (Vec<_>, Vec<_>) =
+        read.into_iter().partition_result();
+
+    let hashes = results
+        .iter()
+        .flat_map(|result| result.rows.iter())
+        .filter(|(status, _)| status.include.is_some())
+        .filter_map(|(_, row)| row.content_hash.clone())
+        .unique()
+        .collect_vec();
+    let loading = view.imports.overlapping(hashes);
+    let _ = mem::replace(
+        &mut view.batch,
+        BatchState::LoadingOverlaps(results, loading, failed),
+    );
+}
+
+pub fn collect_batch_overlaps(view: &mut ParsedRecords) {
+    let BatchState::LoadingOverlaps(_, loading, _) = &mut view.batch else {
+        return;
+    };
+    if !loading.poll_and_check_finished() {
+        return;
+    }
+    let stored = loading.take_expect().map(|imports| {
+        imports
+            .iter()
+            .flat_map(|import| import.rows.iter())
+            .filter_map(|row| row.content_hash.clone())
+            .collect::<HashSet<_>>()
+    });
+
+    let BatchState::LoadingOverlaps(results, _, mut failed) =
+        mem::replace(&mut view.batch, BatchState::None)
+    else {
+        unreachable!()
+    };
+    let stored = match stored {
+        Ok(stored) => stored,
+        Err(err) => {
+            // without the saved rows no file of the batch can be checked
+            failed.push(ProfileError::database(err));
+            let _ = mem::replace(
+                &mut view.batch,
+                BatchState::OverlapsFound(vec![], failed),
+            );
+            return;
+        }
+    };
+    // Rows repeated within one file are separate bookings, so a file only
+    // overlaps with the files before it.
+    let mut earlier = HashSet::new();
+    let files = results
+        .into_iter()
+        .map(|result| {
+            let mut stored_overlaps = vec![];
+            let mut batch_overlaps = vec![];
+            let mut hashes = vec![];
+            for (index, (status, row)) in result.rows.iter().enumerate() {
+                let Some(hash) = row.content_hash.as_ref() else {
+                    continue;
+                };
+                if status.include.is_none() {
+                    continue;
+                }
+                if stored.contains(hash) {
+                    stored_overlaps.push(index);
+                } else if earlier.contains(hash) {
+                    batch_overlaps.push(index);
+                }
+                hashes.push(hash.clone());
+            }
+            earlier.extend(hashes);
+            BatchFile {
+                result,
+                stored_overlaps,
+                batch_overlaps,
+                skip_overlaps: true,
+            }
+        })
+        .collect_vec();
+    let _ =
+        mem::replace(&mut view.batch, BatchState::OverlapsFound(files, failed));
+}
+
+pub fn show_batch(
+    view: &mut ParsedRecords,
+    parsing_file: &mut ParsingFileState,
+    ui: &mut Ui,
+) {
+    let mut start_parse = false;
+    let mut save = false;
+    let mut clear = false;
+    ui.vertical_centered(|ui| match &mut view.batch {
+        BatchState::None => {}
+        BatchState::Reading(_)
+        | BatchState::Read(_)
+        | BatchState::LoadingOverlaps(..)
+        | BatchState::Parsing(_) => {
+            ui.add(Spinner::new());
+        }
+        BatchState::OverlapsFound(files, failed) => {
+            ui.heading(format!("Batch of {} files", files.len()));
+            for err in failed.iter() {
+                ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("Left out of the batch: {err}"),
+                );
+            }
+            overlaps_grid(files, ui);
+            ui.horizontal(|ui| {
+                ui.add_enabled_ui(!files.is_empty(), |ui| {
+                    start_parse = ui
+                        .button("parse batch")
+                        .on_hover_text(PARSE_BATCH_TEXT)
+                        .clicked();
+                });
+                clear = ui.button("nevermind").clicked();
+            });
+        }
+        BatchState::Finished(parsed) => {
+            ui.heading("Final Stats of the Batch");
+            summary_grid(parsed, ui);
+            ui.horizontal(|ui| {
+                save = ui
+                    .button("save all")
+                    .on_hover_text(SAVE_BATCH_TEXT)
+                    .clicked();
+                clear = ui.button("nevermind").clicked();
+            });
+        }
+    });
+
+    if start_parse {
+        let BatchState::OverlapsFound(files, _) =
+            mem::replace(&mut view.batch, BatchState::None)
+        else {
+            unreachable!()
+        };
+        let parsing = files.into_iter().map(BatchFile::into_parsed).collect();
+        let _ = mem::replace(&mut view.batch, BatchState::Parsing(parsing));
+    } else if save {
+        let BatchState::Finished(parsed) =
+            mem::replace(&mut view.batch, BatchState::None)
+        else {
+            unreachable!()
+        };
+        view.save_imports(parsed);
+        parsing_file.finished_parsing();
+    } else if clear {
+        view.batch.clear();
+        parsing_file.finished_parsing();
+    }
+}
+
+fn overlaps_grid(files: &mut [BatchFile], ui: &mut Ui) {
+    ScrollArea::both()
+        .id_salt("batch_overlaps_scroll")
+        .max_height(300.)
+        .show(ui, |ui| {
+            Grid::new("batch_overlaps_grid")
+                .striped(true)
+                .show(ui, |ui| {
+                    ui.label("file");
+                    ui.label("profile");
+                    ui.label("rows");
+                    ui.label("already saved");
+                    ui.label("in earlier file");
+                    ui.label("skip overlapping");
+                    ui.end_row();
+
+                    for file in files {
+                        ui.label(
+                            file.result.import.file_path.display().to_string(),
+                        );
+                        ui.label(file.result.profile.name.clone());
+                        ui.label(file.result.rows.len().to_string());
+                        ui.label(file.stored_overlaps.len().to_string());
+                        ui.label(file.batch_overlaps.len().to_string());
+                        ui.checkbox(&mut file.skip_overlaps, "")
+                            .on_hover_text(SKIP_OVERLAPS_TEXT);
+                        ui.end_row();
+                    }
+                });
+        });
+}
+
+fn summary_grid(parsed: &[ParsedImport], ui: &mut Ui) {
+    let row = |ui: &mut Ui, name: String, values: [usize; 4]| {
+        ui.label(name);
+        for value in values {
+            ui.label(value.to_string());
+        }
+        ui.end_row();
+    };
+    let values = |parsed: &ParsedImport| {
+        [
+            parsed.import.rows.len(),
+            parsed.transactions.len(),
+            parsed.groups.len(),
+            parsed.row_errors.len(),
+        ]
+    };
+
+    Grid::new("batch_summary_grid")
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("file");
+            ui.label("rows");
+            ui.label("transactions");
+            ui.label("groups");
+            ui.label("failed rows");
+            ui.end_row();
+
+            for parsed in parsed {
+                row(
+                    ui,
+                    parsed.import.file_path.display().to_string(),
+                    values(parsed),
+                );
+            }
+            let total =
+                parsed.iter().map(values).fold([0; 4], |mut sum, values| {
+                    sum.iter_mut()
+                        .zip(values)
+                        .for_each(|(sum, value)| *sum += value);
+                    sum
+                });
+            row(ui, String::from("total"), total);
+        });
+}
+
+const PARSE_BATCH_TEXT: &str = r#"
+Parses every file of the batch at the same time. Nothing is saved until
+'save all' is clicked.
+"#;
+
+const SKIP_OVERLAPS_TEXT: &str = r#"
+Leaves out the rows that are already saved with an earlier import or that are
+in a file further up in this list. Uncheck it to parse every row of the file.
+"#;
+
+const SAVE_BATCH_TEXT: &str = r#"
+Saves the imports of all files in one database transaction, either all of
+them are saved or none.
+"#;
@@ -0,0 +1,43 @@
+use crate::{
+    db::query::transaction_query::TransactionQuery,
+    model::{
+        profiles::error::ProfileError,
+        transactions::duplicates::SemanticDuplicate,
+    },
+    utils::PromiseUtilities,
+};
+
+use super::{import_parsing_state::ImportParsingState, ParsedRecords};
+
+/// Loads the saved transactions of the origin of a finished parse. Rows
+/// that only differ in a balance column or formatting slip through the
+/// overlaps, their transactions are caught by [`collect_duplicates`].
+pub fn load_duplicates(view: &mut ParsedRecords) {
+    let ImportParsingState::Parsed(parsed) = &view.import_state else {
+        return;
+    };
+    let loading = view.transactions.of_origin(parsed.origin);
+    view.import_state.wait_for_duplicates(loading);
+}
+
+pub fn collect_duplicates(view: &mut ParsedRecords) {
+    let ImportParsingState::LoadingDuplicates(parsed, loading) =
+        &mut view.import_state
+    else {
+        return;
+    };
+    if !loading.poll_and_check_finished() {
+        return;
+    }
+
+    let existing = match loading.take_expect() {
+        Ok(existing) => existing,
+        Err(err) => {
+            view.import_state.fail(ProfileError::database(err));
+            return;
+        }
+    };
+    parsed.duplicates =
+        SemanticDuplicate::find(&parsed.transactions, &existing);
+    view.import_state.duplicates_found();
+}
@@ -1,6 +1,5 @@
-use std::sync::Arc;
+use std::collections::HashSet;
 
-use hermes::container::data::ImplData;
 use itertools::Itertools;
 use lazy_async_promise::ImmediateValuePromise;
 
@@ -11,12 +10,27 @@ use crate::{
         },
         FileToParse,
     },
-    model::data_import::{row::ImportRow, DataImport},
+    db::query::data_import_query::DataImportQuery,
+    model::{
+        data_import::{row::ImportRow, DataImport},
+        profiles::error::ProfileError,
+    },
+    utils::PromiseUtilities,
 };
 
-use super::ParsedRecords;
+use super::{import_parsing_state::ImportParsingState, ParsedRecords};
 
+/// Reads and tokenizes the file, the overlaps are looked up by
+/// [`load_overlaps`] once the rows and their hashes are known.
 pub fn find_overlaps(view: &mut ParsedRecords, file: FileToParse) {
+    view.import_state.set_reading(read_file(file));
+}
+
+/// Decodes the file in the encoding of its profile, a file that cannot be
+/// decoded is an error instead of a panic of the task.
+pub fn read_file(
+    file: FileToParse,
+) -> ImmediateValuePromise<Result<ImportResultWithOverlap, ProfileError>> {
     let FileToParse {
         file,
         profile: Some(profile),
@@ -29,56 +43,99 @@ pub fn find_overlaps(view: &mut ParsedRecords, file: FileToParse) {
             );
     };
 
-    let all_imports = Arc::clone(view.imports.data());
-
-    let future = ImmediateValuePromise::new(async move {
+    ImmediateValuePromise::new(async move {
         let file = file.path.unwrap();
 
-        let file_str = std::fs::read_to_string(&file).unwrap();
-        let import_rows = file_str
-            .lines()
+        let file_str = match profile.read_file(&file) {
+            Ok(file_str) => file_str,
+            Err(err) => return Ok(Err(err)),
+        };
+        let tokenizer = profile.tokenizer();
+        let import_rows = tokenizer
+            .records(&file_str)
+            .into_iter()
             .enumerate()
-            .map(|(index, line)| ImportRow::init(line.to_string(), index))
-            .collect_vec();
-
-        let new_import = DataImport::init(profile.uuid, &file_str, file);
-        assert!(!import_rows.is_empty());
-
-        let overlaps = all_imports
-            .iter()
-            .filter_map(|import| {
-                let mut first_match = None;
-                let sorted_counts = import.rows.iter().counts_by(|row| {
-                    for (index, new_row) in import_rows.iter().enumerate() {
-                        if !profile.is_margin(index, import_rows.len())
-                            && row.row_content.eq(&new_row.row_content)
-                        {
-                            if first_match.is_none() {
-                                first_match = Some(index);
-                            }
-                            return true;
-                        }
-                    }
-                    false
-                });
-
-                match sorted_counts.get(&true).unwrap_or(&0) {
-                    0 => None,
-                    _ => {
-                        let mut import = import.clone();
-                        import.sort_by_index();
-                        Some(ImportOverlap::new(import, first_match.unwrap()))
-                    }
-                }
+            .map(|(index, record)| {
+                ImportRow::init(record, index)
+                    .with_hash(profile.origin.uuid, &tokenizer)
             })
             .collect_vec();
 
-        Ok(ImportResultWithOverlap::new(
+        if import_rows.is_empty() {
+            return Ok(Err(ProfileError::file(&file, "it contains no rows")));
+        }
+        let balances = match tokenizer.balances(&file_str) {
+            Ok(balances) => balances,
+            Err(err) => return Ok(Err(err)),
+        };
+        let new_import = DataImport::init(profile.uuid, &file_str, file)
+            .with_balances(balances);
+
+        Ok(Ok(ImportResultWithOverlap::new(
             new_import,
             import_rows,
             profile,
-            overlaps,
-        ))
-    });
-    view.import_state.set_overlaps(future);
+            vec![],
+        )))
+    })
+}
+
+/// Asks the database for the imports of the same origin that share a
+/// hash with the rows of the read file.
+pub fn load_overlaps(view: &mut ParsedRecords) {
+    let ImportParsingState::FileRead(result) = &view.import_state else {
+        return;
+    };
+    let hashes = result
+        .rows
+        .iter()
+        .filter(|(status, _)| status.include.is_some())
+        .filter_map(|(_, row)| row.content_hash.clone())
+        .unique()
+        .collect_vec();
+    let loading = view.imports.overlapping(hashes);
+    view.import_state.wait_for_overlaps(loading);
+}
+
+/// Builds the overlaps as soon as the query of [`load_overlaps`] returned
+/// its imports, a failed query fails the parse.
+pub fn collect_overlaps(view: &mut ParsedRecords) {
+    let ImportParsingState::LoadingOverlaps(result, loading) =
+        &mut view.import_state
+    else {
+        return;
+    };
+    if !loading.poll_and_check_finished() {
+        return;
+    }
+
+    let imports = match loading.take_expect() {
+        Ok(imports) => imports,
+        Err(err) => {
+            view.import_state.fail(ProfileError::database(err));
+            return;
+        }
+    };
+    result.overlaps = imports
+        .into_iter()
+        .filter_map(|mut import| {
+            let hashes = import
+                .rows
+                .iter()
+                .filter_map(|row| row.content_hash.as_ref())
+                .collect::<HashSet<_>>();
+            let first_match =
+                result.rows.iter().position(|(status, new_row)| {
+                    status.include.is_some()
+                        && new_row
+                            .content_hash
+                            .as_ref()
+                            .is_some_and(|hash| hashes.contains(hash))
+                })?;
+
+            import.sort_by_index();
+            Some(ImportOverlap::new(import, first_match))
+        })
+        .collect_vec();
+    view.import_state.overlaps_found();
 }
@@ -1,34 +1,122 @@
 use crate::{
     model::{
-        data_import::DataImport, group::Group, profiles::ParseResult,
-        transactions::Transaction,
+        data_import::DataImport,
+        group::Group,
+        origins::OriginUuid,
+        profiles::{
+            error::{ProfileError, RowError},
+            ParseResult,
+        },
+        transactions::{
+            duplicates::SemanticDuplicate, Transaction, TransactionUuid,
+        },
     },
     utils::PromiseUtilities,
 };
 use itertools::Itertools;
 use lazy_async_promise::ImmediateValuePromise;
-use std::mem;
+use std::{collections::HashSet, mem};
 
 use super::ImportResultWithOverlap;
 
 pub enum ImportParsingState {
     None,
-    FindingOverlaps(ImmediateValuePromise<ImportResultWithOverlap>),
+    ReadingFile(
+        ImmediateValuePromise<Result<ImportResultWithOverlap, ProfileError>>,
+    ),
+    FileRead(ImportResultWithOverlap),
+    Failed(ProfileError),
+    /// Waits for the imports the overlap query found.
+    LoadingOverlaps(
+        ImportResultWithOverlap,
+        ImmediateValuePromise<Result<Vec<DataImport>, String>>,
+    ),
     OverlapsFound(ImportResultWithOverlap),
-    Parsing(ImmediateValuePromise<(Vec<Transaction>, DataImport, Vec<Group>)>),
-    Finished(Vec<Transaction>, DataImport, Vec<Group>),
+    Parsing(ImmediateValuePromise<ParsedImport>),
+    Parsed(ParsedImport),
+    /// Waits for the saved transactions of the origin.
+    LoadingDuplicates(
+        ParsedImport,
+        ImmediateValuePromise<Result<Vec<Transaction>, String>>,
+    ),
+    Finished(ParsedImport),
+}
+
+pub struct ParsedImport {
+    pub transactions: Vec<Transaction>,
+    pub import: DataImport,
+    pub groups: Vec<Group>,
+    pub row_errors: Vec<RowError>,
+    pub origin: OriginUuid,
+    pub duplicates: Vec<SemanticDuplicate>,
+    /// Transactions left out of the import by hand.
+    pub excluded: HashSet<TransactionUuid>,
+}
+
+impl ParsedImport {
+    /// Drops the excluded transactions and those of the duplicates that are
+    /// not included. Their rows stay in the import without a group, like the
+    /// rows left out of an overlap.
+    pub fn remove_excluded(&mut self) {
+        let mut excluded = mem::take(&mut self.excluded);
+        excluded.extend(
+            self.duplicates
+                .drain(..)
+                .filter(|duplicate| !duplicate.include)
+                .map(|duplicate| self.transactions[duplicate.new].uuid),
+        );
+        let groups = self
+            .transactions
+            .extract_if(.., |trx| excluded.contains(&trx.uuid))
+            .map(|trx| trx.movement.group_uuid)
+            .collect::<HashSet<_>>();
+
+        self.groups.retain(|group| !groups.contains(&group.uuid));
+        for row in self.import.rows.iter_mut().filter(|row| {
+            row.group_uuid.is_some_and(|group| groups.contains(&group))
+        }) {
+            row.group_uuid = None;
+            row.items.clear();
+        }
+    }
 }
 
 impl ImportParsingState {
     pub fn ready_for_new(&self) -> bool {
         matches!(self, ImportParsingState::None)
     }
-    pub fn set_overlaps(
+    pub fn set_reading(
         &mut self,
-        future: ImmediateValuePromise<ImportResultWithOverlap>,
+        future: ImmediateValuePromise<
+            Result<ImportResultWithOverlap, ProfileError>,
+        >,
     ) {
         assert!(matches!(self, Self::None));
-        let _ = mem::replace(self, ImportParsingState::FindingOverlaps(future));
+        let _ = mem::replace(self, ImportParsingState::ReadingFile(future));
+    }
+
+    pub fn wait_for_overlaps(
+        &mut self,
+        loading: ImmediateValuePromise<Result<Vec<DataImport>, String>>,
+    ) {
+        let ImportParsingState::FileRead(result) =
+            mem::replace(self, ImportParsingState::None)
+        else {
+            unreachable!()
+        };
+        let _ = mem::replace(
+            self,
+            ImportParsingState::LoadingOverlaps(result, loading),
+        );
+    }
+
+    pub fn overlaps_found(&mut self) {
+        let ImportParsingState::LoadingOverlaps(result, _) =
+            mem::replace(self, ImportParsingState::None)
+        else {
+            unreachable!()
+        };
+        let _ = mem::replace(self, ImportParsingState::OverlapsFound(result));
     }
 
     pub fn start_parse(&mut self) {
@@ -37,42 +125,23 @@ impl ImportParsingState {
         else {
             unreachable!()
         };
-        let future = async move {
-            let ImportResultWithOverlap {
-                mut import,
-                profile,
-                mut rows,
-                ..
-            } = overlaps;
-
-            let to_parse_rows = rows
-                .extract_if(.., |t| t.0.is_to_parse())
-                .map(|t| t.1)
-                .collect_vec();
-            import.rows.extend(rows.into_iter().map(|t| t.1));
-
-            let ParseResult {
-                rows,
-                groups,
-                parsed_rows,
-            } = profile.parse_file(to_parse_rows).unwrap();
-
-            import.rows.extend(parsed_rows);
-            (rows, import, groups)
-        };
+        let future = async move { parse_rows(overlaps) };
 
         let _ = mem::replace(self, ImportParsingState::Parsing(future.into()));
     }
-    
+
     pub fn try_resolve(&mut self) {
-        if let Self::FindingOverlaps(finding) = self {
-            finding
+        if let Self::ReadingFile(reading) = self {
+            reading
                 .poll_and_check_finished()
-                .then(|| finding.take_expect())
+                .then(|| reading.take_expect())
         } else {
             None
         }
-        .map(|value| mem::replace(self, Self::OverlapsFound(value)));
+        .map(|value| match value {
+            Ok(value) => mem::replace(self, Self::FileRead(value)),
+            Err(err) => mem::replace(self, Self::Failed(err)),
+        });
 
         if let Self::Parsing(parsing) = self {
             parsing
@@ -81,11 +150,74 @@ impl ImportParsingState {
         } else {
             None
         }
-        .map(|value| {
-            mem::replace(self, Self::Finished(value.0, value.1, value.2))
-        });
+        .map(|value| mem::replace(self, Self::Parsed(value)));
+    }
+
+    pub fn wait_for_duplicates(
+        &mut self,
+        loading: ImmediateValuePromise<Result<Vec<Transaction>, String>>,
+    ) {
+        let ImportParsingState::Parsed(parsed) =
+            mem::replace(self, ImportParsingState::None)
+        else {
+            unreachable!()
+        };
+        let _ = mem::replace(
+            self,
+            ImportParsingState::LoadingDuplicates(parsed, loading),
+        );
+    }
+
+    pub fn duplicates_found(&mut self) {
+        let ImportParsingState::LoadingDuplicates(parsed, _) =
+            mem::replace(self, ImportParsingState::None)
+        else {
+            unreachable!()
+        };
+        let _ = mem::replace(self, ImportParsingState::Finished(parsed));
+    }
+    pub fn fail(&mut self, err: ProfileError) {
+        let _ = mem::replace(self, ImportParsingState::Failed(err));
     }
     pub fn clear(&mut self) {
         let _ = mem::replace(self, ImportParsingState::None);
     }
 }
+
+/// Parses the rows left to parse after the overlaps were resolved, the other
+/// rows are kept in the import without a group.
+pub fn parse_rows(overlaps: ImportResultWithOverlap) -> ParsedImport {
+    let ImportResultWithOverlap {
+        mut import,
+        profile,
+        mut rows,
+        ..
+    } = overlaps;
+
+    let to_parse_rows = rows
+        .extract_if(.., |t| t.0.is_to_parse())
+        .map(|t| t.1)
+        .collect_vec();
+    import.rows.extend(rows.into_iter().map(|t| t.1));
+
+    let ParseResult {
+        rows,
+        groups,
+        parsed_rows,
+        row_errors,
+    } = match to_parse_rows.is_empty() {
+        true => ParseResult::new(vec![], vec![], vec![]),
+        false => profile.parse_file(to_parse_rows),
+    };
+
+    import.rows.extend(parsed_rows);
+    ParsedImport {
+        transactions: rows,
+        import,
+        groups,
+        row_errors,
+        origin: profile.origin.uuid,
+        duplicates: vec![],
+        excluded: HashSet::new(),
+    }
+}
@@ -1,5 +1,8 @@
 mod create_profile;
+mod history;
+mod library;
 mod parser;
+mod sharing;
 
 use eframe::App;
 use egui::{Grid, ScrollArea};
@@ -10,12 +13,21 @@ use hermes::{
 use tokio::sync::mpsc;
 use tracing::info;
 
-use crate::{db::query::profile_query::ProfileQuery, model::profiles::Profile};
+use crate::{
+    db::query::profile_query::ProfileQuery,
+    model::profiles::{portable::PortableFormat, Profile},
+};
 
-use self::create_profile::CreateProfile;
+use self::{
+    create_profile::CreateProfile, history::ProfileHistory,
+    library::ProfileLibrary, sharing::ProfileSharing,
+};
 
 pub struct Profiles {
     create_profile: CreateProfile,
+    history: ProfileHistory,
+    sharing: ProfileSharing,
+    library: ProfileLibrary,
     profiles: manual::Container<Profile>,
 }
 
@@ -29,6 +41,8 @@ impl App for Profiles {
                     ui.heading("Profiles");
                     ui.label(self.profiles.data().len().to_string());
                 });
+                self.sharing.view(ui);
+                self.library.view(ui);
 
                 let mut to_delete = None;
                 let profiles = self.profiles.data();
@@ -39,6 +53,7 @@ impl App for Profiles {
                     Grid::new("all profiles view").show(ui, |ui| {
                         ui.label("uuid");
                         ui.label("name");
+                        ui.label("version");
                         ui.label("top margin");
                         ui.label("btm margin");
                         ui.label("delimiter");
@@ -49,6 +64,7 @@ impl App for Profiles {
                         for profile in profiles.iter() {
                             ui.label(profile.uuid.to_string());
                             ui.label(profile.name.clone());
+                            ui.label(format!("{}", profile.version));
                             ui.label(format!("{}", profile.margins.0));
                             ui.label(format!("{}", profile.margins.1));
                             ui.label(profile.delimiter.to_string());
@@ -59,9 +75,24 @@ impl App for Profiles {
                                 }
                             });
                             ui.group(|ui| {
-                                if ui.button("edit").clicked() {
+                                if ui
+                                    .button("edit")
+                                    .on_hover_text(EDIT_TEXT)
+                                    .clicked()
+                                {
                                     self.create_profile.edit(profile);
                                 }
+                                if ui.button("history").clicked() {
+                                    self.history.select(profile.uuid);
+                                }
+                                if ui.button("export json").clicked() {
+                                    self.sharing
+                                        .export(profile, PortableFormat::Json);
+                                }
+                                if ui.button("export toml").clicked() {
+                                    self.sharing
+                                        .export(profile, PortableFormat::Toml);
+                                }
                                 if ui.button("delete").clicked() {
                                     let _ = to_delete.insert(profile.uuid);
                                 }
@@ -76,6 +107,8 @@ impl App for Profiles {
                     self.profiles.delete(&to_delete_uuid);
                 }
 
+                self.history.view(ui);
+
                 ui.separator();
                 self.create_profile.ui_update(ui);
             });
@@ -92,10 +125,20 @@ impl Profiles {
             let mut profiles =
                 factory.builder().file(file!()).manual();
             profiles.stored_query(ProfileQuery::all_active);
+            // profile files are taken out before the editor sees the drop
+            let (forward, forwarded) = mpsc::channel::<egui::DroppedFile>(20);
             Self {
-                create_profile: CreateProfile::new(reciver, factory),
+                history: ProfileHistory::new(&factory),
+                sharing: ProfileSharing::new(reciver, forward, &factory),
+                library: ProfileLibrary::new(&factory),
+                create_profile: CreateProfile::new(forwarded, factory),
                 profiles,
             }
         }
     }
 }
+
+const EDIT_TEXT: &str = r#"
+Saving an edited profile creates a new version of it. The old version is kept
+for the imports it produced and can be found in the history.
+"#;
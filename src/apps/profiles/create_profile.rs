@@ -4,10 +4,14 @@ mod other_columns;
 
 use std::sync::Arc;
 
-use basics::{default_tags, delimiter, margin_btm, margin_top, name, origin};
+use basics::{
+    default_tags, delimiter, encoding, format, margin_btm, margin_top, name,
+    origin, quote, sheet,
+};
 use egui::Ui;
 use hermes::{container::manual, factory::Factory};
-use main_columns::{datetime_col, expense_col};
+use itertools::Itertools;
+use main_columns::{currency_col, datetime_col, expense_col};
 use other_columns::other_cols;
 use tokio::sync::mpsc;
 
@@ -24,6 +28,9 @@ use crate::{
         origins::Origin,
         profiles::{
             builder::{CreateProfileBuilder, IntermediateProfileState},
+            error::ProfileError,
+            inference::Inference,
+            tokenizer::Tokenizer,
             Profile,
         },
         tags::Tag,
@@ -36,6 +43,8 @@ pub struct CreateProfile {
     preview: ProfilePreview,
     profile_builder: Arc<CreateProfileBuilder>,
     intermediate_profile_state: IntermediateProfileState,
+    /// Guess from the testing file, kept to label the preview columns.
+    inference: Option<Result<Inference, ProfileError>>,
     profiles: manual::Container<Profile>,
     origins: manual::Container<Origin>,
     tags: manual::Container<Tag>,
@@ -61,6 +70,7 @@ impl CreateProfile {
             preview: ProfilePreview::new(reciver),
             profile_builder: Arc::new(CreateProfileBuilder::default()),
             intermediate_profile_state: IntermediateProfileState::default(),
+            inference: None,
             profiles,
             select_origins_state: SelectOriginState::default(),
             select_tags_state: SelectTagsState::default(),
@@ -81,10 +91,25 @@ impl CreateProfile {
             }
         });
         self.show_editing_controls(ui);
-        if ui.button("update builder").clicked() {
-            self.update_builder();
-            self.preview.update_parse_test(&self.profile_builder);
-        }
+        ui.horizontal(|ui| {
+            if ui.button("update builder").clicked() {
+                self.update_builder();
+                self.preview.update_parse_test(&self.profile_builder);
+            }
+            ui.add_enabled_ui(self.preview.testing_file_path().is_some(), |ui| {
+                if ui
+                    .button("infer from testing file")
+                    .on_hover_text("guesses delimiter, margins and columns, the result only needs to be confirmed")
+                    .clicked()
+                {
+                    self.infer_from_testing_file();
+                }
+            });
+            if let Some(Err(err)) = &self.inference {
+                ui.colored_label(ui.visuals().error_fg_color, err.to_string());
+            }
+        });
+        self.header_binding(ui);
 
         const MIN_PREVIEW_HEIGHT: f32 = 200.;
         let preview_height = if ui.available_height() > MIN_PREVIEW_HEIGHT {
@@ -93,14 +118,29 @@ impl CreateProfile {
             MIN_PREVIEW_HEIGHT
         };
         ui.add_sized([ui.available_width(), preview_height], |ui: &mut Ui| {
-            self.preview.profile_preview(ui, &self.profile_builder)
+            self.preview.profile_preview(
+                ui,
+                &self.profile_builder,
+                self.inference.as_ref().and_then(|i| i.as_ref().ok()),
+            )
         });
 
         ui.add_sized([ui.available_width(), 50.], |ui: &mut Ui| {
             ui.vertical_centered_justified(|ui| match self.parse_profile() {
                 Ok(profile) => {
-                    if ui.button("save profile").clicked() {
+                    let label = match profile.previous {
+                        Some(_) => {
+                            format!("save as version {}", profile.version)
+                        }
+                        None => "save profile".to_owned(),
+                    };
+                    if ui.button(label).clicked() {
+                        let is_version = profile.previous.is_some();
                         self.profiles.insert(profile);
+                        // saving again would branch off the same version
+                        if is_version {
+                            self.reset();
+                        }
                     };
                 }
                 Err(()) => {
@@ -119,6 +159,7 @@ impl CreateProfile {
 
     fn reset(&mut self) {
         self.preview.reset();
+        self.inference = None;
         self.profile_builder = Arc::new(CreateProfileBuilder::default());
         self.intermediate_profile_state = IntermediateProfileState::default();
     }
@@ -138,9 +179,17 @@ impl CreateProfile {
             );
         });
         ui.horizontal(|ui| {
-            delimiter(ui, state);
-            margin_top(ui, state);
-            margin_btm(ui, state);
+            format(ui, state);
+            ui.add_enabled_ui(state.format.is_delimited(), |ui| {
+                delimiter(ui, state);
+                quote(ui, state);
+            });
+            encoding(ui, state);
+            sheet(ui, state);
+            ui.add_enabled_ui(!state.format.is_statement(), |ui| {
+                margin_top(ui, state);
+                margin_btm(ui, state);
+            });
         });
         ui.vertical_centered(|ui| {
             default_tags(
@@ -153,8 +202,14 @@ impl CreateProfile {
         ui.add_space(10.);
         ui.separator();
         ui.add_space(10.);
+        if state.format.is_statement() {
+            ui.label(STATEMENT_COLUMNS_TEXT);
+            ui.add_space(10.);
+            return;
+        }
         ui.horizontal(|ui| {
             expense_col(ui, state);
+            currency_col(ui, state);
             datetime_col(ui, state);
         });
         ui.add_space(10.);
@@ -166,6 +221,84 @@ impl CreateProfile {
         ui.add_space(10.);
     }
 
+    /// Binds the columns to the labels of the header row of the testing
+    /// file, which is the last row of the top margin.
+    fn header_binding(&mut self, ui: &mut Ui) {
+        let state = &mut self.intermediate_profile_state;
+        ui.horizontal(|ui| {
+            let header = self
+                .preview
+                .testing_file_path()
+                .and_then(|path| {
+                    state.format.read(&path, state.encoding, &state.sheet).ok()
+                })
+                .filter(|_| state.margin_top > 0)
+                .and_then(|content| {
+                    // only csv is split by the delimiter
+                    let delimiter = state
+                        .delimiter
+                        .chars()
+                        .next()
+                        .or((!state.format.is_delimited()).then_some(';'))?;
+                    let tokenizer =
+                        Tokenizer::new(delimiter, state.quote.chars().next())
+                            .with_format(state.format);
+                    let header = tokenizer
+                        .records(&content)
+                        .into_iter()
+                        .nth(state.margin_top - 1)?;
+                    tokenizer.fields(&header).ok()
+                });
+            ui.add_enabled_ui(header.is_some(), |ui| {
+                if ui
+                    .button("bind columns by header")
+                    .on_hover_text(BIND_HEADERS_TEXT)
+                    .clicked()
+                {
+                    state.headers = header
+                        .into_iter()
+                        .flatten()
+                        .map(|label| label.trim().to_owned())
+                        .enumerate()
+                        .collect();
+                }
+            });
+            if state.headers.is_empty() {
+                ui.label("columns are mapped by position");
+            } else {
+                ui.label(format!(
+                    "columns are bound to the headers: {}",
+                    state
+                        .headers
+                        .iter()
+                        .sorted()
+                        .map(|(pos, label)| format!("{pos} {label}"))
+                        .join(", ")
+                ));
+                if ui.button("map by position").clicked() {
+                    state.headers.clear();
+                }
+            }
+        });
+    }
+
+    fn infer_from_testing_file(&mut self) {
+        let Some(path) = self.preview.testing_file_path() else {
+            return;
+        };
+        let state = &self.intermediate_profile_state;
+        let inference = state
+            .format
+            .read(&path, state.encoding, &state.sheet)
+            .and_then(|content| Inference::infer(&content));
+        if let Ok(inference) = &inference {
+            inference.apply_to(&mut self.intermediate_profile_state);
+            self.update_builder();
+            self.preview.update_parse_test(&self.profile_builder);
+        }
+        self.inference = Some(inference);
+    }
+
     fn update_builder(&mut self) {
         let profile = CreateProfileBuilder::from_inter_state(
             &self.intermediate_profile_state,
@@ -180,3 +313,16 @@ impl CreateProfile {
         (*self.profile_builder).clone().build()
     }
 }
+
+const STATEMENT_COLUMNS_TEXT: &str = r#"
+Statement files need no columns. The amount, currency, booking and value date,
+reference, counterparty, IBAN, end to end id, status, transaction code,
+transaction id, posting text and purpose of every booking are always imported
+the same way. Their files have no margins either.
+"#;
+
+const BIND_HEADERS_TEXT: &str = r#"
+Takes the labels of the last row of the top margin in the testing file. When a
+file is parsed, the columns are looked up by these labels so that inserted or
+reordered columns are still found. Parsing fails if a label is missing.
+"#;
@@ -8,7 +8,12 @@ use crate::{
         tags::tags_dialog::{SelectTagsDialog, SelectTagsState},
     },
     model::{
-        origins::Origin, profiles::builder::IntermediateProfileState, tags::Tag,
+        origins::Origin,
+        profiles::{
+            builder::IntermediateProfileState, encoding::FileEncoding,
+            format::FileFormat,
+        },
+        tags::Tag,
     },
 };
 
@@ -38,6 +43,24 @@ pub(super) fn origin(
     });
 }
 
+pub(super) fn format(
+    ui: &mut Ui,
+    IntermediateProfileState { format, .. }: &mut IntermediateProfileState,
+) {
+    ui.group(|ui| {
+        ui.vertical(|ui| {
+            ui.label("Format").on_hover_text(FORMAT_TEXT);
+            egui::ComboBox::from_id_salt("profile format")
+                .selected_text(format.to_string())
+                .show_ui(ui, |ui| {
+                    for val in FileFormat::values() {
+                        ui.selectable_value(format, val, val.to_string());
+                    }
+                });
+        });
+    });
+}
+
 pub(super) fn delimiter(
     ui: &mut Ui,
     IntermediateProfileState { delimiter, .. }: &mut IntermediateProfileState,
@@ -50,6 +73,50 @@ pub(super) fn delimiter(
     });
 }
 
+pub(super) fn quote(
+    ui: &mut Ui,
+    IntermediateProfileState { quote, .. }: &mut IntermediateProfileState,
+) {
+    ui.group(|ui| {
+        ui.vertical(|ui| {
+            ui.label("Quote");
+            single_char(ui, quote);
+        });
+    });
+}
+
+pub(super) fn encoding(
+    ui: &mut Ui,
+    IntermediateProfileState { encoding, .. }: &mut IntermediateProfileState,
+) {
+    ui.group(|ui| {
+        ui.vertical(|ui| {
+            ui.label("Encoding").on_hover_text(ENCODING_TEXT);
+            egui::ComboBox::from_id_salt("profile encoding")
+                .selected_text(encoding.to_string())
+                .show_ui(ui, |ui| {
+                    for val in FileEncoding::values() {
+                        ui.selectable_value(encoding, val, val.to_string());
+                    }
+                });
+        });
+    });
+}
+
+pub(super) fn sheet(
+    ui: &mut Ui,
+    IntermediateProfileState { format, sheet, .. }: &mut IntermediateProfileState,
+) {
+    ui.add_enabled_ui(*format == FileFormat::Spreadsheet, |ui| {
+        ui.group(|ui| {
+            ui.vertical(|ui| {
+                ui.label("Sheet").on_hover_text(SHEET_TEXT);
+                text(ui, sheet);
+            });
+        });
+    });
+}
+
 pub(super) fn margin_top(
     ui: &mut Ui,
     IntermediateProfileState { margin_top, .. }: &mut IntermediateProfileState,
@@ -95,3 +162,24 @@ pub(super) fn default_tags(
         ui.select_tags_dialog(tags_state, state, tags);
     });
 }
+
+const ENCODING_TEXT: &str = r#"
+The encoding of the exported files. Detect uses UTF-8 when the file is valid
+UTF-8 and Windows-1252 otherwise, which most German banks use. A byte order
+mark at the start of a file always wins.
+"#;
+
+const FORMAT_TEXT: &str = r#"
+CSV files are split into columns by the delimiter and quote. CAMT.053, MT940,
+OFX and QIF files have a row per booking whose fields are imported without
+any column setup. The opening and closing balances of MT940 statements are
+kept with the import. Delimiter, quote and margins do not matter for them.
+Spreadsheets have a column per cell of the sheet and keep their margins,
+delimiter and quote do not matter for them either.
+"#;
+
+const SHEET_TEXT: &str = r#"
+The name of the sheet to read from a workbook, the first sheet when empty.
+Number and date cells are read as they are, the number and date formats of
+the columns only apply to cells that hold text.
+"#;
@@ -3,14 +3,20 @@ use std::hash::Hash;
 use egui::Ui;
 
 use crate::{
-    apps::utils::{drag_int, option_display, text},
-    model::profiles::{
-        builder::IntermediateProfileState,
-        columns::{
-            money::{Expense, Income, Movement, NumberFormat, PosExpense},
-            time::{ExpenseDate, ExpenseDateTime, ExpenseTime},
-            DateTimeColumn, ExpenseColumn,
+    apps::utils::{drag_int, option_display, single_char, text},
+    model::{
+        profiles::{
+            builder::IntermediateProfileState,
+            columns::{
+                money::{
+                    CurrencyCode, CustomNumberFormat, DebitCredit, Expense,
+                    Income, Movement, NumberFormat, PosExpense, SignConvention,
+                },
+                time::{ExpenseDate, ExpenseDateTime, ExpenseTime},
+                CurrencyColumn, DateTimeColumn, ExpenseColumn,
+            },
         },
+        transactions::currency::Currency,
     },
 };
 
@@ -34,6 +40,15 @@ pub(super) fn datetime_col(
     });
 }
 
+pub(super) fn currency_col(
+    ui: &mut Ui,
+    IntermediateProfileState { currency_col, .. }: &mut IntermediateProfileState,
+) {
+    ui.group(|ui| {
+        ui.vertical(|ui| currency_col_selection(ui, currency_col));
+    });
+}
+
 fn expense_col_selection(ui: &mut Ui, expense_col: &mut Option<ExpenseColumn>) {
     ui.label("Select the main expense column/s");
     ui.horizontal(|ui| {
@@ -65,6 +80,23 @@ fn expense_col_selection(ui: &mut Ui, expense_col: &mut Option<ExpenseColumn>) {
                     drag_int(ui, pos);
                     number_format_combobox("format_combobox", format, ui)
                 }
+                ExpenseColumn::Indicated(
+                    (pos1, Movement(format)),
+                    (pos2, debit_credit),
+                ) => {
+                    ui.vertical(|ui| {
+                        drag_int(ui, pos1);
+                        number_format_combobox(
+                            "amount_format_combobox",
+                            format,
+                            ui,
+                        );
+                    });
+                    ui.vertical(|ui| {
+                        drag_int(ui, pos2);
+                        debit_credit_editor(debit_credit, ui);
+                    });
+                }
             }
         }
     });
@@ -93,6 +125,11 @@ fn expense_col_selection(ui: &mut Ui, expense_col: &mut Option<ExpenseColumn>) {
                 Some(ExpenseColumn::default_only_expense()),
                 "Only Expense",
             );
+            ui.selectable_value(
+                expense_col,
+                Some(ExpenseColumn::default_indicated()),
+                "Debit/Credit",
+            );
         });
 }
 
@@ -148,15 +185,126 @@ fn datetime_col_selection(
         });
 }
 
+fn currency_col_selection(ui: &mut Ui, currency_col: &mut CurrencyColumn) {
+    ui.label("Select the currency or its column");
+    ui.horizontal(|ui| match currency_col {
+        CurrencyColumn::Fixed(currency) => {
+            currency_combobox("currency_combobox", currency, ui);
+        }
+        CurrencyColumn::Column(pos, CurrencyCode(fallback)) => {
+            drag_int(ui, pos);
+            ui.label("empty cells:");
+            currency_combobox("fallback_currency_combobox", fallback, ui);
+        }
+    });
+    egui::ComboBox::from_label("currency")
+        .selected_text(currency_col.to_string())
+        .show_ui(ui, |ui| {
+            ui.style_mut().wrap_mode = Some(egui::TextWrapMode::Truncate);
+            ui.set_min_width(60.0);
+            ui.selectable_value(
+                currency_col,
+                CurrencyColumn::default(),
+                "Fixed",
+            );
+            ui.selectable_value(
+                currency_col,
+                CurrencyColumn::default_column(),
+                "Column",
+            );
+        });
+}
+
+fn currency_combobox(id_salt: impl Hash, currency: &mut Currency, ui: &mut Ui) {
+    egui::ComboBox::from_id_salt(id_salt)
+        .selected_text(currency.to_string())
+        .show_ui(ui, |ui| {
+            for val in Currency::common() {
+                let label = val.to_string();
+                ui.selectable_value(currency, val, label);
+            }
+        });
+}
+
 fn number_format_combobox(
-    id_salt: impl Hash,
+    id_salt: impl Hash + Copy,
     format: &mut NumberFormat,
     ui: &mut Ui,
 ) {
+    let selected = match format {
+        NumberFormat::Custom(_) => String::from("Custom"),
+        _ => format.to_string(),
+    };
     egui::ComboBox::from_id_salt(id_salt)
-        .selected_text(format.to_string())
+        .selected_text(selected)
         .show_ui(ui, |ui| {
             ui.selectable_value(format, NumberFormat::European, "European");
             ui.selectable_value(format, NumberFormat::American, "American");
+            ui.selectable_value(format, NumberFormat::Swiss, "Swiss");
+            if ui
+                .selectable_label(
+                    matches!(format, NumberFormat::Custom(_)),
+                    "Custom",
+                )
+                .clicked()
+            {
+                *format = NumberFormat::Custom(format.spec());
+            }
+        });
+    if let NumberFormat::Custom(custom) = format {
+        custom_number_format(id_salt, custom, ui);
+    }
+}
+
+fn debit_credit_editor(debit_credit: &mut DebitCredit, ui: &mut Ui) {
+    egui::ComboBox::from_id_salt("debit_credit_combobox")
+        .selected_text(debit_credit.to_string())
+        .show_ui(ui, |ui| {
+            for preset in DebitCredit::presets() {
+                let label = preset.to_string();
+                ui.selectable_value(debit_credit, preset, label);
+            }
+        });
+    ui.horizontal(|ui| {
+        ui.label("debit");
+        text(ui, &mut debit_credit.debit);
+    });
+    ui.horizontal(|ui| {
+        ui.label("credit");
+        text(ui, &mut debit_credit.credit);
+    });
+}
+
+fn custom_number_format(
+    id_salt: impl Hash,
+    CustomNumberFormat {
+        decimal,
+        grouping,
+        sign,
+        strip_symbols,
+    }: &mut CustomNumberFormat,
+    ui: &mut Ui,
+) {
+    ui.horizontal(|ui| {
+        ui.label("decimal");
+        let mut decimal_str = decimal.to_string();
+        single_char(ui, &mut decimal_str);
+        if let Some(char) = decimal_str.chars().next() {
+            *decimal = char;
+        }
+    });
+    ui.horizontal(|ui| {
+        ui.label("grouping");
+        let mut grouping_str = grouping.map(String::from).unwrap_or_default();
+        single_char(ui, &mut grouping_str);
+        *grouping = grouping_str.chars().next();
+    });
+    egui::ComboBox::from_id_salt((id_salt, "sign"))
+        .selected_text(sign.to_string())
+        .show_ui(ui, |ui| {
+            for val in SignConvention::values() {
+                ui.selectable_value(sign, val, val.to_string());
+            }
         });
+    ui.checkbox(strip_symbols, "strip symbols");
 }
@@ -7,12 +7,15 @@ use crate::{
             builder::IntermediateProfileState,
             columns::{
                 other::{Description, Special},
-                time::{ExpenseDate, ExpenseDateTime, ExpenseTime},
+                time::{
+                    ExpenseDate, ExpenseDateTime, ExpenseTime, SecondaryDate,
+                    StandaloneTime,
+                },
                 ParsableWrapper,
             },
         },
         transactions::{
-            content_description::ContentDescription,
+            content_description::ContentDescription, datetime::DateRole,
             special_content::SpecialType,
         },
     },
@@ -73,8 +76,13 @@ pub(super) fn other_cols(
                             );
                             ui.selectable_value(
                                 col_type,
-                                ParsableWrapper::expensetime(),
-                                "ExpenseTime",
+                                ParsableWrapper::standalonetime(),
+                                "StandaloneTime",
+                            );
+                            ui.selectable_value(
+                                col_type,
+                                ParsableWrapper::secondarydate(),
+                                "SecondaryDate",
                             );
                             ui.selectable_value(
                                 col_type,
@@ -125,6 +133,28 @@ fn other_col_editor(
                 | ParsableWrapper::ExpenseDateTime(ExpenseDateTime(s))
                 | ParsableWrapper::ExpenseDate(ExpenseDate(s))
                 | ParsableWrapper::ExpenseTime(ExpenseTime(s)) => text(ui, s),
+                ParsableWrapper::StandaloneTime(StandaloneTime(
+                    ExpenseTime(format),
+                    time_desc,
+                )) => {
+                    text(ui, format);
+                    text(ui, &mut time_desc.description);
+                }
+                ParsableWrapper::SecondaryDate(SecondaryDate(
+                    ExpenseDate(format),
+                    role,
+                )) => {
+                    text(ui, format);
+                    egui::ComboBox::from_id_salt(format!(
+                        "date role {col_pos}"
+                    ))
+                    .selected_text(role.to_string())
+                    .show_ui(ui, |ui| {
+                        for val in DateRole::values() {
+                            ui.selectable_value(role, val, val.to_string());
+                        }
+                    });
+                }
                 ParsableWrapper::Special(Special(
                     special_type,
                     special_desc,
@@ -0,0 +1,296 @@
+use egui::{Grid, Ui};
+use hermes::{
+    container::{data::ImplData, manual},
+    factory::Factory,
+};
+use itertools::Itertools;
+
+use crate::{
+    db::query::{
+        data_import_query::DataImportQuery, profile_query::ProfileQuery,
+        transaction_query::TransactionQuery,
+    },
+    model::{
+        data_import::{reparse::ReparseDiff, DataImport, DataImportUuid},
+        profiles::{Profile, ProfileUuid},
+        transactions::{properties::TransactionProperties, Transaction},
+    },
+};
+
+/// Outcome of parsing the stored rows of an import with another version.
+struct Reparse {
+    import: DataImportUuid,
+    version: u32,
+    result: Result<ReparseDiff, String>,
+}
+
+/// Shows every version of one profile, what changed between them and the
+/// imports each version produced.
+pub(super) struct ProfileHistory {
+    profiles: manual::Container<Profile>,
+    imports: manual::Container<DataImport>,
+    transactions: manual::Container<Transaction>,
+    selected: Option<ProfileUuid>,
+    reparse: Option<Reparse>,
+}
+
+impl ProfileHistory {
+    pub fn new(factory: &Factory) -> Self {
+        let mut profiles = factory.builder().file(file!()).manual();
+        profiles.stored_query(ProfileQuery::all);
+
+        let mut imports = factory.builder().file(file!()).manual();
+        imports.stored_query(DataImportQuery::all);
+
+        let mut transactions = factory.builder().file(file!()).manual();
+        transactions.stored_query(TransactionQuery::all);
+
+        Self {
+            profiles,
+            imports,
+            transactions,
+            selected: None,
+            reparse: None,
+        }
+    }
+
+    pub fn select(&mut self, profile: ProfileUuid) {
+        self.selected = Some(profile);
+        self.reparse = None;
+    }
+
+    pub fn view(&mut self, ui: &mut Ui) {
+        self.profiles.state_update(true);
+        self.imports.state_update(true);
+        self.transactions.state_update(true);
+
+        let Some(selected) = self.selected else {
+            return;
+        };
+        let profiles = self.profiles.data();
+        let Some(profile) = profiles.iter().find(|p| p.uuid == selected) else {
+            ui.label("... one moment, loading the profile history ...");
+            return;
+        };
+        let versions = profile.versions(profiles);
+        let latest = versions[versions.len() - 1];
+
+        ui.separator();
+        ui.horizontal(|ui| {
+            ui.heading(format!("History of {}", latest.name));
+            if ui.button("close").clicked() {
+                self.selected = None;
+            }
+        });
+
+        let mut reparse = None;
+        let mut apply = false;
+        for (index, version) in versions.iter().enumerate().rev() {
+            ui.group(|ui| {
+                ui.label(format!(
+                    "version {} created {} ({:?})",
+                    version.version,
+                    version.datetime_created.format("%d.%m.%Y %H:%M"),
+                    version.state
+                ));
+                match index.checked_sub(1).map(|index| versions[index]) {
+                    Some(previous) => {
+                        changes_grid(version, previous, ui);
+                    }
+                    None => {
+                        ui.label("first version");
+                    }
+                }
+
+                let imports = self
+                    .imports
+                    .data()
+                    .iter()
+                    .filter(|import| import.profile_uuid == version.uuid)
+                    .sorted_by_key(|import| import.datetime_created)
+                    .collect_vec();
+                if imports.is_empty() {
+                    ui.label("no imports with this version");
+                }
+                for import in imports {
+                    ui.horizontal(|ui| {
+                        ui.label(format!(
+                            "import of {} on {}, {} of {} rows parsed",
+                            import.file_path.display(),
+                            import.datetime_created.format("%d.%m.%Y"),
+                            import
+                                .rows
+                                .iter()
+                                .filter(|row| row.group_uuid.is_some())
+                                .count(),
+                            import.rows.len()
+                        ));
+                        if version.uuid != latest.uuid
+                            && ui
+                                .button(format!(
+                                    "re-parse with version {}",
+                                    latest.version
+                                ))
+                                .on_hover_text(REPARSE_TEXT)
+                                .clicked()
+                        {
+                            reparse = Some(Reparse {
+                                import: import.uuid,
+                                version: latest.version,
+                                result: import
+                                    .reparse_diff(
+                                        latest,
+                                        self.transactions.data(),
+                                    )
+                                    .map_err(|err| err.to_string()),
+                            });
+                        }
+                    });
+                    if let Some(reparse) = self
+                        .reparse
+                        .as_ref()
+                        .filter(|reparse| reparse.import == import.uuid)
+                    {
+                        apply = reparse_result(reparse, ui);
+                    }
+                }
+            });
+        }
+        if reparse.is_some() {
+            self.reparse = reparse;
+        }
+        if apply {
+            if let Some(Reparse {
+                result: Ok(diff), ..
+            }) = self.reparse.take()
+            {
+                self.imports.apply_reparse(diff);
+            }
+        }
+    }
+}
+
+fn changes_grid(version: &Profile, previous: &Profile, ui: &mut Ui) {
+    let changes = version.changes_since(previous);
+    if changes.is_empty() {
+        ui.label("no changes to the previous version");
+        return;
+    }
+    Grid::new(("profile changes", version.uuid))
+        .striped(true)
+        .show(ui, |ui| {
+            ui.label("setting");
+            ui.label("before");
+            ui.label("after");
+            ui.end_row();
+            for change in changes {
+                ui.label(change.setting);
+                ui.label(change.before);
+                ui.label(change.after);
+                ui.end_row();
+            }
+        });
+}
+
+/// Returns whether the changes should be applied.
+fn reparse_result(reparse: &Reparse, ui: &mut Ui) -> bool {
+    let diff = match &reparse.result {
+        Ok(diff) => diff,
+        Err(err) => {
+            ui.colored_label(
+                ui.visuals().error_fg_color,
+                format!(
+                    "version {} cannot parse this import: {err}",
+                    reparse.version
+                ),
+            );
+            return false;
+        }
+    };
+
+    ui.label(format!(
+        "version {}: {} changed values, {} new values, {} transactions \
+         unchanged, {} values kept because they were edited by hand",
+        reparse.version,
+        diff.updates.len(),
+        diff.additions.len(),
+        diff.unchanged,
+        diff.kept_manual
+    ));
+    if !diff.row_errors.is_empty() {
+        ui.colored_label(
+            ui.visuals().warn_fg_color,
+            format!(
+                "{} rows fail and keep their transactions: {}",
+                diff.row_errors.len(),
+                diff.row_errors
+                    .iter()
+                    .map(|err| format!("row {} {}", err.row_index, err.error))
+                    .join(", ")
+            ),
+        );
+    }
+    if !diff.not_imported.is_empty() {
+        ui.label(format!(
+            "rows {} had no transaction and are not imported",
+            diff.not_imported.iter().join(", ")
+        ));
+    }
+
+    if !diff.updates.is_empty() {
+        Grid::new(("reparse updates", reparse.import))
+            .striped(true)
+            .show(ui, |ui| {
+                ui.label("row");
+                ui.label("before");
+                ui.label("after");
+                ui.end_row();
+                for update in &diff.updates {
+                    ui.label(update.row_index.to_string());
+                    ui.label(property_text(&update.before));
+                    ui.label(property_text(&update.after));
+                    ui.end_row();
+                }
+            });
+    }
+
+    ui.add_enabled_ui(!diff.is_empty(), |ui| {
+        ui.button("apply changes")
+            .on_hover_text(APPLY_TEXT)
+            .clicked()
+    })
+    .inner
+}
+
+fn property_text(property: &TransactionProperties) -> String {
+    match property {
+        TransactionProperties::Datetime(datetime) => {
+            datetime.datetime.format("%d.%m.%Y %H:%M").to_string()
+        }
+        TransactionProperties::Movement(movement) => format!(
+            "{:.2} {}",
+            movement.amount as f64 / 100.,
+            movement.currency
+        ),
+        TransactionProperties::Text(text_content) => format!(
+            "{}: {}",
+            text_content.description.description, text_content.content
+        ),
+        TransactionProperties::Special(special_content) => format!(
+            "{}: {}",
+            special_content.description.description, special_content.content
+        ),
+    }
+}
+
+const REPARSE_TEXT: &str = r#"
+Parses the rows stored with this import again with the latest version of the
+profile and compares the result with the saved transactions. Nothing is saved
+until the changes are applied.
+"#;
+
+const APPLY_TEXT: &str = r#"
+Updates the saved transactions in place, so their tags and links are kept.
+Values edited by hand are never overwritten and rows that fail to parse keep
+their transactions. The import is then linked to the latest version.
+"#;
@@ -0,0 +1,136 @@
+use egui::Ui;
+use hermes::{
+    container::{data::ImplData, manual},
+    factory::Factory,
+};
+
+use crate::{
+    components::origins::origins_dialog::{
+        SelectOriginDialog, SelectOriginState,
+    },
+    db::query::{
+        origins_query::OriginsQuery, profile_query::ProfileQuery,
+        tags_query::TagsQuery,
+    },
+    model::{
+        origins::Origin,
+        profiles::{
+            library::{BundledProfile, LIBRARY},
+            Profile,
+        },
+        tags::Tag,
+    },
+};
+
+/// Adds one of the bundled bank profiles for an origin the user picks.
+pub(super) struct ProfileLibrary {
+    profiles: manual::Container<Profile>,
+    tags: manual::Container<Tag>,
+    origins: manual::Container<Origin>,
+    select_origins_state: SelectOriginState,
+    selected: BundledProfile,
+    origin: Option<Origin>,
+    last_result: Option<Result<String, String>>,
+}
+
+impl ProfileLibrary {
+    pub fn new(factory: &Factory) -> Self {
+        let profiles = factory.builder().file(file!()).manual();
+
+        let mut tags = factory.builder().file(file!()).manual();
+        tags.stored_query(TagsQuery::all);
+
+        let mut origins = factory.builder().file(file!()).manual();
+        origins.stored_query(OriginsQuery::all);
+
+        Self {
+            profiles,
+            tags,
+            origins,
+            select_origins_state: SelectOriginState::default(),
+            selected: LIBRARY[0],
+            origin: None,
+            last_result: None,
+        }
+    }
+
+    pub fn view(&mut self, ui: &mut Ui) {
+        self.tags.state_update(true);
+        self.origins.state_update(true);
+
+        ui.horizontal(|ui| {
+            ui.label("Library: ").on_hover_text(LIBRARY_TEXT);
+            egui::ComboBox::from_id_salt("bundled profiles")
+                .selected_text(self.selected.name)
+                .show_ui(ui, |ui| {
+                    for bundled in LIBRARY {
+                        ui.selectable_value(
+                            &mut self.selected,
+                            bundled,
+                            bundled.name,
+                        );
+                    }
+                });
+
+            match &self.origin {
+                Some(origin) => ui.label(format!("for {}", origin.name)),
+                None => ui.label("select an origin"),
+            };
+            ui.select_origin_dialog(
+                &mut self.select_origins_state,
+                &mut self.origin,
+                &mut self.origins,
+            );
+
+            if let Some(origin) = &self.origin {
+                if ui.button("add profile").clicked() {
+                    self.last_result = Some(
+                        self.selected
+                            .instantiate(origin, self.tags.data())
+                            .map(|imported| {
+                                let msg = format!(
+                                    "Added {} for {}",
+                                    imported.profile.name, origin.name
+                                );
+                                self.profiles.import(imported);
+                                msg
+                            })
+                            .map_err(|err| err.to_string()),
+                    );
+                }
+            }
+            if ui.button("check sample").clicked() {
+                self.last_result = Some(
+                    self.selected
+                        .parse_fixture()
+                        .map_err(|err| err.to_string())
+                        .and_then(|result| match result.row_errors.first() {
+                            Some(err) => Err(format!(
+                                "row {} of the sample fails: {}",
+                                err.row_index, err.error
+                            )),
+                            None => Ok(format!(
+                                "all {} sample rows parse",
+                                result.rows.len()
+                            )),
+                        }),
+                );
+            }
+
+            match &self.last_result {
+                Some(Ok(msg)) => ui.label(msg),
+                Some(Err(err)) => ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("Failed: {err}"),
+                ),
+                None => ui.label(""),
+            };
+        });
+    }
+}
+
+const LIBRARY_TEXT: &str = r#"
+Profiles for the csv exports of common banks. The added profile belongs to the
+selected origin and can be edited like any other profile. Check sample parses
+the anonymized export that comes with the profile.
+"#;
@@ -1,4 +1,4 @@
-use std::{ops::Deref, sync::Arc};
+use std::{ops::Deref, path::PathBuf, sync::Arc};
 
 use egui::{DroppedFile, Response, Ui};
 use lazy_async_promise::{
@@ -10,7 +10,10 @@ use tracing::trace;
 
 use crate::{
     apps::utils::blank_option_display,
-    model::profiles::builder::{IntermediateParse, CreateProfileBuilder},
+    model::profiles::{
+        builder::{CreateProfileBuilder, IntermediateParse},
+        inference::Inference,
+    },
     utils::{CompressDisplayResult, CompressResult},
 };
 
@@ -33,6 +36,7 @@ impl ProfilePreview {
         &mut self,
         ui: &mut Ui,
         builder: &Arc<CreateProfileBuilder>,
+        inference: Option<&Inference>,
     ) -> Response {
         self.recive_files(builder);
 
@@ -51,8 +55,11 @@ impl ProfilePreview {
                                     if let IntermediateParse::RowsAndCols(row) = &slice[0] {
                                         ui.label("");
                                         row.iter().enumerate().for_each(|(i, _)| {
+                                            let header = inference
+                                                .and_then(|inference| inference.header_name(i))
+                                                .map_or(String::new(), |name| format!("\n{name}"));
                                             ui.label(format!(
-                                                "{i} {}",
+                                                "{i} {}{header}",
                                                 blank_option_display(builder.get_from_pos(i).as_ref())
                                             ));
                                         });
@@ -91,6 +98,10 @@ impl ProfilePreview {
         }
     }
 
+    pub fn testing_file_path(&self) -> Option<PathBuf> {
+        self.testing_file.as_ref().as_ref()?.path.clone()
+    }
+
     pub fn reset(&mut self) {
         self.testing_file = Arc::new(None);
         self.parsed_testing_file = None;
@@ -117,10 +128,8 @@ impl ProfilePreview {
             async move {
                 let file = to_be_parsed.deref().clone();
                 let file = file.unwrap().path.unwrap();
-                let str_file =
-                    unpack_result!(std::fs::read_to_string(file), tx);
-                let rows: Vec<String> =
-                    str_file.lines().map(str::to_string).collect();
+                let str_file = unpack_result!(builder.read_file(&file), tx);
+                let rows = builder.records(&str_file);
                 let total_len = rows.len();
                 for (index, row) in rows.into_iter().enumerate() {
                     let parsed = unpack_result!(
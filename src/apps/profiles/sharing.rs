@@ -0,0 +1,180 @@
+use std::{fs, path::PathBuf};
+
+use egui::{DroppedFile, Ui};
+use hermes::{
+    container::{data::ImplData, manual},
+    factory::Factory,
+};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::{
+    db::query::{
+        origins_query::OriginsQuery, profile_query::ProfileQuery,
+        tags_query::TagsQuery,
+    },
+    model::{
+        origins::Origin,
+        profiles::{
+            portable::{PortableFormat, PortableProfile},
+            Profile,
+        },
+        tags::Tag,
+    },
+};
+
+/// Exports profiles to json or toml files and imports dropped profile
+/// files. Every other dropped file is passed on to the profile editor.
+pub(super) struct ProfileSharing {
+    reciver: mpsc::Receiver<DroppedFile>,
+    forward: mpsc::Sender<DroppedFile>,
+    profiles: manual::Container<Profile>,
+    tags: manual::Container<Tag>,
+    origins: manual::Container<Origin>,
+    last_result: Option<Result<String, String>>,
+}
+
+impl ProfileSharing {
+    pub fn new(
+        reciver: mpsc::Receiver<DroppedFile>,
+        forward: mpsc::Sender<DroppedFile>,
+        factory: &Factory,
+    ) -> Self {
+        let profiles = factory.builder().file(file!()).manual();
+
+        let mut tags = factory.builder().file(file!()).manual();
+        tags.stored_query(TagsQuery::all);
+
+        let mut origins = factory.builder().file(file!()).manual();
+        origins.stored_query(OriginsQuery::all);
+
+        Self {
+            reciver,
+            forward,
+            profiles,
+            tags,
+            origins,
+            last_result: None,
+        }
+    }
+
+    /// Writes the profile next to the application as
+    /// `<name>.profile.<format>`.
+    pub fn export(&mut self, profile: &Profile, format: PortableFormat) {
+        let path = PathBuf::from(format!(
+            "{}.profile.{}",
+            file_name(&profile.name),
+            format.extension()
+        ));
+        let written = PortableProfile::from(profile)
+            .write(format)
+            .map_err(|err| err.to_string())
+            .and_then(|content| {
+                fs::write(&path, content).map_err(|err| err.to_string())
+            });
+        self.last_result = Some(match written {
+            Ok(()) => {
+                Ok(format!("Exported {} to {}", profile.name, path.display()))
+            }
+            Err(err) => {
+                warn!(
+                    msg = format!(
+                        "Exporting profile [{}] failed: [{err}]",
+                        profile.name
+                    )
+                );
+                Err(err)
+            }
+        });
+    }
+
+    fn recive_files(&mut self) {
+        while let Ok(file) = self.reciver.try_recv() {
+            let Some(format) =
+                file.path.as_deref().and_then(PortableFormat::from_path)
+            else {
+                if self.forward.try_send(file).is_err() {
+                    warn!(msg = "The profile editor did not take the file");
+                }
+                continue;
+            };
+            let Some(path) = file.path else {
+                continue;
+            };
+            info!(
+                msg = "Recived dropped profile file",
+                file = format!("{}", path.display())
+            );
+
+            let imported = fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|content| {
+                    PortableProfile::parse(&content, format)
+                        .and_then(|portable| {
+                            portable.into_profile(
+                                self.tags.data(),
+                                self.origins.data(),
+                            )
+                        })
+                        .map_err(|err| err.to_string())
+                });
+            self.last_result = Some(match imported {
+                Ok(imported) => {
+                    let msg = format!(
+                        "Imported {} with {} new tags{}",
+                        imported.profile.name,
+                        imported.new_tags.len(),
+                        match &imported.new_origin {
+                            Some(origin) => {
+                                format!(" and the new origin {}", origin.name)
+                            }
+                            None => String::new(),
+                        }
+                    );
+                    self.profiles.import(imported);
+                    Ok(msg)
+                }
+                Err(err) => {
+                    warn!(
+                        msg = format!(
+                            "Importing profile from [{}] failed: [{err}]",
+                            path.display()
+                        )
+                    );
+                    Err(err)
+                }
+            });
+        }
+    }
+
+    pub fn view(&mut self, ui: &mut Ui) {
+        self.tags.state_update(true);
+        self.origins.state_update(true);
+        self.recive_files();
+
+        ui.horizontal(|ui| {
+            ui.label("drop a .json or .toml file to import a profile")
+                .on_hover_text(IMPORT_TEXT);
+            match &self.last_result {
+                Some(Ok(msg)) => ui.label(msg),
+                Some(Err(err)) => ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("Failed: {err}"),
+                ),
+                None => ui.label(""),
+            };
+        });
+    }
+}
+
+fn file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+const IMPORT_TEXT: &str = r#"
+Tags and the origin are matched by their name, the ones that do not exist yet
+are created. Files written by a newer version of the application are rejected.
+Any other dropped file is used as the testing file of the profile editor.
+"#;
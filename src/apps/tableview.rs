@@ -11,15 +11,29 @@ use hermes::{
     container::{data::ImplData, manual},
     factory::Factory,
 };
+use itertools::Itertools;
 
 use crate::{
-    components::expense_records::table::TransactsTable, db::query::transaction_query::TransactionQuery, model::transactions::Transaction
+    components::expense_records::table::TransactsTable,
+    db::query::{
+        exchange_rate_query::ExchangeRateQuery,
+        transaction_query::TransactionQuery,
+    },
+    model::{
+        exchange_rates::{ExchangeRate, ExchangeRates},
+        transactions::{currency::Currency, Transaction},
+    },
 };
 
 pub struct TableView {
     transacts: manual::Container<Transaction>,
     columns_info: TransactsTable,
 
+    exchange_rates: manual::Container<ExchangeRate>,
+    rates: ExchangeRates,
+    /// Additionally shows the totals converted into this currency.
+    reporting_currency: Option<Currency>,
+
     filter_state: FilterState,
     hide_filters: bool,
     action_state: ActionState,
@@ -44,6 +58,12 @@ impl SidePanelState {
 impl App for TableView {
     fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
         self.transacts.state_update(true);
+        self.exchange_rates.state_update(true);
+        if self.exchange_rates.has_changed() {
+            self.rates = ExchangeRates::new(
+                self.exchange_rates.set_viewed().data().iter(),
+            );
+        }
 
         CentralPanel::default().show(ctx, |ui| {
             CentralPanel::default().show_inside(ui, |ui| {
@@ -69,6 +89,25 @@ impl App for TableView {
                     }
                 });
 
+                let totals = Transaction::totals(
+                    self.transacts
+                        .data()
+                        .iter()
+                        .filter(|r| self.filter_state.filter(r)),
+                );
+                ui.horizontal(|ui| {
+                    ui.label(format!(
+                        "Totals: {}",
+                        totals
+                            .into_iter()
+                            .map(|(currency, cents)| cents_text(
+                                cents, &currency
+                            ))
+                            .join(", ")
+                    ));
+                    self.reporting_currency_ui(ui);
+                });
+
                 self.columns_info.toggles(ui);
 
                 self.columns_info.show_filtered(
@@ -128,10 +167,15 @@ impl TableView {
         async move {
             let mut transacts = factory.builder().file(file!()).manual();
             transacts.stored_query(TransactionQuery::all);
+            let mut exchange_rates = factory.builder().file(file!()).manual();
+            exchange_rates.stored_query(ExchangeRateQuery::all);
             Self {
                 action_state: ActionState::new(transacts.actor()),
                 transacts,
                 columns_info: TransactsTable::default(),
+                exchange_rates,
+                rates: ExchangeRates::default(),
+                reporting_currency: None,
                 filter_state: FilterState::default(),
                 hide_filters: true,
                 side_panel_state: SidePanelState::default(),
@@ -141,6 +185,54 @@ impl TableView {
     }
 }
 
+impl TableView {
+    fn reporting_currency_ui(&mut self, ui: &mut egui::Ui) {
+        egui::ComboBox::from_id_salt("table_view_reporting_currency")
+            .selected_text(
+                self.reporting_currency
+                    .as_ref()
+                    .map_or("no conversion".to_owned(), |c| c.to_string()),
+            )
+            .show_ui(ui, |ui| {
+                ui.selectable_value(
+                    &mut self.reporting_currency,
+                    None,
+                    "no conversion",
+                );
+                for currency in Currency::common() {
+                    let label = currency.to_string();
+                    ui.selectable_value(
+                        &mut self.reporting_currency,
+                        Some(currency),
+                        label,
+                    );
+                }
+            });
+
+        let Some(currency) = &self.reporting_currency else {
+            return;
+        };
+        let (total, missing) = self.rates.total(
+            self.transacts
+                .data()
+                .iter()
+                .filter(|r| self.filter_state.filter(r)),
+            currency,
+        );
+        ui.label(format!("= {}", cents_text(total, currency)));
+        if missing > 0 {
+            ui.label(format!("({missing} without exchange rate left out)"));
+        }
+    }
+}
+
+fn cents_text(cents: Option<i64>, currency: &Currency) -> String {
+    match cents {
+        Some(cents) => format!("{:.2} {currency}", cents as f64 / 100f64),
+        None => format!("too large to sum in {currency}"),
+    }
+}
+
 const NO_RECORDS_EMPTY_TEXT: &str = r#"
 Usually there would be a list of expenses here...
 
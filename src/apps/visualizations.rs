@@ -1,12 +1,16 @@
 mod bar_chart;
+mod exchange_rate_import;
 
 use bar_chart::BarChartVis;
 use eframe::App;
+use exchange_rate_import::ExchangeRateImport;
 use hermes::factory::Factory;
+use tokio::sync::mpsc;
 
 pub struct Visualizations {
     update_callback_ctx: Option<egui::Context>,
     bars: BarChartVis,
+    rate_import: ExchangeRateImport,
     selected_anchor: Anchor,
 }
 
@@ -21,6 +25,8 @@ impl App for Visualizations {
 
         egui::CentralPanel::default().show(ctx, |ui| {
             ui.heading("Visualizations");
+            self.rate_import.view(ui);
+            ui.separator();
             match self.selected_anchor {
                 Anchor::BarChart => self.bars.view(ui),
             }
@@ -30,13 +36,16 @@ impl App for Visualizations {
 
 impl Visualizations {
     pub fn init(
+        reciver: mpsc::Receiver<egui::DroppedFile>,
         factory: &Factory,
     ) -> impl std::future::Future<Output = Self> + Send + 'static {
         let bars = BarChartVis::new(factory);
+        let rate_import = ExchangeRateImport::new(reciver, factory);
         async move {
             Self {
                 update_callback_ctx: None,
                 bars: bars.await,
+                rate_import: rate_import.await,
                 selected_anchor: Anchor::BarChart,
             }
         }
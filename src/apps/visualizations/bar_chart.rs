@@ -10,10 +10,17 @@ use hermes::{
     container::{data::ImplData, manual},
     factory::Factory,
 };
+use itertools::Itertools;
 
 use crate::{
-    db::query::transaction_query::TransactionQuery,
-    model::transactions::Transaction,
+    db::query::{
+        exchange_rate_query::ExchangeRateQuery,
+        transaction_query::TransactionQuery,
+    },
+    model::{
+        exchange_rates::{ExchangeRate, ExchangeRates},
+        transactions::{currency::Currency, datetime::DateRole, Transaction},
+    },
 };
 
 #[derive(Default)]
@@ -25,7 +32,19 @@ enum Charts {
 
 pub(super) struct BarChartVis {
     selected: Charts,
+    /// Transactions without a datetime of the selected role are bucketed
+    /// by their primary datetime.
+    date_role: Option<DateRole>,
+    /// Amounts are only summed within one currency.
+    currency: Currency,
+    currencies: Vec<Currency>,
+    /// Converts the other currencies into `currency` with the rate of the
+    /// transaction date instead of leaving them out.
+    convert: bool,
+    /// Transactions left out because no exchange rate was found.
+    missing_rates: usize,
     transactions: manual::Container<Transaction>,
+    exchange_rates: manual::Container<ExchangeRate>,
     weekly: Vec<Bar>,
     monthly: Vec<Bar>,
 }
@@ -36,12 +55,22 @@ impl BarChartVis {
     ) -> impl std::future::Future<Output = Self> + Send + 'static {
         let mut transactions =
             factory.builder().file(file!()).manual();
+        let mut exchange_rates = factory.builder().file(file!()).manual();
         async move {
             transactions.stored_query(TransactionQuery::all);
-            let (weekly, monthly) = Self::update_graphs(&[]);
+            exchange_rates.stored_query(ExchangeRateQuery::all);
+            let currency = Currency::default();
+            let (weekly, monthly, missing_rates) =
+                Self::update_graphs(&[], None, &currency, None);
             Self {
                 selected: Charts::default(),
+                date_role: None,
+                currency,
+                currencies: vec![],
+                convert: false,
+                missing_rates,
                 transactions,
+                exchange_rates,
                 weekly,
                 monthly,
             }
@@ -50,31 +79,71 @@ impl BarChartVis {
 
     pub fn update(&mut self) {
         self.transactions.state_update(true);
-        if self.transactions.has_changed() {
-            let (weekly, monthly) = Self::update_graphs(
-                self.transactions.set_viewed().data().deref(),
-            );
-            self.weekly = weekly;
-            self.monthly = monthly;
+        self.exchange_rates.state_update(true);
+        if self.transactions.has_changed() || self.exchange_rates.has_changed()
+        {
+            self.recompute();
         }
     }
 
-    fn update_graphs(transactions: &[Transaction]) -> (Vec<Bar>, Vec<Bar>) {
+    fn recompute(&mut self) {
+        let rates =
+            ExchangeRates::new(self.exchange_rates.set_viewed().data().iter());
+        let transactions = self.transactions.set_viewed().data();
+        self.currencies = transactions
+            .iter()
+            .map(|trx| trx.currency().clone())
+            .chain(Currency::common().into_iter().filter(|_| self.convert))
+            .unique()
+            .sorted()
+            .collect();
+        let (weekly, monthly, missing_rates) = Self::update_graphs(
+            transactions.deref(),
+            self.date_role,
+            &self.currency,
+            self.convert.then_some(&rates),
+        );
+        self.weekly = weekly;
+        self.monthly = monthly;
+        self.missing_rates = missing_rates;
+    }
+
+    /// Without `rates` only transactions in `currency` are shown.
+    fn update_graphs(
+        transactions: &[Transaction],
+        date_role: Option<DateRole>,
+        currency: &Currency,
+        rates: Option<&ExchangeRates>,
+    ) -> (Vec<Bar>, Vec<Bar>, usize) {
+        let mut missing_rates = 0;
+        let transactions = transactions
+            .iter()
+            .filter_map(|trx| {
+                if currency.eq(trx.currency()) {
+                    return Some((trx, trx.amount()));
+                }
+                let converted = rates?.convert_transaction(trx, currency);
+                if converted.is_none() {
+                    missing_rates += 1;
+                }
+                converted.map(|cents| (trx, cents as f64 / 100f64))
+            })
+            .collect_vec();
         if transactions.is_empty() {
-            return (vec![], vec![]);
+            return (vec![], vec![], missing_rates);
         }
 
         let min = transactions
             .iter()
-            .min_by(|a, b| a.datetime.cmp_datetime(&b.datetime))
-            .expect("No min Record found but should be present")
-            .datetime();
+            .map(|(trx, _)| trx.datetime_of(date_role))
+            .min()
+            .expect("No min Record found but should be present");
 
         let max = transactions
             .iter()
-            .max_by(|a, b| a.datetime.cmp_datetime(&b.datetime))
-            .expect("No max Record found but should be present")
-            .datetime();
+            .map(|(trx, _)| trx.datetime_of(date_role))
+            .max()
+            .expect("No max Record found but should be present");
 
         let mut weekly_amounts = (0..max.sub(min).num_weeks())
             .map(|week_index| (week_index as u64 * 7, 0f64))
@@ -100,15 +169,16 @@ impl BarChartVis {
 
         let is_in_week = is_in_week_fn(min);
 
-        for transac in transactions {
+        for (transac, transac_amount) in transactions {
+            let datetime = transac.datetime_of(date_role);
             for (week_index_in_days, amount) in &mut weekly_amounts {
-                if is_in_week(*week_index_in_days, transac.datetime()) {
-                    *amount += transac.amount();
+                if is_in_week(*week_index_in_days, datetime) {
+                    *amount += transac_amount;
                 }
             }
             for (month, amount) in &mut monthly_amounts {
-                if is_in_month(month, transac.datetime()) {
-                    *amount += transac.amount();
+                if is_in_month(month, datetime) {
+                    *amount += transac_amount;
                 }
             }
         }
@@ -120,7 +190,7 @@ impl BarChartVis {
                     .checked_add_days(Days::new(week_index_as_days))
                     .expect("Adding days shouldnt fail")
                     .format("%e %B %Y");
-                let bar_name = format!("{date} {amount:.2}€");
+                let bar_name = format!("{date} {amount:.2} {currency}");
                 Bar::new(week_index_as_days as f64 / 7f64, amount)
                     .name(bar_name)
             })
@@ -130,12 +200,12 @@ impl BarChartVis {
             .enumerate()
             .map(|(index, (month, amount))| {
                 let date = month.format("%B %Y");
-                let bar_name = format!("{date} {amount:.2}€");
+                let bar_name = format!("{date} {amount:.2} {currency}");
                 Bar::new(index as f64, amount).name(bar_name)
             })
             .collect::<Vec<_>>();
 
-        (week_bars, month_bars)
+        (week_bars, month_bars, missing_rates)
     }
 
     pub fn view(&mut self, ui: &mut Ui) {
@@ -148,8 +218,55 @@ impl BarChartVis {
             if ui.button("monthly").clicked() {
                 self.selected = Charts::Monthly;
             }
+            ui.separator();
+            let before = self.date_role;
+            egui::ComboBox::from_id_salt("bar_chart_date_role")
+                .selected_text(date_role_text(self.date_role))
+                .show_ui(ui, |ui| {
+                    ui.selectable_value(
+                        &mut self.date_role,
+                        None,
+                        date_role_text(None),
+                    );
+                    for role in DateRole::values() {
+                        ui.selectable_value(
+                            &mut self.date_role,
+                            Some(role),
+                            date_role_text(Some(role)),
+                        );
+                    }
+                });
+            ui.separator();
+            let before_currency = self.currency.clone();
+            egui::ComboBox::from_id_salt("bar_chart_currency")
+                .selected_text(self.currency.to_string())
+                .show_ui(ui, |ui| {
+                    for currency in self.currencies.clone() {
+                        let label = currency.to_string();
+                        ui.selectable_value(
+                            &mut self.currency,
+                            currency,
+                            label,
+                        );
+                    }
+                });
+            let before_convert = self.convert;
+            ui.checkbox(&mut self.convert, "convert other currencies");
+            if before != self.date_role
+                || before_currency != self.currency
+                || before_convert != self.convert
+            {
+                self.recompute();
+            }
         });
 
+        if self.convert && self.missing_rates > 0 {
+            ui.label(format!(
+                "{} records left out, no exchange rate to {} found.",
+                self.missing_rates, self.currency
+            ));
+        }
+
         ui.label(format!(
             "Curretly {} records.",
             self.transactions.data().len()
@@ -180,6 +297,10 @@ impl BarChartVis {
     }
 }
 
+fn date_role_text(date_role: Option<DateRole>) -> String {
+    date_role.map_or("transaction date".to_owned(), |role| role.to_string())
+}
+
 fn is_in_week_fn(
     min: &DateTime<Local>,
 ) -> impl Fn(u64, &DateTime<Local>) -> bool + '_ {
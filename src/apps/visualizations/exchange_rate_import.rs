@@ -0,0 +1,91 @@
+use std::fs;
+
+use egui::{DroppedFile, Ui};
+use hermes::{
+    container::{data::ImplData, manual},
+    factory::Factory,
+};
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+
+use crate::{
+    db::query::exchange_rate_query::ExchangeRateQuery,
+    model::exchange_rates::{parse_ecb_csv, ExchangeRate},
+};
+
+/// Imports ECB reference rate csv files dropped onto the visualizations.
+pub(super) struct ExchangeRateImport {
+    reciver: mpsc::Receiver<DroppedFile>,
+    exchange_rates: manual::Container<ExchangeRate>,
+    last_import: Option<Result<usize, String>>,
+}
+
+impl ExchangeRateImport {
+    pub fn new(
+        reciver: mpsc::Receiver<DroppedFile>,
+        factory: &Factory,
+    ) -> impl std::future::Future<Output = Self> + Send + 'static {
+        let mut exchange_rates = factory.builder().file(file!()).manual();
+        async move {
+            exchange_rates.stored_query(ExchangeRateQuery::all);
+            Self {
+                reciver,
+                exchange_rates,
+                last_import: None,
+            }
+        }
+    }
+
+    fn recive_files(&mut self) {
+        while let Ok(file) = self.reciver.try_recv() {
+            info!(
+                msg = "Recived dropped exchange rates file",
+                file = format!("{file:?}")
+            );
+            let Some(path) = file.path else {
+                continue;
+            };
+            let rates = fs::read_to_string(&path)
+                .map_err(|err| err.to_string())
+                .and_then(|content| {
+                    parse_ecb_csv(&content).map_err(|err| err.to_string())
+                });
+            self.last_import = Some(match rates {
+                Ok(rates) => {
+                    let len = rates.len();
+                    self.exchange_rates.insert_many(rates);
+                    Ok(len)
+                }
+                Err(err) => {
+                    warn!(
+                        msg = format!(
+                            "Importing exchange rates from [{}] failed: [{err}]",
+                            path.display()
+                        )
+                    );
+                    Err(err)
+                }
+            });
+        }
+    }
+
+    pub fn view(&mut self, ui: &mut Ui) {
+        self.exchange_rates.state_update(true);
+        self.recive_files();
+
+        ui.horizontal(|ui| {
+            ui.label(format!(
+                "{} exchange rates stored, drop an ECB csv file to import more.",
+                self.exchange_rates.data().len()
+            ));
+            match &self.last_import {
+                Some(Ok(len)) => ui.label(format!("Imported {len} rates.")),
+                Some(Err(err)) => ui.colored_label(
+                    ui.visuals().error_fg_color,
+                    format!("Import failed: {err}"),
+                ),
+                None => ui.label(""),
+            };
+        });
+    }
+}
@@ -2,7 +2,9 @@ use std::ops::Deref;
 
 use egui::{Grid, Ui, Widget};
 
-use crate::model::transactions::Transaction;
+use crate::model::transactions::{
+    properties::TransactionProperties, Transaction,
+};
 
 pub struct TransactionFullView {
     record: Transaction,
@@ -13,27 +15,33 @@ impl TransactionFullView {
         Self { record }
     }
 
-    fn description_ui(&mut self, ui: &mut Ui) {
-        ui.heading("Description:");
-        //ui.vertical(|ui| match self.description_container() {
-        //    Some(desc) => {
-        //        Grid::new("description_grid").show(ui, |ui| {
-        //            desc.as_vec().into_iter().for_each(|desc| {
-        //                ui.label(&desc.title);
-        //                ui.label(
-        //                    desc.datetime_created
-        //                        .format("%d/%m/%Y %H:%M")
-        //                        .to_string(),
-        //                );
-        //                ui.label(&desc.desc);
-        //                ui.end_row();
-        //            });
-        //        });
-        //    }
-        //    _ => {
-        //        ui.label("no desc");
-        //    }
-        //});
+    fn properties_ui(&mut self, ui: &mut Ui) {
+        ui.heading("Properties:");
+        ui.vertical(|ui| {
+            if self.properties.is_empty() {
+                ui.label("no properties");
+                return;
+            }
+            Grid::new("properties_grid").striped(true).show(ui, |ui| {
+                self.properties.iter().for_each(|property| {
+                    match property {
+                        TransactionProperties::Text(text) => {
+                            ui.label(&text.description.description);
+                            ui.label("Text");
+                            ui.label(&text.content);
+                        }
+                        TransactionProperties::Special(special) => {
+                            ui.label(&special.description.description);
+                            ui.label(format!("{:?}", special.content_type));
+                            ui.label(&special.content);
+                        }
+                        TransactionProperties::Datetime(_)
+                        | TransactionProperties::Movement(_) => return,
+                    }
+                    ui.end_row();
+                });
+            });
+        });
     }
 }
 
@@ -52,7 +60,7 @@ impl Widget for &mut TransactionFullView {
                 ui.end_row();
 
                 ui.heading("Amount:");
-                ui.heading(self.amount().to_string());
+                ui.heading(self.amount_text());
                 ui.end_row();
 
                 //ui.heading("Tags:");
@@ -65,7 +73,7 @@ impl Widget for &mut TransactionFullView {
                 //});
                 //ui.end_row();
 
-                self.description_ui(ui);
+                self.properties_ui(ui);
             });
         })
         .response
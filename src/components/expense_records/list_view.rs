@@ -22,7 +22,7 @@ impl Widget for TransactionsListView<'_> {
                 ui.end_row();
 
                 ui.label("Amount");
-                ui.label(r.amount().to_string());
+                ui.label(r.amount_text());
                 ui.end_row();
 
                 ui.label("DateTime performed");
@@ -6,16 +6,22 @@ use uuid::Uuid;
 
 use crate::{
     components::table::TableColumn,
-    model::{tags::Tag, transactions::Transaction},
+    model::{
+        tags::Tag,
+        transactions::{datetime::DateRole, Transaction},
+    },
 };
 
 pub(crate) struct TransactsTable {
     datetime_created: TableColumn<Transaction, DateTime<Local>>,
     uuid: TableColumn<Transaction, Uuid>,
-    amount: TableColumn<Transaction, i32>,
+    amount: TableColumn<Transaction, i64>,
     //description: TableColumn<Transaction, String>,
     tags: TableColumn<Transaction, Vec<Tag>>,
     datetime: TableColumn<Transaction, DateTime<Local>>,
+    value_date: TableColumn<Transaction, DateTime<Local>>,
+    booking_date: TableColumn<Transaction, DateTime<Local>>,
+    completed_date: TableColumn<Transaction, DateTime<Local>>,
     //origin: TableColumn<Transaction, String>,
 }
 
@@ -24,6 +30,9 @@ impl TransactsTable {
         ui.horizontal(|ui| {
             self.datetime_created.toggle(ui);
             self.datetime.toggle(ui);
+            self.value_date.toggle(ui);
+            self.booking_date.toggle(ui);
+            self.completed_date.toggle(ui);
             self.uuid.toggle(ui);
             self.amount.toggle(ui);
             //self.description.toggle(ui);
@@ -34,6 +43,9 @@ impl TransactsTable {
     pub(crate) fn header(&self, ui: &mut Ui) {
         self.datetime_created.header(ui);
         self.datetime.header(ui);
+        self.value_date.header(ui);
+        self.booking_date.header(ui);
+        self.completed_date.header(ui);
         self.uuid.header(ui);
         self.amount.header(ui);
         //self.description.header(ui);
@@ -47,6 +59,9 @@ impl TransactsTable {
     ) {
         self.datetime_created.sorting_header(records, ui);
         self.datetime.sorting_header(records, ui);
+        self.value_date.sorting_header(records, ui);
+        self.booking_date.sorting_header(records, ui);
+        self.completed_date.sorting_header(records, ui);
         self.uuid.sorting_header(records, ui);
         self.amount.sorting_header(records, ui);
         //self.description.sorting_header(records, ui);
@@ -56,6 +71,9 @@ impl TransactsTable {
     pub(crate) fn row(&self, record: &Transaction, ui: &mut Ui) {
         self.datetime_created.display_value(record, ui);
         self.datetime.display_value(record, ui);
+        self.value_date.display_value(record, ui);
+        self.booking_date.display_value(record, ui);
+        self.completed_date.display_value(record, ui);
         self.uuid.display_value(record, ui);
         self.amount.display_value(record, ui);
         //self.description.display_value(record, ui);
@@ -112,6 +130,15 @@ impl Default for TransactsTable {
             tags: TableColumn::active("tags", d_tags),
             datetime: TableColumn::active("datetime", d_datetime)
                 .extract_fn(datetime),
+            value_date: TableColumn::inactive("value date", d_value_date)
+                .extract_fn(value_date),
+            booking_date: TableColumn::inactive("booking date", d_booking_date)
+                .extract_fn(booking_date),
+            completed_date: TableColumn::inactive(
+                "completed date",
+                d_completed_date,
+            )
+            .extract_fn(completed_date),
             //origin: TableColumn::active("origin", d_origin).extract_fn(origin),
         }
     }
@@ -129,6 +156,37 @@ fn d_datetime(record: &Transaction, ui: &mut Ui) {
     ui.label(format!("{}", record.datetime().date_naive()));
 }
 
+fn value_date(record: &Transaction) -> &DateTime<Local> {
+    record.datetime_of(Some(DateRole::Value))
+}
+
+fn d_value_date(record: &Transaction, ui: &mut Ui) {
+    d_role_date(record, DateRole::Value, ui);
+}
+
+fn booking_date(record: &Transaction) -> &DateTime<Local> {
+    record.datetime_of(Some(DateRole::Booking))
+}
+
+fn d_booking_date(record: &Transaction, ui: &mut Ui) {
+    d_role_date(record, DateRole::Booking, ui);
+}
+
+fn completed_date(record: &Transaction) -> &DateTime<Local> {
+    record.datetime_of(Some(DateRole::Completed))
+}
+
+fn d_completed_date(record: &Transaction, ui: &mut Ui) {
+    d_role_date(record, DateRole::Completed, ui);
+}
+
+fn d_role_date(record: &Transaction, role: DateRole, ui: &mut Ui) {
+    match record.datetime_by_role(role) {
+        Some(datetime) => ui.label(format!("{}", datetime.date)),
+        None => ui.label("-"),
+    };
+}
+
 fn uuid(record: &Transaction) -> &Uuid {
     &record.uuid
 }
@@ -137,12 +195,12 @@ fn d_uuid(record: &Transaction, ui: &mut Ui) {
     ui.label(format!("{}", *record.uuid));
 }
 
-fn amount(record: &Transaction) -> &i32 {
+fn amount(record: &Transaction) -> &i64 {
     &record.movement.amount
 }
 
 fn d_amount(record: &Transaction, ui: &mut Ui) {
-    ui.label(record.amount().to_string());
+    ui.label(record.amount_text());
 }
 
 //fn d_description(record: &Transaction, ui: &mut Ui) {
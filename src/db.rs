@@ -2,8 +2,10 @@ use std::{collections::HashMap, hash::Hash};
 
 use chrono::{DateTime, Local, NaiveDate, NaiveTime};
 use itertools::{Chunk, Itertools};
+use lazy_async_promise::ImmediateValuePromise;
 use sea_orm::{ActiveModelTrait, Insert, IntoActiveModel, QueryTrait};
 use sea_query::IdenList;
+use tokio::sync::mpsc;
 use uuid::Uuid;
 
 pub mod builders;
@@ -48,6 +50,29 @@ fn uuid_to_str(uuid: Uuid) -> String {
     uuid.hyphenated().to_string()
 }
 
+/// A sender for the closure of a manual query and a promise of the first
+/// result sent through it. The container runs its query again whenever the
+/// tables change and may hold the data of another query, only the promise
+/// tells that exactly this query ran. A failed run sends its error as text,
+/// so the promise resolves either way.
+#[allow(clippy::type_complexity)]
+fn first_result<T>() -> (
+    mpsc::UnboundedSender<Result<T, String>>,
+    ImmediateValuePromise<Result<T, String>>,
+)
+where
+    T: Send + 'static,
+{
+    let (sender, mut receiver) = mpsc::unbounded_channel();
+    let promise = ImmediateValuePromise::new(async move {
+        // the sender is only dropped together with its container
+        Ok(receiver.recv().await.unwrap_or_else(|| {
+            Err(String::from("the query was dropped before it ran"))
+        }))
+    });
+    (sender, promise)
+}
+
 pub trait VecIntoActiveModel<T, A>
 where
     T: IntoActiveModel<A>,
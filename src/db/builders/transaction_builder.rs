@@ -155,10 +155,12 @@ where
         self,
     ) -> HashMap<(TransactionUuid, TransactionRelType), impl Iterator<Item = T>>
     {
+        // rows of one transaction are not ordered next to each other, so
+        // they have to be grouped over the whole result
         self.into_iter()
-            .chunk_by(IsTransacRelated::key)
+            .into_group_map_by(IsTransacRelated::key)
             .into_iter()
-            .map(|group| (group.0, group.1.collect_vec().into_iter()))
+            .map(|(key, group)| (key, group.into_iter()))
             .collect::<HashMap<_, _>>()
     }
 }
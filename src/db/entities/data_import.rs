@@ -15,6 +15,8 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub file_path: String,
     pub datetime_created: String,
+    #[sea_orm(column_type = "Text")]
+    pub balances: String,
     // ToDo - this has to be deletable, but maybe also not because all the
     // recrods depend from this
 }
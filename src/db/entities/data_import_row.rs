@@ -17,6 +17,8 @@ pub struct Model {
     #[sea_orm(column_type = "Text")]
     pub row_content: String,
     pub row_index: i32,
+    #[sea_orm(column_type = "Blob", nullable)]
+    pub content_hash: Option<Vec<u8>>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -4,7 +4,10 @@ use sea_orm::entity::prelude::*;
 
 use crate::model::{
     group::GroupUuid,
-    transactions::{datetime::DatetimeUuid, properties::OriginType},
+    transactions::{
+        datetime::{DateRole, DatetimeUuid},
+        properties::OriginType,
+    },
 };
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -19,6 +22,7 @@ pub struct Model {
     pub time: Option<String>,
     pub timezone: i32,
     pub group_uuid: GroupUuid,
+    pub role: Option<DateRole>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
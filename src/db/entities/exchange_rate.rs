@@ -0,0 +1,22 @@
+use sea_orm::entity::prelude::*;
+
+use crate::model::transactions::currency::Currency;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
+#[sea_orm(table_name = "exchange_rate")]
+pub struct Model {
+    #[sea_orm(
+        primary_key,
+        auto_increment = false,
+        column_type = "custom(\"DATE\")"
+    )]
+    pub date: String,
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub currency: Currency,
+    pub rate: i64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
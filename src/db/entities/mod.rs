@@ -8,6 +8,7 @@ pub mod data_import;
 pub mod data_import_row;
 pub mod data_import_row_item;
 pub mod datetime;
+pub mod exchange_rate;
 pub mod movement;
 pub mod origins;
 pub mod possible_transaction_link;
@@ -4,7 +4,9 @@ use sea_orm::entity::prelude::*;
 
 use crate::model::{
     group::GroupUuid,
-    transactions::{movement::MovementUuid, properties::OriginType},
+    transactions::{
+        currency::Currency, movement::MovementUuid, properties::OriginType,
+    },
 };
 
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
@@ -13,7 +15,8 @@ pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
     pub uuid: MovementUuid,
     pub origin_type: OriginType,
-    pub amount: i32,
+    pub amount: i64,
+    pub currency: Currency,
     pub group_uuid: GroupUuid,
 }
 
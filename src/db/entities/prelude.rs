@@ -7,6 +7,7 @@ pub use super::data_import::Entity as DataImport;
 pub use super::data_import_row::Entity as DataImportRow;
 pub use super::data_import_row_item::Entity as DataImportRowItem;
 pub use super::datetime::Entity as Datetime;
+pub use super::exchange_rate::Entity as ExchangeRate;
 pub use super::movement::Entity as Movement;
 pub use super::origins::Entity as Origins;
 pub use super::possible_transaction_link::Entity as PossibleTransactionLink;
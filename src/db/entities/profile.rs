@@ -7,6 +7,8 @@ use crate::model::{
     origins::OriginUuid,
     profiles::{
         columns::{self, ModelParsableWrapper},
+        encoding::FileEncoding,
+        format::FileFormat,
         ProfileUuid, State,
     },
     transactions::{
@@ -26,16 +28,26 @@ pub struct Model {
     pub top_margin: i32,
     pub bottom_margin: i32,
     pub delimiter: String,
+    pub quote: Option<String>,
+    pub encoding: FileEncoding,
+    pub format: FileFormat,
+    pub sheet: String,
     #[sea_orm(column_type = "Text")]
     pub amount: String,
     #[sea_orm(column_type = "Text")]
     pub datetime: String,
     #[sea_orm(column_type = "Text")]
+    pub currency: String,
+    #[sea_orm(column_type = "Text")]
     pub other_data: String,
+    #[sea_orm(column_type = "Text")]
+    pub headers: String,
     pub width: i32,
     pub origin_uuid: OriginUuid,
     pub state: State,
     pub datetime_created: String,
+    pub version: i32,
+    pub previous_uuid: Option<ProfileUuid>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -110,9 +122,13 @@ pub enum ParsableWrapper {
     Expense(columns::money::Expense),
     PosExpense(columns::money::PosExpense),
     Movement(columns::money::Movement),
+    DebitCredit(columns::money::DebitCredit),
+    CurrencyCode(columns::money::CurrencyCode),
     ExpenseDateTime(columns::time::ExpenseDateTime),
     ExpenseDate(columns::time::ExpenseDate),
     ExpenseTime(columns::time::ExpenseTime),
+    StandaloneTime(columns::time::ExpenseTime, ContentDescriptionUuid),
+    SecondaryDate(columns::time::SecondaryDate),
     Description(ContentDescriptionUuid),
     Special(SpecialType, ContentDescriptionUuid),
 }
@@ -121,7 +137,8 @@ impl ParsableWrapper {
     pub fn need_desc(&self) -> Option<&ContentDescriptionUuid> {
         match self {
             ParsableWrapper::Description(uuid)
-            | ParsableWrapper::Special(_, uuid) => Some(uuid),
+            | ParsableWrapper::Special(_, uuid)
+            | ParsableWrapper::StandaloneTime(_, uuid) => Some(uuid),
             _ => None,
         }
     }
@@ -144,6 +161,12 @@ impl From<(ParsableWrapper, Option<ModelContentDescription>)>
             ParsableWrapper::Movement(movement) => {
                 ModelParsableWrapper::Movement(movement)
             }
+            ParsableWrapper::DebitCredit(debit_credit) => {
+                ModelParsableWrapper::DebitCredit(debit_credit)
+            }
+            ParsableWrapper::CurrencyCode(currency_code) => {
+                ModelParsableWrapper::CurrencyCode(currency_code)
+            }
             ParsableWrapper::ExpenseDateTime(expense_date_time) => {
                 ModelParsableWrapper::ExpenseDateTime(expense_date_time)
             }
@@ -153,6 +176,17 @@ impl From<(ParsableWrapper, Option<ModelContentDescription>)>
             ParsableWrapper::ExpenseTime(expense_time) => {
                 ModelParsableWrapper::ExpenseTime(expense_time)
             }
+            ParsableWrapper::StandaloneTime(expense_time, _) => {
+                ModelParsableWrapper::StandaloneTime(
+                    columns::time::StandaloneTime(
+                        expense_time,
+                        value.1.unwrap(),
+                    ),
+                )
+            }
+            ParsableWrapper::SecondaryDate(secondary_date) => {
+                ModelParsableWrapper::SecondaryDate(secondary_date)
+            }
             ParsableWrapper::Description(_) => {
                 ModelParsableWrapper::Description(columns::other::Description(
                     value.1.unwrap(),
@@ -183,6 +217,12 @@ impl From<ModelParsableWrapper> for ParsableWrapper {
             ModelParsableWrapper::Movement(movement) => {
                 ParsableWrapper::Movement(movement)
             }
+            ModelParsableWrapper::DebitCredit(debit_credit) => {
+                ParsableWrapper::DebitCredit(debit_credit)
+            }
+            ModelParsableWrapper::CurrencyCode(currency_code) => {
+                ParsableWrapper::CurrencyCode(currency_code)
+            }
             ModelParsableWrapper::ExpenseDateTime(expense_date_time) => {
                 ParsableWrapper::ExpenseDateTime(expense_date_time)
             }
@@ -192,6 +232,12 @@ impl From<ModelParsableWrapper> for ParsableWrapper {
             ModelParsableWrapper::ExpenseTime(expense_time) => {
                 ParsableWrapper::ExpenseTime(expense_time)
             }
+            ModelParsableWrapper::StandaloneTime(
+                columns::time::StandaloneTime(expense_time, desc),
+            ) => ParsableWrapper::StandaloneTime(expense_time, desc.uuid),
+            ModelParsableWrapper::SecondaryDate(secondary_date) => {
+                ParsableWrapper::SecondaryDate(secondary_date)
+            }
             ModelParsableWrapper::Description(description) => {
                 ParsableWrapper::Description(description.0.uuid)
             }
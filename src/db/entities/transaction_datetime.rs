@@ -9,7 +9,7 @@ use crate::model::transactions::{
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "transaction_datetime")]
 pub struct Model {
-    #[sea_orm(primary_key, auto_increment = false, unique)]
+    #[sea_orm(primary_key, auto_increment = false)]
     pub transaction_uuid: TransactionUuid,
     #[sea_orm(primary_key, auto_increment = false)]
     pub datetime_uuid: DatetimeUuid,
@@ -19,7 +19,7 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(
         belongs_to = "super::special_content::Entity",
-        from = "Column::TransactionUuid",
+        from = "Column::SpecialUuid",
         to = "super::special_content::Column::Uuid",
         on_update = "Restrict",
         on_delete = "Restrict"
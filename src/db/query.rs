@@ -2,6 +2,7 @@
 //
 pub mod content_description_query;
 pub mod data_import_query;
+pub mod exchange_rate_query;
 pub mod group_query;
 pub mod origins_query;
 pub mod profile_query;
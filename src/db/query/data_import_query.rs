@@ -1,4 +1,5 @@
 pub mod models_to_row_items;
+mod reparse_query;
 mod row_items_query;
 mod row_query;
 
@@ -13,20 +14,30 @@ use hermes::{
     ContainsTables, TablesCollector,
 };
 use itertools::Itertools;
+use lazy_async_promise::ImmediateValuePromise;
 use models_to_row_items::EntitiesToInsert;
 use num_traits::Zero;
-use row_query::all_rows;
+use reparse_query::ReparseEntities;
+pub use row_query::hash_legacy_rows;
+use row_query::{all_rows, imports_with_hashes, rows_of_imports};
 use sea_orm::{
-    DatabaseConnection, DbErr, EntityOrSelect, EntityTrait, QueryTrait,
+    sea_query::Expr, ColumnTrait, DatabaseConnection, DbErr, EntityOrSelect,
+    EntityTrait, QueryFilter, QueryTrait,
 };
 
 use crate::{
     db::{
         combine_types,
         entities::{self, prelude::*},
-        parse_datetime_str, IntoInsertQueries,
+        first_result, parse_datetime_str, IntoInsertQueries,
+    },
+    model::{
+        data_import::{
+            reparse::ReparseDiff, row::ModelImportRow, DataImportUuid,
+            ModelDataImport,
+        },
+        profiles::ProfileUuid,
     },
-    model::data_import::{row::ModelImportRow, ModelDataImport},
 };
 
 pub trait DataImportQuery {
@@ -54,6 +65,31 @@ pub trait DataImportQuery {
             }),
         )
     }
+
+    fn profile_query(
+        import: DataImportUuid,
+        profile: ProfileUuid,
+    ) -> impl QueryTrait + Send + 'static {
+        DataImport::update_many()
+            .filter(entities::data_import::Column::Uuid.eq(*import))
+            .col_expr(
+                entities::data_import::Column::Profile,
+                Expr::value(*profile),
+            )
+    }
+
+    /// Writes the changes of a re-parse and records the profile version
+    /// that made them on the import.
+    fn apply_reparse(&mut self, diff: ReparseDiff);
+
+    /// Loads only the imports that share a row with `hashes`, with their
+    /// rows but without the items of the rows. The hashes contain the
+    /// origin, so only imports of the same origin are found. The promise
+    /// resolves with them or the error once the query ran.
+    fn overlapping(
+        &mut self,
+        hashes: Vec<Vec<u8>>,
+    ) -> ImmediateValuePromise<Result<Vec<ModelDataImport>, String>>;
 }
 
 impl DataImportQuery for manual::Container<ModelDataImport> {
@@ -70,6 +106,35 @@ impl DataImportQuery for manual::Container<ModelDataImport> {
             builder.execute_many(imports).execute_many(rows).execute_many(items);
         });
     }
+
+    fn apply_reparse(&mut self, diff: ReparseDiff) {
+        let profile = Self::profile_query(diff.import, diff.profile);
+        let entities = ReparseEntities::from(diff);
+        self.execute_many(|builder| {
+            entities.add_all_to_transaction(builder).execute(profile);
+        });
+    }
+
+    fn overlapping(
+        &mut self,
+        hashes: Vec<Vec<u8>>,
+    ) -> ImmediateValuePromise<Result<Vec<ModelDataImport>, String>> {
+        let (sender, promise) = first_result();
+        self.manual_query(move |db, mut collector| {
+            let hashes = hashes.clone();
+            let sender = sender.clone();
+            async move {
+                let imports =
+                    overlapping_imports(&db, &mut collector, &hashes).await;
+                // nobody waits for the runs after the first
+                let _ = sender.send(
+                    imports.as_ref().cloned().map_err(ToString::to_string),
+                );
+                ExecutedQuery::new_collector(collector, imports)
+            }
+        });
+        promise
+    }
 }
 
 pub(super) async fn all_imports(
@@ -100,6 +165,44 @@ pub(super) async fn all_imports(
     ))
 }
 
+async fn overlapping_imports(
+    db: &DatabaseConnection,
+    collector: &mut TablesCollector,
+    hashes: &[Vec<u8>],
+) -> Result<Vec<ModelDataImport>, DbErr> {
+    let uuids = imports_with_hashes(db, hashes).await?;
+    if uuids.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let imports = DataImport::find()
+        .select()
+        .filter(
+            entities::data_import::Column::Uuid
+                .is_in(uuids.iter().map(|uuid| **uuid)),
+        )
+        .and_find_tables(collector)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(to_model_import)
+        .collect_vec();
+    let rows = rows_of_imports(db, collector, &uuids).await?;
+
+    Ok(combine_types(
+        imports,
+        rows,
+        |o| o.uuid,
+        |i| i.origin_import,
+        |import, rows| {
+            import
+                .rows
+                .extend(rows.into_iter().map(ModelImportRow::from));
+            import.sort_by_index();
+        },
+    ))
+}
+
 fn to_model_import(
     entities::data_import::Model {
         uuid,
@@ -107,6 +210,7 @@ fn to_model_import(
         file_hash,
         file_path,
         datetime_created,
+        balances,
     }: entities::data_import::Model,
 ) -> ModelDataImport {
     ModelDataImport {
@@ -116,5 +220,6 @@ fn to_model_import(
         file_path: PathBuf::from(file_path),
         datetime_created: parse_datetime_str(&datetime_created),
         rows: vec![],
+        balances: serde_json::from_str(&balances).unwrap(),
     }
 }
@@ -66,6 +66,7 @@ fn import_from_model(
         file_path,
         datetime_created,
         rows,
+        balances,
     }: ModelDataImport,
 ) -> DestructuredImport {
     let import = entities::data_import::Model {
@@ -74,6 +75,7 @@ fn import_from_model(
         file_hash,
         file_path: file_path.to_string_lossy().into_owned(),
         datetime_created: datetime_to_str(datetime_created),
+        balances: serde_json::ser::to_string(&balances).unwrap(),
     };
 
     let (rows, items) =
@@ -97,6 +99,7 @@ fn row_from_model(
         items,
         row_content,
         row_index,
+        content_hash,
     }: ModelImportRow,
 ) -> (
     entities::data_import_row::Model,
@@ -113,12 +116,13 @@ fn row_from_model(
         group_uuid,
         row_content,
         row_index: row_index as i32,
+        content_hash,
     };
 
     (row, items)
 }
 
-fn row_item_from_model(
+pub(super) fn row_item_from_model(
     origin_row: ImportRowUuid,
     ModelImportRowItem {
         uuid,
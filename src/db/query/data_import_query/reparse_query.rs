@@ -0,0 +1,129 @@
+use hermes::carrier::execute::TransactionBuilder;
+use sea_orm::{sea_query::OnConflict, EntityTrait};
+
+use crate::{
+    db::{
+        entities::{self, prelude::*},
+        query::transaction_query::{
+            transaction_datetime_query::datetime_from_model,
+            transaction_movement_query::movement_from_model,
+            transaction_properties::TransactionEntityContainer,
+            transaction_special_query::special_from_model,
+            transaction_text_query::text_from_model,
+        },
+        IntoInsertQueries,
+    },
+    model::{
+        data_import::reparse::{PropertyAddition, PropertyUpdate, ReparseDiff},
+        transactions::properties::{TransactionProperties, TransactionRelType},
+    },
+};
+
+use super::models_to_row_items::row_item_from_model;
+
+/// Entities written when a re-parse is applied. Updated properties are
+/// upserted on their uuid, added ones are inserted with their links.
+#[derive(Default)]
+pub(super) struct ReparseEntities {
+    movements: Vec<entities::movement::Model>,
+    datetimes: Vec<entities::datetime::Model>,
+    texts: Vec<entities::text_content::Model>,
+    specials: Vec<entities::special_content::Model>,
+    additions: TransactionEntityContainer,
+    items: Vec<entities::data_import_row_item::Model>,
+}
+
+impl From<ReparseDiff> for ReparseEntities {
+    fn from(value: ReparseDiff) -> Self {
+        let mut entities = Self::default();
+        for PropertyUpdate {
+            transaction, after, ..
+        } in value.updates
+        {
+            // the links to the transaction already exist
+            let rel_type = TransactionRelType::Additional;
+            match after {
+                TransactionProperties::Datetime(datetime) => {
+                    entities.datetimes.push(
+                        datetime_from_model(transaction, rel_type, datetime).0,
+                    )
+                }
+                TransactionProperties::Movement(movement) => {
+                    entities.movements.push(
+                        movement_from_model(transaction, rel_type, movement).0,
+                    )
+                }
+                TransactionProperties::Text(text_content) => {
+                    entities.texts.push(
+                        text_from_model(transaction, rel_type, text_content).0,
+                    )
+                }
+                TransactionProperties::Special(special_content) => entities
+                    .specials
+                    .push(special_from_model(transaction, special_content).0),
+            }
+        }
+        for PropertyAddition {
+            transaction,
+            row,
+            item,
+            property,
+        } in value.additions
+        {
+            entities.additions.add_property(transaction, property);
+            entities.items.push(row_item_from_model(row, item));
+        }
+        entities
+    }
+}
+
+impl ReparseEntities {
+    pub fn add_all_to_transaction<'builder, 'executor>(
+        self,
+        builder: &'builder mut TransactionBuilder<'executor>,
+    ) -> &'builder mut TransactionBuilder<'executor> {
+        self.additions
+            .add_all_to_transaction(builder)
+            .execute_many(self.movements.into_insert_queries(|a| {
+                Movement::insert_many(a).on_conflict(
+                    OnConflict::column(entities::movement::Column::Uuid)
+                        .update_columns([
+                            entities::movement::Column::Amount,
+                            entities::movement::Column::Currency,
+                        ])
+                        .to_owned(),
+                )
+            }))
+            .execute_many(self.datetimes.into_insert_queries(|a| {
+                Datetime::insert_many(a).on_conflict(
+                    OnConflict::column(entities::datetime::Column::Uuid)
+                        .update_columns([
+                            entities::datetime::Column::Date,
+                            entities::datetime::Column::Time,
+                            entities::datetime::Column::Timezone,
+                            entities::datetime::Column::Role,
+                        ])
+                        .to_owned(),
+                )
+            }))
+            .execute_many(self.texts.into_insert_queries(|a| {
+                TextContent::insert_many(a).on_conflict(
+                    OnConflict::column(entities::text_content::Column::Uuid)
+                        .update_column(entities::text_content::Column::Content)
+                        .to_owned(),
+                )
+            }))
+            .execute_many(self.specials.into_insert_queries(|a| {
+                SpecialContent::insert_many(a).on_conflict(
+                    OnConflict::column(entities::special_content::Column::Uuid)
+                        .update_column(
+                            entities::special_content::Column::Content,
+                        )
+                        .to_owned(),
+                )
+            }))
+            .execute_many(self.items.into_insert_queries(|a| {
+                DataImportRowItem::insert_many(a).do_nothing()
+            }))
+    }
+}
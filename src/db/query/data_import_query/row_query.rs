@@ -1,21 +1,35 @@
 use hermes::{ContainsTables, TablesCollector};
 use itertools::Itertools;
-use sea_orm::{DatabaseConnection, DbErr, EntityOrSelect, EntityTrait};
+use sea_orm::{
+    sea_query::Expr, ColumnTrait, DatabaseConnection, DbErr, EntityOrSelect,
+    EntityTrait, JoinType, QueryFilter, QuerySelect, RelationTrait,
+    TransactionTrait,
+};
 
 use crate::{
     db::{
         combine_types,
-        entities::{self, prelude::DataImportRow},
+        entities::{
+            self,
+            prelude::{DataImport, DataImportRow},
+        },
+    },
+    model::{
+        data_import::{
+            row::{ImportRow, ImportRowUuid, ModelImportRow},
+            row_item::ModelImportRowItem,
+            DataImportUuid,
+        },
+        group::GroupUuid,
+        origins::OriginUuid,
     },
-    model::{data_import::{
-        row::{ImportRowUuid, ModelImportRow},
-        row_item::ModelImportRowItem,
-        DataImportUuid,
-    }, group::GroupUuid},
 };
 
 use super::row_items_query::all_row_items;
 
+/// Keeps the number of bound values per statement below the sqlite limit.
+const CHUNK_SIZE: usize = 500;
+
 pub(super) async fn all_rows(
     db: &DatabaseConnection,
     collector: &mut TablesCollector,
@@ -43,6 +57,93 @@ pub(super) async fn all_rows(
     ))
 }
 
+/// Rows of `imports` without their items, enough to compare them with the
+/// rows of a new file.
+pub(super) async fn rows_of_imports(
+    db: &DatabaseConnection,
+    collector: &mut TablesCollector,
+    imports: &[DataImportUuid],
+) -> Result<Vec<ImportRowWithOrigin>, DbErr> {
+    let mut rows = vec![];
+    for chunk in imports.chunks(CHUNK_SIZE) {
+        rows.extend(
+            DataImportRow::find()
+                .select()
+                .filter(
+                    entities::data_import_row::Column::OriginImport
+                        .is_in(chunk.iter().map(|uuid| **uuid)),
+                )
+                .and_find_tables(collector)
+                .all(db)
+                .await?
+                .into_iter()
+                .map(ImportRowWithOrigin::from),
+        );
+    }
+    Ok(rows)
+}
+
+/// Imports with at least one row of these content hashes. Goes through
+/// the index on the hash, no row content is loaded.
+pub(super) async fn imports_with_hashes(
+    db: &DatabaseConnection,
+    hashes: &[Vec<u8>],
+) -> Result<Vec<DataImportUuid>, DbErr> {
+    let mut imports = vec![];
+    for chunk in hashes.chunks(CHUNK_SIZE) {
+        imports.extend(
+            DataImportRow::find()
+                .select_only()
+                .column(entities::data_import_row::Column::OriginImport)
+                .distinct()
+                .filter(
+                    entities::data_import_row::Column::ContentHash
+                        .is_in(chunk.iter().cloned()),
+                )
+                .into_tuple::<DataImportUuid>()
+                .all(db)
+                .await?,
+        );
+    }
+    Ok(imports.into_iter().unique().collect())
+}
+
+/// Gives the rows stored before the content hash existed theirs. The hash
+/// is computed in rust, so this runs once on start up instead of in a
+/// migration, the overlap search only reads the hashes.
+pub async fn hash_legacy_rows(db: &DatabaseConnection) -> Result<(), DbErr> {
+    let rows = DataImportRow::find()
+        .select_only()
+        .column(entities::data_import_row::Column::Uuid)
+        .column(entities::data_import_row::Column::RowContent)
+        .column(entities::profile::Column::OriginUuid)
+        .inner_join(DataImport)
+        .join(
+            JoinType::InnerJoin,
+            entities::data_import::Relation::Profile.def(),
+        )
+        .filter(entities::data_import_row::Column::ContentHash.is_null())
+        .into_tuple::<(ImportRowUuid, String, OriginUuid)>()
+        .all(db)
+        .await?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let transaction = db.begin().await?;
+    for (uuid, row_content, origin) in rows {
+        DataImportRow::update_many()
+            .col_expr(
+                entities::data_import_row::Column::ContentHash,
+                Expr::value(ImportRow::hash_content(origin, &row_content)),
+            )
+            .filter(entities::data_import_row::Column::Uuid.eq(*uuid))
+            .exec(&transaction)
+            .await?;
+    }
+    transaction.commit().await
+}
+
 #[derive(Debug, Clone)]
 pub struct ImportRowWithOrigin {
     pub origin_import: DataImportUuid,
@@ -51,6 +152,7 @@ pub struct ImportRowWithOrigin {
     pub row_content: String,
     pub row_index: i32,
     pub items: Vec<ModelImportRowItem>,
+    pub content_hash: Option<Vec<u8>>,
 }
 
 impl From<entities::data_import_row::Model> for ImportRowWithOrigin {
@@ -61,6 +163,7 @@ impl From<entities::data_import_row::Model> for ImportRowWithOrigin {
             group_uuid,
             row_content,
             row_index,
+            content_hash,
         }: entities::data_import_row::Model,
     ) -> Self {
         ImportRowWithOrigin {
@@ -70,6 +173,7 @@ impl From<entities::data_import_row::Model> for ImportRowWithOrigin {
             row_index,
             origin_import,
             items: vec![],
+            content_hash,
         }
     }
 }
@@ -82,6 +186,7 @@ impl From<ImportRowWithOrigin> for ModelImportRow {
             row_content,
             row_index,
             items,
+            content_hash,
             ..
         }: ImportRowWithOrigin,
     ) -> Self {
@@ -91,6 +196,7 @@ impl From<ImportRowWithOrigin> for ModelImportRow {
             row_content,
             row_index: row_index as usize,
             items,
+            content_hash,
         }
     }
 }
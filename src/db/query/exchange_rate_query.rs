@@ -0,0 +1,86 @@
+use hermes::{
+    carrier::{
+        execute::ImplExecuteCarrier, manual_query::ImplManualQueryCarrier,
+        query::ExecutedQuery,
+    },
+    container::manual,
+    ContainsTables, TablesCollector,
+};
+use itertools::Itertools;
+use sea_orm::{
+    sea_query::OnConflict, DatabaseConnection, DbErr, EntityOrSelect,
+    EntityTrait, QueryTrait,
+};
+
+use crate::{
+    db::{
+        entities::{self, prelude::*},
+        naive_date_to_str, parse_naive_date_str, IntoInsertQueries,
+    },
+    model::exchange_rates::ModelExchangeRate,
+};
+
+pub trait ExchangeRateQuery {
+    /// Rates of an already known date and currency are replaced, so
+    /// importing a newer file corrects older values.
+    fn insert_many_queries(
+        rates: Vec<ModelExchangeRate>,
+    ) -> Vec<impl QueryTrait + Send + 'static> {
+        rates
+            .into_iter()
+            .map(|rate| entities::exchange_rate::Model {
+                date: naive_date_to_str(rate.date),
+                currency: rate.currency,
+                rate: rate.rate,
+            })
+            .collect_vec()
+            .into_insert_queries(|a| {
+                ExchangeRate::insert_many(a).on_conflict(
+                    OnConflict::columns([
+                        entities::exchange_rate::Column::Date,
+                        entities::exchange_rate::Column::Currency,
+                    ])
+                    .update_column(entities::exchange_rate::Column::Rate)
+                    .to_owned(),
+                )
+            })
+    }
+
+    fn insert_many(&mut self, rates: Vec<ModelExchangeRate>);
+
+    fn all(&mut self);
+}
+
+impl ExchangeRateQuery for manual::Container<ModelExchangeRate> {
+    fn insert_many(&mut self, rates: Vec<ModelExchangeRate>) {
+        let queries = Self::insert_many_queries(rates);
+        self.execute_many(|builder| {
+            builder.execute_many(queries);
+        });
+    }
+
+    fn all(&mut self) {
+        self.manual_query(|db, mut collector| async move {
+            let rates = all_exchange_rates(&db, &mut collector).await;
+            ExecutedQuery::new_collector(collector, rates)
+        });
+    }
+}
+
+pub(super) async fn all_exchange_rates(
+    db: &DatabaseConnection,
+    collector: &mut TablesCollector,
+) -> Result<Vec<ModelExchangeRate>, DbErr> {
+    Ok(ExchangeRate::find()
+        .select()
+        .and_find_tables(collector)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|rate| ModelExchangeRate {
+            date: parse_naive_date_str(&rate.date),
+            currency: rate.currency,
+            rate: rate.rate,
+        })
+        .collect())
+}
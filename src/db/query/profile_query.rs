@@ -25,8 +25,12 @@ use crate::{
         origins::{ModelOrigin, OriginUuid},
         profiles::{
             columns::{
-                self, DateTimeColumn, ExpenseColumn, ModelParsableWrapper,
+                self, CurrencyColumn, DateTimeColumn, ExpenseColumn,
+                ModelParsableWrapper,
             },
+            encoding::FileEncoding,
+            format::FileFormat,
+            portable::ImportedProfile,
             ModelProfile, ProfileUuid, State,
         },
         tags::ModelTag,
@@ -37,7 +41,8 @@ use crate::{
 use super::{
     super::{entities, parse_datetime_str},
     content_description_query::{all_profile_descriptions, ProfileDescription},
-    tags_query::{all_profile_tags, profile_tags_from_models},
+    origins_query::OriginsQuery,
+    tags_query::{all_profile_tags, profile_tags_from_models, TagsQuery},
 };
 
 pub trait ProfileQuery {
@@ -55,6 +60,17 @@ pub trait ProfileQuery {
 
     fn delete(&mut self, to_delete: &ProfileUuid);
 
+    fn superseded_query(
+        previous: &ProfileUuid,
+    ) -> impl QueryTrait + Send + 'static {
+        Profile::update_many()
+            .filter(entities::profile::Column::Uuid.eq(**previous))
+            .col_expr(
+                entities::profile::Column::State,
+                State::Superseded.into(),
+            )
+    }
+
     fn deleted_many_query(
         to_delete: impl IntoIterator<Item = ProfileUuid>,
     ) -> impl QueryTrait + Send + 'static {
@@ -86,6 +102,8 @@ pub trait ProfileQuery {
     }
 
     fn insert(&mut self, to_insert: ModelProfile);
+
+    fn import(&mut self, imported: ImportedProfile);
 }
 
 pub(super) async fn all_profiles(
@@ -118,13 +136,21 @@ async fn all_profiles_core(
             entities::profile::Column::TopMargin,
             entities::profile::Column::BottomMargin,
             entities::profile::Column::Delimiter,
+            entities::profile::Column::Quote,
+            entities::profile::Column::Encoding,
+            entities::profile::Column::Format,
+            entities::profile::Column::Sheet,
             entities::profile::Column::Amount,
             entities::profile::Column::Datetime,
+            entities::profile::Column::Currency,
             entities::profile::Column::OtherData,
+            entities::profile::Column::Headers,
             entities::profile::Column::Width,
             entities::profile::Column::State,
             entities::profile::Column::DatetimeCreated,
             entities::profile::Column::OriginUuid,
+            entities::profile::Column::Version,
+            entities::profile::Column::PreviousUuid,
         ])
         .column_as(entities::origins::Column::Name, "origin_name")
         .column_as(entities::origins::Column::Description, "origin_description")
@@ -201,7 +227,9 @@ impl ProfileQuery for manual::Container<ModelProfile> {
         });
     }
 
+    /// Inserting a new version of a profile supersedes the previous one.
     fn insert(&mut self, to_insert: ModelProfile) {
+        let previous = to_insert.previous;
         let (profs, descs, prof_descs, tags) = Self::insert_query(to_insert);
         // ToDo, origins and tags also need to be inserted here
         self.execute_many(|transac| {
@@ -210,6 +238,40 @@ impl ProfileQuery for manual::Container<ModelProfile> {
                 .execute(descs)
                 .execute(prof_descs)
                 .execute(tags);
+            if let Some(previous) = previous {
+                transac.execute(Self::superseded_query(&previous));
+            }
+        });
+    }
+
+    /// Inserts a profile read from a file together with the tags and the
+    /// origin it needs, so the profile never points to missing rows.
+    fn import(
+        &mut self,
+        ImportedProfile {
+            profile,
+            new_tags,
+            new_origin,
+        }: ImportedProfile,
+    ) {
+        let origin =
+            new_origin.map(manual::Container::<ModelOrigin>::insert_query);
+        let tags = new_tags
+            .into_iter()
+            .map(manual::Container::<ModelTag>::insert_query)
+            .collect_vec();
+        let (profs, descs, prof_descs, profile_tags) =
+            Self::insert_query(profile);
+        self.execute_many(|transac| {
+            if let Some(origin) = origin {
+                transac.execute(origin);
+            }
+            transac
+                .execute_many(tags)
+                .execute(profs)
+                .execute(descs)
+                .execute(prof_descs)
+                .execute(profile_tags);
         });
     }
 
@@ -223,14 +285,22 @@ pub struct ProfileBuilder {
     name: String,
     margins: (usize, usize),
     delimiter: char,
+    quote: Option<char>,
+    encoding: FileEncoding,
+    format: FileFormat,
+    sheet: String,
     amount: ExpenseColumn,
     datetime: DateTimeColumn,
+    currency: CurrencyColumn,
     other_data: HashMap<usize, ParsableWrapper>,
+    headers: HashMap<usize, String>,
     width: usize,
     default_tags: Vec<ModelTag>,
     origin: ModelOrigin,
     state: State,
     datetime_created: DateTime<Local>,
+    version: u32,
+    previous: Option<ProfileUuid>,
     desc_containers: Option<Vec<ModelContentDescription>>,
 }
 
@@ -256,14 +326,22 @@ impl ProfileBuilder {
                 profile.bottom_margin.try_into().unwrap(),
             ),
             delimiter: profile.delimiter.chars().nth(0).unwrap(),
+            quote: profile.quote.and_then(|quote| quote.chars().nth(0)),
+            encoding: profile.encoding,
+            format: profile.format,
+            sheet: profile.sheet.clone(),
             amount: serde_json::from_str(&profile.amount).unwrap(),
             datetime: serde_json::from_str(&profile.datetime).unwrap(),
+            currency: serde_json::from_str(&profile.currency).unwrap(),
             other_data,
+            headers: serde_json::from_str(&profile.headers).unwrap(),
             width: profile.width.try_into().unwrap(),
             default_tags: vec![],
             origin,
             state: profile.state,
             datetime_created: parse_datetime_str(&profile.datetime_created),
+            version: profile.version.try_into().unwrap(),
+            previous: profile.previous_uuid,
             desc_containers: None,
         }
     }
@@ -281,17 +359,25 @@ impl ProfileBuilder {
             name: self.name,
             margins: self.margins,
             delimiter: self.delimiter,
+            quote: self.quote,
+            encoding: self.encoding,
+            format: self.format,
+            sheet: self.sheet,
             amount: self.amount,
             datetime: self.datetime,
+            currency: self.currency,
             other_data: parsable_wrappers_to_model(
                 self.other_data,
                 self.desc_containers.unwrap(),
             ),
+            headers: self.headers,
             width: self.width,
             default_tags: self.default_tags,
             origin: self.origin,
             state: self.state,
             datetime_created: self.datetime_created,
+            version: self.version,
+            previous: self.previous,
         }
     }
 }
@@ -303,15 +389,23 @@ struct ProfileWithOrigin {
     pub top_margin: i32,
     pub bottom_margin: i32,
     pub delimiter: String,
+    pub quote: Option<String>,
+    pub encoding: FileEncoding,
+    pub format: FileFormat,
+    pub sheet: String,
     pub amount: String,
     pub datetime: String,
+    pub currency: String,
     pub other_data: String,
+    pub headers: String,
     pub width: i32,
     pub state: State,
     pub datetime_created: String,
     pub origin_uuid: OriginUuid,
     pub origin_name: String,
     pub origin_description: String,
+    pub version: i32,
+    pub previous_uuid: Option<ProfileUuid>,
 }
 
 fn profile_from_model(
@@ -320,14 +414,22 @@ fn profile_from_model(
         name,
         margins,
         delimiter,
+        quote,
+        encoding,
+        format,
+        sheet,
         amount,
         datetime,
+        currency,
         other_data,
+        headers,
         width,
         default_tags,
         origin,
         state,
         datetime_created,
+        version,
+        previous,
     }: ModelProfile,
 ) -> (
     entities::profile::Model,
@@ -349,13 +451,21 @@ fn profile_from_model(
             top_margin: margins.0 as i32,
             bottom_margin: margins.1 as i32,
             delimiter: delimiter.into(),
+            quote: quote.map(String::from),
+            encoding,
+            format,
+            sheet,
             amount: serde_json::ser::to_string(&amount).unwrap(),
             datetime: serde_json::ser::to_string(&datetime).unwrap(),
+            currency: serde_json::ser::to_string(&currency).unwrap(),
             other_data,
+            headers: serde_json::ser::to_string(&headers).unwrap(),
             width: width as i32,
             origin_uuid: origin.uuid,
             state,
             datetime_created: datetime_to_str(datetime_created),
+            version: version as i32,
+            previous_uuid: previous,
         },
         descs,
         profile_descs,
@@ -427,9 +537,10 @@ fn parsable_wrapper_from_model(
         ModelParsableWrapper::Description(columns::other::Description(
             desc,
         ))
-        | ModelParsableWrapper::Special(columns::other::Special(_, desc)) => {
-            Some(description_from_model(profile_uuid, desc.clone()))
-        }
+        | ModelParsableWrapper::Special(columns::other::Special(_, desc))
+        | ModelParsableWrapper::StandaloneTime(
+            columns::time::StandaloneTime(_, desc),
+        ) => Some(description_from_model(profile_uuid, desc.clone())),
         _ => None,
     };
     (ParsableWrapper::from(model), container)
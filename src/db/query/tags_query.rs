@@ -41,6 +41,24 @@ pub trait TagsQuery {
 
     fn delete(&mut self, to_delete: TagUuid);
 
+    fn insert_query(
+        Tag {
+            uuid,
+            tag,
+            description,
+        }: ModelTag,
+    ) -> impl QueryTrait + Send + 'static {
+        Tags::insert(
+            entities::tags::Model {
+                uuid,
+                tag,
+                description,
+            }
+            .into_active_model(),
+        )
+        .do_nothing()
+    }
+
     fn insert(&mut self, to_insert: ModelTag);
 
     fn all(&mut self);
@@ -51,25 +69,8 @@ impl TagsQuery for manual::Container<Tag> {
         self.execute(Self::delete_query(to_delete));
     }
 
-    fn insert(
-        &mut self,
-        Tag {
-            uuid,
-            tag,
-            description,
-        }: ModelTag,
-    ) {
-        self.execute(
-            Tags::insert(
-                entities::tags::Model {
-                    uuid,
-                    tag,
-                    description,
-                }
-                .into_active_model(),
-            )
-            .do_nothing(),
-        );
+    fn insert(&mut self, to_insert: ModelTag) {
+        self.execute(Self::insert_query(to_insert));
     }
 
     fn all(&mut self) {
@@ -4,18 +4,32 @@ pub(crate) mod transaction_properties;
 pub(crate) mod transaction_special_query;
 pub(crate) mod transaction_text_query;
 
+use std::collections::HashSet;
+
 use hermes::{
     carrier::{manual_query::ImplManualQueryCarrier, query::ExecutedQuery},
     container::manual,
     ContainsTables, TablesCollector,
 };
 use itertools::Itertools;
-use sea_orm::{DatabaseConnection, DbErr, EntityOrSelect, EntityTrait};
+use lazy_async_promise::ImmediateValuePromise;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, DbErr, EntityOrSelect, EntityTrait,
+    JoinType, QueryFilter, QuerySelect, RelationTrait,
+};
 use transaction_datetime_query::all_datetimes;
 use transaction_movement_query::all_movements;
 use transaction_properties::TransactionEntityContainer;
+use transaction_special_query::all_specials;
+use transaction_text_query::all_texts;
 
-use crate::{db::combine_types, model::{tags::Tag, transactions::ModelTransaction}};
+use crate::{
+    db::{combine_types, entities, first_result},
+    model::{
+        group::GroupUuid, origins::OriginUuid, tags::Tag,
+        transactions::ModelTransaction,
+    },
+};
 
 use super::{
     super::{
@@ -28,6 +42,13 @@ use super::{
 pub trait TransactionQuery {
     fn all(&mut self);
 
+    /// Transactions parsed from imports of `origin`, the promise resolves
+    /// with them once the query ran.
+    fn of_origin(
+        &mut self,
+        origin: OriginUuid,
+    ) -> ImmediateValuePromise<Result<Vec<ModelTransaction>, String>>;
+
     fn insert_queries(
         transact: ModelTransaction,
     ) -> TransactionEntityContainer {
@@ -61,6 +82,26 @@ impl TransactionQuery for manual::Container<ModelTransaction> {
         });
     }
 
+    fn of_origin(
+        &mut self,
+        origin: OriginUuid,
+    ) -> ImmediateValuePromise<Result<Vec<ModelTransaction>, String>> {
+        let (sender, promise) = first_result();
+        self.manual_query(move |db, mut collector| {
+            let sender = sender.clone();
+            async move {
+                let transactions =
+                    origin_transactions(&db, &mut collector, origin).await;
+                // nobody waits for the runs after the first
+                let _ = sender.send(
+                    transactions.as_ref().cloned().map_err(ToString::to_string),
+                );
+                ExecutedQuery::new_collector(collector, transactions)
+            }
+        });
+        promise
+    }
+
     fn insert(&mut self, transact: ModelTransaction) {
         Self::insert_queries(transact).insert_everything(self);
     }
@@ -70,6 +111,34 @@ impl TransactionQuery for manual::Container<ModelTransaction> {
     }
 }
 
+async fn origin_transactions(
+    db: &DatabaseConnection,
+    collector: &mut TablesCollector,
+    origin: OriginUuid,
+) -> Result<Vec<ModelTransaction>, DbErr> {
+    let groups = DataImportRow::find()
+        .select_only()
+        .column(entities::data_import_row::Column::GroupUuid)
+        .inner_join(DataImport)
+        .join(
+            JoinType::InnerJoin,
+            entities::data_import::Relation::Profile.def(),
+        )
+        .filter(entities::data_import_row::Column::GroupUuid.is_not_null())
+        .filter(entities::profile::Column::OriginUuid.eq(*origin))
+        .into_tuple::<GroupUuid>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect::<HashSet<_>>();
+
+    Ok(all_transactions(db, collector)
+        .await?
+        .into_iter()
+        .filter(|trx| groups.contains(&trx.movement.group_uuid))
+        .collect_vec())
+}
+
 pub(super) async fn all_transactions(
     db: &DatabaseConnection,
     collector: &mut TablesCollector,
@@ -98,6 +167,24 @@ pub(super) async fn all_transactions(
         |trx, tags| trx.feed_tags(tags.into_iter().map(Tag::from)),
     );
 
+    let specials = all_specials(db, collector).await?;
+    let transactions = combine_types(
+        transactions,
+        specials,
+        |trx| trx.uuid,
+        |s| s.transaction_uuid,
+        |trx, specials| trx.feed_properties_opt(Some(specials.into_iter())),
+    );
+
+    let texts = all_texts(db, collector).await?;
+    let transactions = combine_types(
+        transactions,
+        texts,
+        |trx| trx.uuid,
+        |t| t.transaction_uuid,
+        |trx, texts| trx.feed_properties_opt(Some(texts.into_iter())),
+    );
+
     Ok(transactions
         .into_iter()
         .map(|builder| builder.feed_datetimes(&mut transaction_datetimes))
@@ -11,7 +11,7 @@ use crate::{
     model::{
         group::GroupUuid,
         transactions::{
-            datetime::{DatetimeUuid, ModelDatetime},
+            datetime::{DateRole, DatetimeUuid, ModelDatetime},
             properties::{
                 OriginType, TransactionProperties, TransactionRelType,
             },
@@ -35,6 +35,7 @@ pub(in crate::db) struct DatetimeOfTransaction {
     pub time: Option<String>,
     pub timezone: i32,
     pub group_uuid: GroupUuid,
+    pub role: Option<DateRole>,
 }
 
 pub(super) async fn all_datetimes(
@@ -51,6 +52,7 @@ pub(super) async fn all_datetimes(
         .column(datetime::Column::Time)
         .column(datetime::Column::Timezone)
         .column(datetime::Column::GroupUuid)
+        .column(datetime::Column::Role)
         .left_join(Datetime)
         .and_find_tables(collector)
         .into_model()
@@ -67,6 +69,7 @@ impl From<DatetimeOfTransaction> for ModelDatetime {
             val.time.as_deref().map(parse_naive_time_str),
             val.timezone,
             val.group_uuid,
+            val.role,
         )
     }
 }
@@ -87,6 +90,7 @@ pub fn datetime_from_model(
         time,
         timezone,
         group_uuid,
+        role,
         ..
     }: ModelDatetime,
 ) -> (
@@ -100,6 +104,7 @@ pub fn datetime_from_model(
         time: time.map(naive_time_to_str),
         timezone,
         group_uuid,
+        role,
     };
     let link = entities::transaction_datetime::Model {
         transaction_uuid,
@@ -8,6 +8,7 @@ use crate::{
     model::{
         group::GroupUuid,
         transactions::{
+            currency::Currency,
             movement::{ModelMovement, MovementUuid},
             properties::{
                 OriginType, TransactionProperties, TransactionRelType,
@@ -25,7 +26,8 @@ pub(in crate::db) struct MovementOfTransaction {
     pub transaction_uuid: TransactionUuid,
     pub rel_type: TransactionRelType,
     pub origin_type: OriginType,
-    pub amount: i32,
+    pub amount: i64,
+    pub currency: Currency,
     pub group_uuid: GroupUuid,
 }
 
@@ -40,6 +42,7 @@ pub(super) async fn all_movements(
         .column(transaction_movement::Column::RelType)
         .column(movement::Column::OriginType)
         .column(movement::Column::Amount)
+        .column(movement::Column::Currency)
         .column(movement::Column::GroupUuid)
         .left_join(Movement)
         .and_find_tables(collector)
@@ -54,6 +57,7 @@ impl From<MovementOfTransaction> for ModelMovement {
             value.uuid,
             value.origin_type,
             value.amount,
+            value.currency,
             value.group_uuid,
         )
     }
@@ -72,6 +76,7 @@ pub fn movement_from_model(
         uuid,
         origin_type,
         amount,
+        currency,
         group_uuid,
     }: ModelMovement,
 ) -> (
@@ -82,6 +87,7 @@ pub fn movement_from_model(
         uuid,
         origin_type,
         amount,
+        currency,
         group_uuid,
     };
     let link = entities::transaction_movement::Model {
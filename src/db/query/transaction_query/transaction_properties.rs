@@ -128,7 +128,7 @@ impl TransactionEntityContainer {
             self.add_property(transac_uuid, prop);
         })
     }
-    fn add_property(
+    pub(crate) fn add_property(
         &mut self,
         transac_uuid: TransactionUuid,
         property: TransactionProperties,
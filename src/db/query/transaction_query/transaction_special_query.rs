@@ -1,17 +1,24 @@
-use chrono::{DateTime, Local};
 use hermes::{ContainsTables, TablesCollector};
 use sea_orm::{
-    DatabaseConnection, DbErr, EntityTrait, FromQueryResult, QuerySelect,
+    DatabaseConnection, DbErr, EntityTrait, FromQueryResult, JoinType,
+    QuerySelect, RelationTrait,
 };
 
 use crate::{
-    db::entities::{self, content_description, prelude::*, special_content},
+    db::{
+        entities::{
+            self, content_description, prelude::*, special_content,
+            transaction_special,
+        },
+        parse_datetime_str,
+    },
     model::{
         group::GroupUuid,
         transactions::{
             content_description::{
                 ContentDescriptionUuid, ModelContentDescription,
             },
+            properties::TransactionProperties,
             special_content::{
                 ModelSpecialContent, SpecialContentUuid, SpecialType,
             },
@@ -22,22 +29,23 @@ use crate::{
 
 #[derive(FromQueryResult)]
 pub(in crate::db) struct SpecialOfTransaction {
+    pub transaction_uuid: TransactionUuid,
     uuid: SpecialContentUuid,
     content: String,
     special_type: SpecialType,
     group_uuid: GroupUuid,
     description: String,
     description_uuid: ContentDescriptionUuid,
-    datetime_created: DateTime<Local>,
+    datetime_created: String,
 }
 
-#[allow(dead_code)]
 pub(super) async fn all_specials(
     db: &DatabaseConnection,
     collector: &mut TablesCollector,
 ) -> Result<Vec<SpecialOfTransaction>, DbErr> {
-    SpecialContent::find()
+    TransactionSpecial::find()
         .select_only()
+        .column(transaction_special::Column::TransactionUuid)
         .column(special_content::Column::Uuid)
         .column(special_content::Column::Content)
         .column(special_content::Column::SpecialType)
@@ -45,7 +53,11 @@ pub(super) async fn all_specials(
         .column(special_content::Column::DescriptionUuid)
         .column(content_description::Column::Description)
         .column(content_description::Column::DatetimeCreated)
-        .left_join(ContentDescription)
+        .left_join(SpecialContent)
+        .join(
+            JoinType::LeftJoin,
+            special_content::Relation::ContentDescription.def(),
+        )
         .and_find_tables(collector)
         .into_model()
         .all(db)
@@ -62,6 +74,7 @@ impl From<SpecialOfTransaction> for ModelSpecialContent {
             description,
             description_uuid,
             datetime_created,
+            ..
         }: SpecialOfTransaction,
     ) -> Self {
         Self::new(
@@ -70,7 +83,7 @@ impl From<SpecialOfTransaction> for ModelSpecialContent {
             ModelContentDescription::new(
                 description_uuid,
                 description,
-                datetime_created,
+                parse_datetime_str(&datetime_created),
             ),
             special_type,
             group_uuid,
@@ -78,6 +91,12 @@ impl From<SpecialOfTransaction> for ModelSpecialContent {
     }
 }
 
+impl From<SpecialOfTransaction> for TransactionProperties {
+    fn from(value: SpecialOfTransaction) -> Self {
+        TransactionProperties::Special(value.into())
+    }
+}
+
 pub fn special_from_model(
     transaction_uuid: TransactionUuid,
     ModelSpecialContent {
@@ -1,13 +1,16 @@
-use chrono::{DateTime, Local};
 use hermes::{ContainsTables, TablesCollector};
 use sea_orm::{
-    DatabaseConnection, DbErr, EntityTrait, FromQueryResult, QuerySelect,
+    DatabaseConnection, DbErr, EntityTrait, FromQueryResult, JoinType,
+    QuerySelect, RelationTrait,
 };
 
 use crate::{
     db::{
-        datetime_to_str,
-        entities::{self, content_description, prelude::*, text_content},
+        entities::{
+            self, content_description, prelude::*, text_content,
+            transaction_text,
+        },
+        parse_datetime_str,
     },
     model::{
         group::GroupUuid,
@@ -15,7 +18,7 @@ use crate::{
             content_description::{
                 ContentDescriptionUuid, ModelContentDescription,
             },
-            properties::TransactionRelType,
+            properties::{TransactionProperties, TransactionRelType},
             text_content::{ModelTextContent, TextContentUuid},
             TransactionUuid,
         },
@@ -24,27 +27,33 @@ use crate::{
 
 #[derive(FromQueryResult)]
 pub(in crate::db) struct TextOfTransaction {
+    pub transaction_uuid: TransactionUuid,
     uuid: TextContentUuid,
     content: String,
     group_uuid: GroupUuid,
     description: String,
     description_uuid: ContentDescriptionUuid,
-    datetime_created: DateTime<Local>,
+    datetime_created: String,
 }
 
-pub(super) async fn all_datetimes(
+pub(super) async fn all_texts(
     db: &DatabaseConnection,
     collector: &mut TablesCollector,
 ) -> Result<Vec<TextOfTransaction>, DbErr> {
-    TextContent::find()
+    TransactionText::find()
         .select_only()
+        .column(transaction_text::Column::TransactionUuid)
         .column(text_content::Column::Uuid)
         .column(text_content::Column::Content)
         .column(text_content::Column::GroupUuid)
         .column(text_content::Column::DescriptionUuid)
         .column(content_description::Column::Description)
         .column(content_description::Column::DatetimeCreated)
-        .left_join(ContentDescription)
+        .left_join(TextContent)
+        .join(
+            JoinType::LeftJoin,
+            text_content::Relation::ContentDescription.def(),
+        )
         .and_find_tables(collector)
         .into_model()
         .all(db)
@@ -60,6 +69,7 @@ impl From<TextOfTransaction> for ModelTextContent {
             description,
             description_uuid,
             datetime_created,
+            ..
         }: TextOfTransaction,
     ) -> Self {
         Self::new(
@@ -68,13 +78,19 @@ impl From<TextOfTransaction> for ModelTextContent {
             ModelContentDescription::new(
                 description_uuid,
                 description,
-                datetime_created,
+                parse_datetime_str(&datetime_created),
             ),
             group_uuid,
         )
     }
 }
 
+impl From<TextOfTransaction> for TransactionProperties {
+    fn from(value: TextOfTransaction) -> Self {
+        TransactionProperties::Text(value.into())
+    }
+}
+
 pub fn text_from_model(
     transaction_uuid: TransactionUuid,
     rel_type: TransactionRelType,
@@ -1,4 +1,5 @@
 pub mod data_import;
+pub mod exchange_rates;
 //pub mod linker;
 pub mod group;
 pub mod origins;
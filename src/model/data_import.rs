@@ -1,20 +1,25 @@
+pub mod reparse;
 pub mod row;
 pub mod row_item;
 
-use std::{
-    hash::{DefaultHasher, Hash, Hasher},
-    path::PathBuf,
-};
+use std::{borrow::Cow, path::PathBuf};
 
 use chrono::{DateTime, Local};
+use itertools::Itertools;
 use row::ImportRow;
+use sha2::{Digest, Sha256};
 
 use crate::{db::InitUuid, uuid_impls};
 
-use super::profiles::ProfileUuid;
+use super::profiles::{
+    error::ProfileError, format::StatementBalance, ParseResult, Profile,
+    ProfileUuid,
+};
 
 pub type ModelDataImport = DataImport;
 
+const HASH_LEN: usize = 32;
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct DataImport {
     pub uuid: DataImportUuid,
@@ -23,6 +28,8 @@ pub struct DataImport {
     pub file_path: PathBuf,
     pub datetime_created: DateTime<Local>,
     pub rows: Vec<ImportRow>,
+    /// Balances of the statements in the file, empty for csv files.
+    pub balances: Vec<StatementBalance>,
 }
 
 uuid_impls!(DataImportUuid);
@@ -33,18 +40,96 @@ impl DataImport {
         file_contents: &str,
         file_path: PathBuf,
     ) -> Self {
-        let mut hasher = DefaultHasher::new();
-        file_contents.hash(&mut hasher);
-        let hash = hasher.finish();
-
         Self {
             uuid: DataImportUuid::init(),
             profile_uuid,
-            file_hash: hash.to_be_bytes().to_vec(),
+            file_hash: Self::hash_contents(file_contents),
             file_path,
             datetime_created: Local::now(),
             rows: vec![],
+            balances: vec![],
+        }
+    }
+
+    pub fn with_balances(mut self, balances: Vec<StatementBalance>) -> Self {
+        self.balances = balances;
+        self
+    }
+
+    /// SHA-256 over the lines of the file joined by `\n`, so the same
+    /// statement saved with other line endings has the same hash.
+    pub fn hash_contents(file_contents: &str) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        for (index, line) in file_contents.lines().enumerate() {
+            if index > 0 {
+                hasher.update(b"\n");
+            }
+            hasher.update(line.as_bytes());
+        }
+        hasher.finalize().to_vec()
+    }
+
+    /// Imports from before the SHA-256 hash stored a hash that changes
+    /// between runs, theirs is computed from the stored rows instead. The
+    /// rows hold every record of the file, margins included.
+    pub fn content_hash(&self) -> Cow<'_, [u8]> {
+        if self.file_hash.len() == HASH_LEN {
+            return Cow::Borrowed(&self.file_hash);
+        }
+        let content = self
+            .rows
+            .iter()
+            .sorted_by_key(|row| row.row_index)
+            .map(|row| row.row_content.as_str())
+            .join("\n");
+        Cow::Owned(Self::hash_contents(&content))
+    }
+
+    /// The earliest import of exactly this file content.
+    pub fn find_same_file<'a>(
+        imports: &'a [DataImport],
+        file_contents: &str,
+    ) -> Option<&'a DataImport> {
+        let hash = Self::hash_contents(file_contents);
+        imports
+            .iter()
+            .filter(|import| import.content_hash().as_ref() == hash.as_slice())
+            .min_by_key(|import| import.datetime_created)
+    }
+
+    /// Parses the stored rows again with `profile`, usually a newer version
+    /// of the one this import was made with. The margins of `profile` are
+    /// applied to the stored rows as if they were the file.
+    pub fn reparse(
+        &self,
+        profile: &Profile,
+    ) -> Result<ParseResult, ProfileError> {
+        let rows = self
+            .rows
+            .iter()
+            .sorted_by_key(|row| row.row_index)
+            .collect_vec();
+        let content =
+            rows.iter().map(|row| row.row_content.as_str()).join("\n");
+        let profile = profile.resolve_headers(&content)?;
+
+        let to_parse = rows
+            .iter()
+            .filter(|row| !profile.is_margin(row.row_index, rows.len()))
+            .map(|row| {
+                ImportRow::new(
+                    row.uuid,
+                    None,
+                    row.row_content.clone(),
+                    row.row_index,
+                    vec![],
+                )
+            })
+            .collect_vec();
+        if to_parse.is_empty() {
+            return Ok(ParseResult::new(vec![], vec![], vec![]));
         }
+        Ok(profile.parse_file(to_parse))
     }
 
     pub fn sort_by_index(&mut self) {
@@ -0,0 +1,298 @@
+use std::collections::HashMap;
+
+use crate::model::{
+    group::GroupUuid,
+    profiles::{
+        error::{ProfileError, RowError},
+        Profile, ProfileUuid,
+    },
+    transactions::{
+        datetime::Datetime,
+        movement::Movement,
+        properties::{OriginType, TransactionProperties},
+        special_content::SpecialContent,
+        text_content::TextContent,
+        Transaction, TransactionUuid,
+    },
+};
+
+use super::{
+    row::ImportRowUuid,
+    row_item::{ContentRef, ImportRowItem},
+    DataImport, DataImportUuid,
+};
+
+/// A stored property that gets the newly parsed values. It keeps its uuid,
+/// so the tags and links of the transaction stay attached.
+#[derive(Debug, Clone)]
+pub struct PropertyUpdate {
+    pub transaction: TransactionUuid,
+    pub row_index: usize,
+    pub before: TransactionProperties,
+    pub after: TransactionProperties,
+}
+
+/// A property the stored parse did not produce, e.g. from a column that was
+/// added in a newer version of the profile.
+#[derive(Debug, Clone)]
+pub struct PropertyAddition {
+    pub transaction: TransactionUuid,
+    pub row: ImportRowUuid,
+    pub item: ImportRowItem,
+    pub property: TransactionProperties,
+}
+
+/// What parsing the stored rows of an import again would change.
+#[derive(Debug, Clone)]
+pub struct ReparseDiff {
+    pub import: DataImportUuid,
+    pub profile: ProfileUuid,
+    pub updates: Vec<PropertyUpdate>,
+    pub additions: Vec<PropertyAddition>,
+    /// Transactions the re-parse does not change.
+    pub unchanged: usize,
+    /// Properties edited by hand, they are never overwritten.
+    pub kept_manual: usize,
+    /// Rows that parse but never had a transaction. They are not imported,
+    /// they may have been left out because they overlapped another import.
+    pub not_imported: Vec<usize>,
+    /// Rows that fail to parse, their transactions are kept as they are.
+    pub row_errors: Vec<RowError>,
+}
+
+impl ReparseDiff {
+    pub fn is_empty(&self) -> bool {
+        self.updates.is_empty() && self.additions.is_empty()
+    }
+}
+
+impl DataImport {
+    /// Compares a re-parse of the stored rows with `profile` to the
+    /// transactions the rows are linked to by their items. `transactions`
+    /// has to contain the transactions of this import.
+    pub fn reparse_diff(
+        &self,
+        profile: &Profile,
+        transactions: &[Transaction],
+    ) -> Result<ReparseDiff, ProfileError> {
+        let by_ref = transactions
+            .iter()
+            .flat_map(|trx| {
+                [
+                    ContentRef::Movement(trx.movement.uuid),
+                    ContentRef::Datetime(trx.datetime.uuid),
+                ]
+                .into_iter()
+                .chain(trx.properties.iter().map(ContentRef::from))
+                .map(move |content_ref| (content_ref, trx))
+            })
+            .collect::<HashMap<_, _>>();
+        let stored = self
+            .rows
+            .iter()
+            .map(|row| (row.uuid, row))
+            .collect::<HashMap<_, _>>();
+
+        let parsed = self.reparse(profile)?;
+        let mut diff = ReparseDiff {
+            import: self.uuid,
+            profile: profile.uuid,
+            updates: vec![],
+            additions: vec![],
+            unchanged: 0,
+            kept_manual: 0,
+            not_imported: vec![],
+            row_errors: parsed.row_errors,
+        };
+
+        // the transactions are in the order of the rows that parsed
+        let parsed_rows = parsed
+            .parsed_rows
+            .into_iter()
+            .filter(|row| row.group_uuid.is_some());
+        for (row, new) in parsed_rows.zip(parsed.rows) {
+            let old = stored.get(&row.uuid).and_then(|stored| {
+                stored
+                    .items
+                    .iter()
+                    .find_map(|item| by_ref.get(&item.parsed_content_ref))
+            });
+            let Some(old) = old else {
+                diff.not_imported.push(row.row_index);
+                continue;
+            };
+
+            let changes = diff.updates.len() + diff.additions.len();
+            let old_properties = keyed(old);
+            for (key, property) in keyed(&new) {
+                match old_properties.iter().find(|(old_key, _)| key.eq(old_key))
+                {
+                    Some((_, old_property)) if is_manual(old_property) => {
+                        diff.kept_manual += 1;
+                    }
+                    Some((_, old_property)) => {
+                        let after = with_identity(property, old_property);
+                        if after.ne(old_property) {
+                            diff.updates.push(PropertyUpdate {
+                                transaction: old.uuid,
+                                row_index: row.row_index,
+                                before: old_property.clone(),
+                                after,
+                            });
+                        }
+                    }
+                    None => {
+                        let content_ref = ContentRef::from(&property);
+                        let Some(item) = row.items.iter().find(|item| {
+                            item.parsed_content_ref == content_ref
+                        }) else {
+                            continue;
+                        };
+                        diff.additions.push(PropertyAddition {
+                            transaction: old.uuid,
+                            row: row.uuid,
+                            item: item.clone(),
+                            property: with_group(
+                                property,
+                                old.movement.group_uuid,
+                            ),
+                        });
+                    }
+                }
+            }
+            if diff.updates.len() + diff.additions.len() == changes {
+                diff.unchanged += 1;
+            }
+        }
+        Ok(diff)
+    }
+}
+
+/// Properties of two parses are paired by what they hold, their uuids
+/// differ with every parse. Repeated labels are told apart by their order.
+fn keyed(trx: &Transaction) -> Vec<((String, usize), TransactionProperties)> {
+    let main = [
+        ("amount".to_owned(), trx.movement.clone().into()),
+        ("datetime".to_owned(), trx.datetime.clone().into()),
+    ];
+    let others = trx
+        .properties
+        .iter()
+        .map(|property| (label(property), property.clone()));
+
+    let mut seen = HashMap::new();
+    main.into_iter()
+        .chain(others)
+        .map(|(label, property)| {
+            let nth = seen.entry(label.clone()).or_insert(0);
+            let key = (label, *nth);
+            *nth += 1;
+            (key, property)
+        })
+        .collect()
+}
+
+fn label(property: &TransactionProperties) -> String {
+    match property {
+        TransactionProperties::Datetime(datetime) => {
+            format!("date {:?}", datetime.role)
+        }
+        TransactionProperties::Movement(_) => "movement".to_owned(),
+        TransactionProperties::Text(text_content) => {
+            format!("text {}", text_content.description.description)
+        }
+        TransactionProperties::Special(special_content) => format!(
+            "{:?} {}",
+            special_content.content_type,
+            special_content.description.description
+        ),
+    }
+}
+
+fn is_manual(property: &TransactionProperties) -> bool {
+    match property {
+        TransactionProperties::Datetime(datetime) => {
+            datetime.origin_type == OriginType::Manual
+        }
+        TransactionProperties::Movement(movement) => {
+            movement.origin_type == OriginType::Manual
+        }
+        TransactionProperties::Text(_) | TransactionProperties::Special(_) => {
+            false
+        }
+    }
+}
+
+/// The newly parsed values under the uuid, group and description of the
+/// stored property. The labels of the keys make sure both are of one kind.
+fn with_identity(
+    new: TransactionProperties,
+    old: &TransactionProperties,
+) -> TransactionProperties {
+    match (new, old) {
+        (
+            TransactionProperties::Datetime(new),
+            TransactionProperties::Datetime(old),
+        ) => Datetime::new(
+            old.uuid,
+            old.origin_type,
+            new.date,
+            new.time,
+            new.timezone,
+            old.group_uuid,
+            new.role,
+        )
+        .into(),
+        (
+            TransactionProperties::Movement(new),
+            TransactionProperties::Movement(old),
+        ) => Movement::new(
+            old.uuid,
+            old.origin_type,
+            new.amount,
+            new.currency,
+            old.group_uuid,
+        )
+        .into(),
+        (
+            TransactionProperties::Text(new),
+            TransactionProperties::Text(old),
+        ) => TextContent::new(
+            old.uuid,
+            new.content,
+            old.description.clone(),
+            old.group_uuid,
+        )
+        .into(),
+        (
+            TransactionProperties::Special(new),
+            TransactionProperties::Special(old),
+        ) => SpecialContent {
+            content: new.content,
+            ..old.clone()
+        }
+        .into(),
+        (new, _) => new,
+    }
+}
+
+fn with_group(
+    mut property: TransactionProperties,
+    group_uuid: GroupUuid,
+) -> TransactionProperties {
+    match &mut property {
+        TransactionProperties::Datetime(datetime) => {
+            datetime.group_uuid = group_uuid
+        }
+        TransactionProperties::Movement(movement) => {
+            movement.group_uuid = group_uuid
+        }
+        TransactionProperties::Text(text_content) => {
+            text_content.group_uuid = group_uuid
+        }
+        TransactionProperties::Special(special_content) => {
+            special_content.group_uuid = group_uuid
+        }
+    }
+    property
+}
@@ -1,4 +1,12 @@
-use crate::{db::InitUuid, model::group::GroupUuid, uuid_impls};
+use sha2::{Digest, Sha256};
+
+use crate::{
+    db::InitUuid,
+    model::{
+        group::GroupUuid, origins::OriginUuid, profiles::tokenizer::Tokenizer,
+    },
+    uuid_impls,
+};
 
 use super::row_item::ImportRowItem;
 
@@ -11,6 +19,9 @@ pub struct ImportRow {
     pub row_content: String,
     pub row_index: usize,
     pub items: Vec<ImportRowItem>,
+    /// Hash of the content within the origin of the import, overlapping
+    /// rows are looked up by it. See [`ImportRow::hash_content`].
+    pub content_hash: Option<Vec<u8>>,
 }
 
 impl ImportRow {
@@ -30,8 +41,29 @@ impl ImportRow {
             row_content,
             row_index,
             items,
+            content_hash: None,
         }
     }
+
+    /// SHA-256 over the origin and the content. Statements of two origins
+    /// may share rows like a salary booking, those are no overlap. Formats
+    /// with a stable id per booking pass that id instead of the content.
+    pub fn hash_content(origin: OriginUuid, row_content: &str) -> Vec<u8> {
+        let mut hasher = Sha256::new();
+        hasher.update(origin.as_bytes());
+        hasher.update(row_content.as_bytes());
+        hasher.finalize().to_vec()
+    }
+
+    pub fn with_hash(
+        mut self,
+        origin: OriginUuid,
+        tokenizer: &Tokenizer,
+    ) -> Self {
+        let identity = tokenizer.identity(&self.row_content);
+        self.content_hash = Some(Self::hash_content(origin, &identity));
+        self
+    }
 }
 
 uuid_impls!(ImportRowUuid);
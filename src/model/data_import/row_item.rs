@@ -89,7 +89,7 @@ impl ImportRowItem {
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ContentRef {
     Datetime(DatetimeUuid),
     Movement(MovementUuid),
@@ -97,3 +97,22 @@ pub enum ContentRef {
     Special(SpecialContentUuid),
     None,
 }
+
+impl From<&TransactionProperties> for ContentRef {
+    fn from(value: &TransactionProperties) -> Self {
+        match value {
+            TransactionProperties::Datetime(datetime) => {
+                ContentRef::Datetime(datetime.uuid)
+            }
+            TransactionProperties::Movement(movement) => {
+                ContentRef::Movement(movement.uuid)
+            }
+            TransactionProperties::Text(text_content) => {
+                ContentRef::Text(text_content.uuid)
+            }
+            TransactionProperties::Special(special_content) => {
+                ContentRef::Special(special_content.uuid)
+            }
+        }
+    }
+}
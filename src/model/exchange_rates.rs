@@ -0,0 +1,250 @@
+use std::{collections::BTreeMap, fmt::Display};
+
+use chrono::{Days, NaiveDate};
+
+use super::{
+    profiles::tokenizer::Tokenizer,
+    transactions::{currency::Currency, movement::checked_sum, Transaction},
+};
+
+pub type ModelExchangeRate = ExchangeRate;
+
+/// Rates are stored as fixed point numbers with this many units per 1.0,
+/// which is more precise than the four decimals the ECB publishes.
+pub const RATE_SCALE: i64 = 1_000_000;
+const RATE_DECIMALS: usize = 6;
+
+/// Days a rate is used for after it was published. The ECB skips weekends
+/// and holidays, Good Friday to Easter Monday is the longest gap.
+pub const MAX_RATE_AGE_DAYS: i64 = 4;
+
+/// Units of `currency` one euro was worth on `date`, scaled by
+/// [`RATE_SCALE`]. Euro is the base of the ECB reference rates and is
+/// never stored.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ExchangeRate {
+    pub date: NaiveDate,
+    pub currency: Currency,
+    pub rate: i64,
+}
+
+impl ExchangeRate {
+    pub fn new(date: NaiveDate, currency: Currency, rate: i64) -> Self {
+        Self {
+            date,
+            currency,
+            rate,
+        }
+    }
+}
+
+/// Converts amounts between currencies with the stored daily rates.
+#[derive(Debug, Clone, Default)]
+pub struct ExchangeRates {
+    rates: BTreeMap<Currency, BTreeMap<NaiveDate, i64>>,
+}
+
+impl ExchangeRates {
+    pub fn new<'a>(rates: impl IntoIterator<Item = &'a ExchangeRate>) -> Self {
+        let mut by_currency: BTreeMap<Currency, BTreeMap<NaiveDate, i64>> =
+            BTreeMap::new();
+        for rate in rates {
+            by_currency
+                .entry(rate.currency.clone())
+                .or_default()
+                .insert(rate.date, rate.rate);
+        }
+        Self { rates: by_currency }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rates.is_empty()
+    }
+
+    /// The latest rate on or before `date`, rates are not published on
+    /// weekends and holidays. A rate older than [`MAX_RATE_AGE_DAYS`] is a
+    /// gap in the stored rates and counts as missing.
+    pub fn rate(&self, currency: &Currency, date: NaiveDate) -> Option<i64> {
+        if Currency::default().eq(currency) {
+            return Some(RATE_SCALE);
+        }
+        let oldest = date - Days::new(MAX_RATE_AGE_DAYS as u64);
+        self.rates
+            .get(currency)?
+            .range(oldest..=date)
+            .next_back()
+            .map(|(_, rate)| *rate)
+    }
+
+    /// Converts `cents` of `from` into cents of `to`, rounded half away
+    /// from zero. `None` if a rate is missing for the date.
+    pub fn convert(
+        &self,
+        cents: i64,
+        from: &Currency,
+        to: &Currency,
+        date: NaiveDate,
+    ) -> Option<i64> {
+        if from == to {
+            return Some(cents);
+        }
+        let from_rate = i128::from(self.rate(from, date)?);
+        let to_rate = i128::from(self.rate(to, date)?);
+        if from_rate <= 0 {
+            return None;
+        }
+        let numerator = i128::from(cents) * to_rate * 2;
+        let rounding = numerator.signum() * from_rate;
+        i64::try_from((numerator + rounding) / (from_rate * 2)).ok()
+    }
+
+    /// The amount of the transaction in `to`, using the rate of the day
+    /// it was booked.
+    pub fn convert_transaction(
+        &self,
+        transaction: &Transaction,
+        to: &Currency,
+    ) -> Option<i64> {
+        self.convert(
+            transaction.movement.amount,
+            transaction.currency(),
+            to,
+            transaction.datetime().date_naive(),
+        )
+    }
+
+    /// Sum of all transactions in `to` and the number of transactions
+    /// which were left out because a rate was missing. The sum is `None`
+    /// if it overflows.
+    pub fn total<'a>(
+        &self,
+        transactions: impl IntoIterator<Item = &'a Transaction>,
+        to: &Currency,
+    ) -> (Option<i64>, usize) {
+        let mut missing = 0;
+        let total =
+            checked_sum(transactions.into_iter().filter_map(|transaction| {
+                let converted = self.convert_transaction(transaction, to);
+                if converted.is_none() {
+                    missing += 1;
+                }
+                converted
+            }));
+        (total, missing)
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum ExchangeRateError {
+    Header(String),
+    Row(String),
+}
+
+impl ExchangeRateError {
+    fn header(msg: &str) -> Self {
+        Self::Header(format!("The header of the rates file is invalid: {msg}"))
+    }
+
+    fn row(row: &str, msg: &str) -> Self {
+        Self::Row(format!("The row {row} could not be parsed: {msg}"))
+    }
+}
+
+impl Display for ExchangeRateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Header(msg) | Self::Row(msg) => write!(f, "{msg}"),
+        }
+    }
+}
+
+/// Parses the daily reference rates csv of the ECB, one row per day and one
+/// column per currency after the leading `Date` column. Missing rates are
+/// written as `N/A` or left empty.
+pub fn parse_ecb_csv(
+    content: &str,
+) -> Result<Vec<ExchangeRate>, ExchangeRateError> {
+    let tokenizer = Tokenizer::new(',', Some('"'));
+    let mut records = tokenizer
+        .records(content)
+        .into_iter()
+        .filter(|record| !record.trim().is_empty());
+
+    let header = records
+        .next()
+        .ok_or_else(|| ExchangeRateError::header("the file is empty"))?;
+    let header = tokenizer
+        .fields(&header)
+        .map_err(|err| ExchangeRateError::header(&err.to_string()))?;
+    let mut header = header.iter().map(|field| field.trim());
+    if !header
+        .next()
+        .is_some_and(|first| first.eq_ignore_ascii_case("date"))
+    {
+        return Err(ExchangeRateError::header("the first column is not Date"));
+    }
+    let currencies = header
+        .map(|code| match code {
+            "" => Ok(None),
+            code => code
+                .parse::<Currency>()
+                .map(Some)
+                .map_err(|err| ExchangeRateError::header(&err)),
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let mut rates = vec![];
+    for record in records {
+        let fields = tokenizer
+            .fields(&record)
+            .map_err(|err| ExchangeRateError::row(&record, &err.to_string()))?;
+        let mut fields = fields.iter().map(|field| field.trim());
+        let date = fields
+            .next()
+            .and_then(parse_ecb_date)
+            .ok_or_else(|| ExchangeRateError::row(&record, "invalid date"))?;
+        for (currency, value) in currencies.iter().zip(fields) {
+            let Some(currency) = currency else {
+                continue;
+            };
+            if value.is_empty() || value.eq_ignore_ascii_case("N/A") {
+                continue;
+            }
+            let rate = parse_rate(value).ok_or_else(|| {
+                ExchangeRateError::row(
+                    &record,
+                    &format!("invalid rate {value}"),
+                )
+            })?;
+            rates.push(ExchangeRate::new(date, currency.clone(), rate));
+        }
+    }
+    Ok(rates)
+}
+
+/// The download uses `2024-01-31`, older files `31 January 2024`.
+fn parse_ecb_date(str: &str) -> Option<NaiveDate> {
+    NaiveDate::parse_from_str(str, "%Y-%m-%d")
+        .or_else(|_| NaiveDate::parse_from_str(str, "%d %B %Y"))
+        .ok()
+}
+
+/// Parses a positive decimal like `1.0832` exactly into [`RATE_SCALE`].
+fn parse_rate(str: &str) -> Option<i64> {
+    let (int, frac) = str.split_once('.').unwrap_or((str, ""));
+    let all_digits = |part: &str| part.chars().all(|c| c.is_ascii_digit());
+    if int.is_empty() || !all_digits(int) || !all_digits(frac) {
+        return None;
+    }
+    let (frac, rest) = frac.split_at(frac.len().min(RATE_DECIMALS));
+    if rest.chars().any(|c| c != '0') {
+        return None;
+    }
+    let frac = format!("{frac:0<RATE_DECIMALS$}");
+    let rate = int
+        .parse::<i64>()
+        .ok()?
+        .checked_mul(RATE_SCALE)?
+        .checked_add(frac.parse().ok()?)?;
+    (rate > 0).then_some(rate)
+}
@@ -27,7 +27,7 @@ pub fn evaluate_if_transfer_link(
 }
 
 fn amounts_are_opposites(left: &ExpenseRecord, right: &ExpenseRecord) -> bool {
-    (left.amount() * -1).eq(right.amount())
+    left.amount().checked_neg().eq(&Some(*right.amount()))
 }
 
 fn create_transfer_possible_link(
@@ -1,14 +1,30 @@
 pub mod builder;
 pub mod columns;
+pub mod detection;
+pub mod encoding;
 pub mod error;
+pub mod format;
+pub mod history;
+pub mod inference;
+pub mod library;
+pub mod portable;
+pub mod tokenizer;
 
 use chrono::{DateTime, Local};
-use columns::{DateTimeColumn, ExpenseColumn, ParsableWrapper};
-use error::ProfileError;
+use columns::{
+    CurrencyColumn, DateTimeColumn, ExpenseColumn, ParsableWrapper, RowCell,
+};
+use encoding::FileEncoding;
+use error::{InColumn, ProfileError, RowError};
+use format::FileFormat;
 use itertools::Itertools;
 use sea_orm::{DeriveActiveEnum, EnumIter};
 use sea_query::StringLen;
-use std::collections::HashMap;
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
+use tokenizer::Tokenizer;
 use uuid::Uuid;
 
 use crate::{
@@ -21,7 +37,7 @@ use super::{
     data_import::{row::ImportRow, row_item::ImportRowItem},
     origins::Origin,
     tags::Tag,
-    transactions::Transaction,
+    transactions::{properties::TransactionProperties, Transaction},
 };
 
 pub type ModelProfile = Profile;
@@ -32,14 +48,28 @@ pub struct Profile {
     pub name: String,
     pub margins: (usize, usize),
     pub delimiter: char,
+    pub quote: Option<char>,
+    pub encoding: FileEncoding,
+    pub format: FileFormat,
+    /// Sheet of a workbook, empty for the first one.
+    pub sheet: String,
     pub amount: ExpenseColumn,
     pub datetime: DateTimeColumn,
+    pub currency: CurrencyColumn,
     pub other_data: HashMap<usize, ParsableWrapper>,
+    /// Header labels the columns are bound to, columns without a label are
+    /// only found by their position.
+    pub headers: HashMap<usize, String>,
     pub width: usize,
     pub default_tags: Vec<Tag>,
     pub origin: Origin,
     pub state: State,
     pub datetime_created: DateTime<Local>,
+    /// Starts at 1 and counts up with every edit.
+    pub version: u32,
+    /// The version this one replaced, imports keep pointing to the version
+    /// that produced them.
+    pub previous: Option<ProfileUuid>,
 }
 
 uuid_impls!(ProfileUuid);
@@ -51,6 +81,9 @@ pub enum State {
     Active,
     #[sea_orm(string_value = "Deleted")]
     Deleted,
+    /// Replaced by a newer version, no longer offered for new imports.
+    #[sea_orm(string_value = "Superseded")]
+    Superseded,
 }
 
 impl Profile {
@@ -59,141 +92,301 @@ impl Profile {
         name: String,
         amount: ExpenseColumn,
         datetime: DateTimeColumn,
+        currency: CurrencyColumn,
         other_data: Vec<(usize, ParsableWrapper)>,
+        headers: HashMap<usize, String>,
         margins: (usize, usize),
         delimiter: char,
+        quote: Option<char>,
+        encoding: FileEncoding,
+        format: FileFormat,
+        sheet: String,
         default_tags: Vec<Tag>,
         origin: Origin,
     ) -> Self {
-        let width = [
-            other_data.iter().map(|(pos, _)| *pos).collect_vec(),
-            amount.get_positions(),
-            datetime.get_positions(),
-        ]
-        .concat()
-        .into_iter()
-        .max()
-        .unwrap();
-
         let other_data = other_data.into_iter().collect::<HashMap<_, _>>();
+        let width =
+            Self::max_position(&amount, &datetime, &currency, &other_data);
 
         Self {
             uuid: Uuid::new_v4().into(),
             name,
             margins,
             delimiter,
+            quote,
+            encoding,
+            format,
+            sheet,
             amount,
             datetime,
+            currency,
             other_data,
+            headers,
             width,
             default_tags: default_tags.into_iter().unique().collect_vec(),
             origin,
             state: State::Active,
             datetime_created: Local::now(),
+            version: 1,
+            previous: None,
         }
     }
 
-    pub fn parse_file(
-        &self,
-        mut rows: Vec<ImportRow>,
-    ) -> Result<ParseResult, ProfileError> {
-        assert!(!rows.is_empty());
-        assert!(!rows.first().unwrap().row_content.is_empty());
+    /// Makes this the version after `previous`.
+    pub fn succeeding(mut self, previous: ProfileUuid, version: u32) -> Self {
+        self.previous = Some(previous);
+        self.version = version + 1;
+        self
+    }
 
-        let (transactions, mut profile_errors) = rows
-            .iter_mut()
-            .enumerate()
-            .fold((vec![], vec![]), |(mut trxs, mut errs), (_, row)| {
-                //TODO: dont forget to check that this is correct
+    /// Parses every row on its own, rows that fail are kept in the result
+    /// without any items so that the rest of the file can still be imported.
+    pub fn parse_file(&self, mut rows: Vec<ImportRow>) -> ParseResult {
+        if rows.is_empty() {
+            return ParseResult::new(vec![], vec![], vec![]);
+        }
+
+        let (transactions, row_errors) = rows.iter_mut().fold(
+            (vec![], vec![]),
+            |(mut trxs, mut errs), row| {
                 match self.parse_row(row) {
                     Ok(trx) => trxs.push(trx),
                     Err(err) => errs.push(err),
                 }
                 (trxs, errs)
-            });
+            },
+        );
 
-        if !profile_errors.is_empty() {
-            Err(profile_errors.remove(0))
-        } else {
-            Ok(ParseResult::new(transactions, rows))
-        }
+        ParseResult::new(transactions, rows, row_errors)
     }
 
+    /// Only touches the row once every column was parsed successfully.
     fn parse_row(
         &self,
         row: &mut ImportRow,
-    ) -> Result<(Transaction, Group), ProfileError> {
+    ) -> Result<(Transaction, Group), RowError> {
         assert!(row.items.is_empty());
         // ToDo actually creat whole group here
         let group = Group::init();
 
-        let mut row_items = row
-            .row_content
-            .split(self.delimiter)
-            .map(str::to_owned)
+        let mut row_items = self
+            .tokenizer()
+            .cells(&row.row_content)
+            .map_err(|err| RowError::new(row, err))?
+            .into_iter()
             .enumerate()
-            .map(ImportRowItem::init)
+            .map(|(index, (content, cell))| {
+                (ImportRowItem::init((index, content)), cell)
+            })
             .collect_vec();
-        if row_items.len() < self.width {
-            return Err(ProfileError::width(self.width, row_items.len()));
+        // the width is the highest position, not the number of columns
+        if row_items.len() <= self.width {
+            return Err(RowError::new(
+                row,
+                ProfileError::width(self.width + 1, row_items.len()),
+            ));
         }
 
         let mut transac_builder = TransactionBuilder::init();
+        let mut items = vec![];
 
-        {
+        let currency = {
             let mut get_from_vec =
-                |pos: usize| -> ImportRowItem { row_items.remove(pos) };
+                |pos: usize| -> Result<RowCell, ProfileError> {
+                    let index = row_items
+                        .iter()
+                        .position(|(item, _)| item.item_index == pos)
+                        .ok_or(ProfileError::missing_column(pos))?;
+                    Ok(row_items.remove(index))
+                };
+
+            let (currency, mut currency_items) = self
+                .currency
+                .parse_str(&mut get_from_vec)
+                .map_err(|err| RowError::in_column(row, err))?;
 
-            let movement =
-                self.amount.parse_str(group.uuid, &mut get_from_vec)?;
-            let _ = transac_builder.movement.insert(movement.0);
+            let movement = self
+                .amount
+                .parse_str(group.uuid, &mut get_from_vec)
+                .map_err(|err| RowError::in_column(row, err))?;
+            currency_items
+                .iter_mut()
+                .for_each(|item| item.set_movement_ref(movement.0.uuid));
+            let _ = transac_builder
+                .movement
+                .insert(movement.0.with_currency(currency.clone()));
 
-            let datetime =
-                self.datetime.parse_str(group.uuid, &mut get_from_vec)?;
+            let datetime = self
+                .datetime
+                .parse_str(group.uuid, &mut get_from_vec)
+                .map_err(|err| RowError::in_column(row, err))?;
             let _ = transac_builder.datetime.insert(datetime.0);
 
-            row.items.extend(movement.1);
-            row.items.extend(datetime.1);
-        }
+            items.extend(movement.1);
+            items.extend(currency_items);
+            items.extend(datetime.1);
+            currency
+        };
 
-        let (props, items) = row_items
-            .into_iter()
-            .filter_map(|item| {
-                self.other_data
-                    .get(&item.item_index)
-                    .map(|parser| (item, parser))
-            })
-            .fold(
-                (vec![], vec![]),
-                |(mut props, mut items), (mut item, parser)| {
-                    let property =
-                        parser.to_property(group.uuid, &item.content).unwrap();
-                    item.set_property_ref(&property);
-                    props.push(property);
-                    items.push(item);
-                    (props, items)
-                },
-            );
-
-        transac_builder.properties.extend(props);
-        row.items = items;
+        for (mut item, cell) in row_items {
+            let Some(parser) = self.other_data.get(&item.item_index) else {
+                continue;
+            };
+            // a statement leaves the fields its format does not have empty
+            if self.format.is_statement() && item.content.is_empty() {
+                continue;
+            }
+            let property = parser
+                .to_property(group.uuid, &item.content, cell)
+                .in_column(item.item_index, parser)
+                .map_err(|err| RowError::in_column(row, err))?;
+            // additional amounts are in the currency of the main amount
+            let property = match property {
+                TransactionProperties::Movement(movement) => {
+                    movement.with_currency(currency.clone()).into()
+                }
+                property => property,
+            };
+            item.set_property_ref(&property);
+            transac_builder.properties.push(property);
+            items.push(item);
+        }
 
         transac_builder.feed_tags(self.default_tags.clone());
 
+        row.items = items;
         row.group_uuid = Some(group.uuid);
 
         Ok((transac_builder.build(), group))
     }
 
+    fn max_position(
+        amount: &ExpenseColumn,
+        datetime: &DateTimeColumn,
+        currency: &CurrencyColumn,
+        other_data: &HashMap<usize, ParsableWrapper>,
+    ) -> usize {
+        [
+            other_data.keys().copied().collect_vec(),
+            amount.get_positions(),
+            datetime.get_positions(),
+            currency.get_positions(),
+        ]
+        .concat()
+        .into_iter()
+        .max()
+        .unwrap()
+    }
+
+    /// Moves the columns bound to a header label to where that label is in
+    /// the header of this file. The header is the last row of the top
+    /// margin. Without bound labels the profile is returned unchanged.
+    pub fn resolve_headers(&self, content: &str) -> Result<Self, ProfileError> {
+        if self.headers.is_empty() {
+            return Ok(self.clone());
+        }
+        let tokenizer = self.tokenizer();
+        let header = self
+            .margins
+            .0
+            .checked_sub(1)
+            .and_then(|index| tokenizer.records(content).into_iter().nth(index))
+            .ok_or_else(|| ProfileError::missing_header_row(self.margins.0))?;
+        let header = tokenizer
+            .fields(&header)?
+            .into_iter()
+            .map(|label| label.trim().to_owned())
+            .collect_vec();
+
+        let mut missing = vec![];
+        let moved = self
+            .headers
+            .iter()
+            .filter_map(|(pos, label)| {
+                match header.iter().position(|found| found.eq(label)) {
+                    Some(found) => Some((*pos, found)),
+                    None => {
+                        missing.push(label.clone());
+                        None
+                    }
+                }
+            })
+            .collect::<HashMap<_, _>>();
+        if !missing.is_empty() {
+            return Err(ProfileError::missing_headers(&missing));
+        }
+
+        let map = |pos: usize| moved.get(&pos).copied().unwrap_or(pos);
+        // a moved column must not land on another column
+        let mut taken = HashSet::new();
+        let collisions = [
+            self.other_data.keys().copied().collect_vec(),
+            self.amount.get_positions(),
+            self.datetime.get_positions(),
+            self.currency.get_positions(),
+        ]
+        .concat()
+        .into_iter()
+        .map(map)
+        .filter(|pos| !taken.insert(*pos))
+        .sorted()
+        .dedup()
+        .collect_vec();
+        if !collisions.is_empty() {
+            return Err(ProfileError::header_collision(&collisions));
+        }
+
+        let mut profile = self.clone();
+        profile.amount.map_positions(map);
+        profile.datetime.map_positions(map);
+        profile.currency.map_positions(map);
+        profile.other_data = self
+            .other_data
+            .iter()
+            .map(|(pos, wrapper)| (map(*pos), wrapper.clone()))
+            .collect();
+        profile.headers = self
+            .headers
+            .iter()
+            .map(|(pos, label)| (map(*pos), label.clone()))
+            .collect();
+        profile.width = Self::max_position(
+            &profile.amount,
+            &profile.datetime,
+            &profile.currency,
+            &profile.other_data,
+        );
+        Ok(profile)
+    }
+
+    pub fn tokenizer(&self) -> Tokenizer {
+        Tokenizer::new(self.delimiter, self.quote).with_format(self.format)
+    }
+
+    /// Reads a file in the encoding of this profile, or the sheet of this
+    /// profile for a workbook.
+    pub fn read_file(&self, path: &Path) -> Result<String, ProfileError> {
+        self.format.read(path, self.encoding, &self.sheet)
+    }
+
+    /// The margins that are cut off the file. Every record of a statement
+    /// format is a booking, so they have none whatever the profile says.
+    pub fn effective_margins(&self) -> (usize, usize) {
+        match self.format.is_statement() {
+            true => (0, 0),
+            false => self.margins,
+        }
+    }
+
     pub fn is_margin(&self, index: usize, total_len: usize) -> bool {
         self.is_top_margin(index) || self.is_bottom_margin(index, total_len)
     }
 
     pub fn is_top_margin(&self, index: usize) -> bool {
-        index < self.margins.0
+        index < self.effective_margins().0
     }
     pub fn is_bottom_margin(&self, index: usize, total_len: usize) -> bool {
-        index >= (total_len - self.margins.1)
+        index >= (total_len - self.effective_margins().1)
     }
 }
 
@@ -201,12 +394,14 @@ pub struct ParseResult {
     pub(crate) rows: Vec<Transaction>,
     pub(crate) groups: Vec<Group>,
     pub(crate) parsed_rows: Vec<ImportRow>,
+    pub(crate) row_errors: Vec<RowError>,
 }
 
 impl ParseResult {
     pub fn new(
         parses: Vec<(Transaction, Group)>,
         parsed_rows: Vec<ImportRow>,
+        row_errors: Vec<RowError>,
     ) -> Self {
         let (rows, groups) = parses.into_iter().fold(
             (vec![], vec![]),
@@ -220,6 +415,7 @@ impl ParseResult {
             rows,
             groups,
             parsed_rows,
+            row_errors,
         }
     }
 }
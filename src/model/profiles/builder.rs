@@ -1,4 +1,7 @@
-use std::collections::HashSet;
+use std::{
+    collections::{HashMap, HashSet},
+    path::Path,
+};
 
 use crate::{
     db::InitUuid,
@@ -6,8 +9,12 @@ use crate::{
 };
 
 use super::{
-    error::ProfileError, DateTimeColumn, ExpenseColumn, ParsableWrapper,
-    Profile,
+    columns::CurrencyColumn,
+    encoding::FileEncoding,
+    error::ProfileError,
+    format::{FileFormat, StatementColumns},
+    tokenizer::Tokenizer,
+    DateTimeColumn, ExpenseColumn, ParsableWrapper, Profile, ProfileUuid,
 };
 
 // ToDo merge with the other profile builder
@@ -17,11 +24,18 @@ pub struct CreateProfileBuilder {
     col_positions: HashSet<usize>,
     expense_col: Option<ExpenseColumn>,
     datetime_col: Option<DateTimeColumn>,
+    currency_col: CurrencyColumn,
     other_cols: Vec<(usize, ParsableWrapper)>,
+    headers: HashMap<usize, String>,
     margins: Option<(usize, usize)>,
     delimiter: Option<char>,
+    quote: Option<char>,
+    encoding: FileEncoding,
+    format: FileFormat,
+    sheet: String,
     default_tags: Vec<Tag>,
     origin_name: Option<Origin>,
+    previous: Option<(ProfileUuid, u32)>,
 }
 
 impl CreateProfileBuilder {
@@ -46,6 +60,11 @@ impl CreateProfileBuilder {
         self.datetime_col = Some(val);
         Ok(())
     }
+    pub fn currency_col(&mut self, val: CurrencyColumn) -> Result<(), ()> {
+        self.add_many_pos(val.get_positions())?;
+        self.currency_col = val;
+        Ok(())
+    }
     pub fn other_cols(
         &mut self,
         vals: Vec<(usize, ParsableWrapper)>,
@@ -54,6 +73,27 @@ impl CreateProfileBuilder {
         self.other_cols = vals;
         Ok(())
     }
+    /// Statement formats always map the fields of their entries the same
+    /// way, the columns and margins set before are replaced. Needs the
+    /// format first.
+    pub fn statement_columns(&mut self) -> Result<(), ()> {
+        let StatementColumns {
+            amount,
+            datetime,
+            currency,
+            other_data,
+        } = StatementColumns::new(self.format);
+        self.margins = Some((0, 0));
+        self.col_positions.clear();
+        self.expense_col(amount)?;
+        self.datetime_col(datetime)?;
+        self.currency_col(currency)?;
+        self.other_cols(other_data)
+    }
+    pub fn headers(mut self, headers: HashMap<usize, String>) -> Self {
+        self.headers = headers;
+        self
+    }
     pub fn margins(mut self, top: usize, btm: usize) -> Self {
         self.margins = Some((top, btm));
         self
@@ -62,6 +102,22 @@ impl CreateProfileBuilder {
         self.delimiter = Some(delimiter);
         self
     }
+    pub fn quote(mut self, quote: Option<char>) -> Self {
+        self.quote = quote;
+        self
+    }
+    pub fn encoding(mut self, encoding: FileEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+    pub fn format(mut self, format: FileFormat) -> Self {
+        self.format = format;
+        self
+    }
+    pub fn sheet(mut self, sheet: String) -> Self {
+        self.sheet = sheet;
+        self
+    }
     pub fn default_tags(mut self, default_tags: Vec<Tag>) -> Self {
         self.default_tags = default_tags;
         self
@@ -69,6 +125,10 @@ impl CreateProfileBuilder {
     pub fn origin(&mut self, origin: Origin) {
         self.origin_name = Some(origin);
     }
+    pub fn previous(mut self, previous: Option<(ProfileUuid, u32)>) -> Self {
+        self.previous = previous;
+        self
+    }
     pub fn get_from_pos(&self, pos: usize) -> Option<ParsableWrapper> {
         if !self.col_positions.contains(&pos) {
             return None;
@@ -86,6 +146,7 @@ impl CreateProfileBuilder {
                         .datetime_col
                         .clone()
                         .and_then(|v| v.get_from_pos(pos)))
+                    .or(self.currency_col.clone().get_from_pos(pos))
             })
     }
     pub fn build(self) -> Result<Profile, ()> {
@@ -104,22 +165,45 @@ impl CreateProfileBuilder {
                 Some(margins),
                 Some(delimiter),
                 Some(origin_name),
-            ) => Ok(Profile::new(
-                name,
-                expense_col,
-                datetime_col,
-                self.other_cols,
-                margins,
-                delimiter,
-                self.default_tags,
-                origin_name,
-            )),
+            ) => {
+                let profile = Profile::new(
+                    name,
+                    expense_col,
+                    datetime_col,
+                    self.currency_col,
+                    self.other_cols,
+                    // labels of unused columns would only fail the resolving
+                    self.headers
+                        .into_iter()
+                        .filter(|(pos, label)| {
+                            self.col_positions.contains(pos)
+                                && !label.is_empty()
+                        })
+                        .collect(),
+                    margins,
+                    delimiter,
+                    self.quote,
+                    self.encoding,
+                    self.format,
+                    self.sheet,
+                    self.default_tags,
+                    origin_name,
+                );
+                Ok(match self.previous {
+                    Some((previous, version)) => {
+                        profile.succeeding(previous, version)
+                    }
+                    None => profile,
+                })
+            }
             _ => Err(()),
         }
     }
 
     fn add_many_pos(&mut self, pos: Vec<usize>) -> Result<(), ()> {
-        if pos.iter().any(|pos| self.col_positions.contains(pos)) {
+        // two column variants like Split or Indicated need distinct positions
+        let distinct = pos.iter().collect::<HashSet<_>>().len() == pos.len();
+        if !distinct || pos.iter().any(|pos| self.col_positions.contains(pos)) {
             return Err(());
         }
         for pos in pos {
@@ -142,18 +226,34 @@ impl CreateProfileBuilder {
             state.delimiter.chars().collect::<Vec<_>>().first()
         {
             builder = builder.delimiter(*delimiter);
+        } else if !state.format.is_delimited() {
+            // only csv is split by it, but every profile has one
+            builder = builder.delimiter(';');
         }
+        builder = builder
+            .quote(state.quote.chars().next())
+            .encoding(state.encoding)
+            .format(state.format)
+            .sheet(state.sheet.clone());
 
-        if let Some(expense_col) = &state.expense_col {
-            builder.expense_col(expense_col.clone())?;
-        }
-        if let Some(datetime_col) = &state.datetime_col {
-            builder.datetime_col(datetime_col.clone())?;
-        }
+        if state.format.is_statement() {
+            builder.statement_columns()?;
+        } else {
+            if let Some(expense_col) = &state.expense_col {
+                builder.expense_col(expense_col.clone())?;
+            }
+            if let Some(datetime_col) = &state.datetime_col {
+                builder.datetime_col(datetime_col.clone())?;
+            }
+            builder.currency_col(state.currency_col.clone())?;
 
-        builder.other_cols(state.other_cols.clone())?;
+            builder.other_cols(state.other_cols.clone())?;
+        }
 
-        Ok(builder.default_tags(state.default_tags.clone()))
+        Ok(builder
+            .headers(state.headers.clone())
+            .default_tags(state.default_tags.clone())
+            .previous(state.previous))
     }
     pub fn intermediate_parse(
         &self,
@@ -163,7 +263,9 @@ impl CreateProfileBuilder {
     ) -> Result<IntermediateParse, ProfileError> {
         let group_uuid = GroupUuid::init();
 
-        if let Some((top, btm)) = self.margins {
+        if let Some((top, btm)) =
+            self.margins.filter(|_| !self.format.is_statement())
+        {
             if index < top || index >= (total_len - btm) {
                 return Ok(IntermediateParse::None);
             }
@@ -171,27 +273,22 @@ impl CreateProfileBuilder {
         let Some(delimiter) = self.delimiter else {
             return Ok(IntermediateParse::Rows(Ok(row)));
         };
-        let mut row = row
-            .split(delimiter)
-            .map(|val| Ok(val.to_string()))
+        let cells = Tokenizer::new(delimiter, self.quote)
+            .with_format(self.format)
+            .cells(&row)?;
+        let mut row = cells
+            .iter()
+            .map(|(str, _)| Ok(str.clone()))
             .collect::<Vec<_>>();
 
-        let mut other_cols = self.other_cols.clone();
-        if let Some(expense_col) = &self.expense_col {
-            other_cols.extend(expense_col.clone().into_cols());
-        }
-        if let Some(datetime_col) = &self.datetime_col {
-            other_cols.extend(datetime_col.clone().into_cols());
-        }
-
-        for (pos, el) in other_cols {
-            let Some(Ok(str)) = row.get(pos) else {
+        for (pos, el) in self.columns() {
+            let Some((str, cell)) = cells.get(pos) else {
                 return Err(ProfileError::ColumnWidth(format!(
                     "{pos} is not in bounds"
                 )));
             };
             let new_str = el
-                .to_property(group_uuid, str.as_str())
+                .to_property(group_uuid, str, *cell)
                 .map(|val| format!("{val:?}"))
                 .map_err(|err| format!("{err:?}"));
 
@@ -201,6 +298,30 @@ impl CreateProfileBuilder {
 
         Ok(IntermediateParse::RowsAndCols(row))
     }
+    pub fn read_file(&self, path: &Path) -> Result<String, ProfileError> {
+        self.format.read(path, self.encoding, &self.sheet)
+    }
+    pub fn records(&self, content: &str) -> Vec<String> {
+        match self.delimiter {
+            Some(delimiter) => Tokenizer::new(delimiter, self.quote)
+                .with_format(self.format)
+                .records(content),
+            None => content.lines().map(str::to_owned).collect(),
+        }
+    }
+
+    /// All columns set so far with their position.
+    fn columns(&self) -> Vec<(usize, ParsableWrapper)> {
+        let mut columns = self.other_cols.clone();
+        if let Some(expense_col) = &self.expense_col {
+            columns.extend(expense_col.clone().into_cols());
+        }
+        if let Some(datetime_col) = &self.datetime_col {
+            columns.extend(datetime_col.clone().into_cols());
+        }
+        columns.extend(self.currency_col.clone().into_cols());
+        columns
+    }
 }
 
 #[derive(Debug, Clone, PartialEq)]
@@ -216,11 +337,22 @@ pub struct IntermediateProfileState {
     pub margin_top: usize,
     pub margin_btm: usize,
     pub delimiter: String,
+    pub quote: String,
+    pub encoding: FileEncoding,
+    pub format: FileFormat,
+    /// Sheet of a workbook, empty for the first one.
+    pub sheet: String,
     pub expense_col: Option<ExpenseColumn>,
     pub datetime_col: Option<DateTimeColumn>,
+    pub currency_col: CurrencyColumn,
     pub other_cols: Vec<(usize, ParsableWrapper)>,
+    /// Empty when the columns are only mapped by position.
+    pub headers: HashMap<usize, String>,
     pub default_tags: Vec<Tag>,
     pub origin: Option<Origin>,
+    /// Uuid and version of the profile being edited, saving creates the
+    /// next version of it.
+    pub previous: Option<(ProfileUuid, u32)>,
 }
 
 impl IntermediateProfileState {
@@ -230,15 +362,22 @@ impl IntermediateProfileState {
             margin_top: profile.margins.0,
             margin_btm: profile.margins.1,
             delimiter: profile.delimiter.to_string(),
+            quote: profile.quote.map(String::from).unwrap_or_default(),
+            encoding: profile.encoding,
+            format: profile.format,
+            sheet: profile.sheet.clone(),
             expense_col: Some(profile.amount.clone()),
             datetime_col: Some(profile.datetime.clone()),
+            currency_col: profile.currency.clone(),
             other_cols: profile
                 .other_data
                 .iter()
                 .map(|(a, b)| (*a, ParsableWrapper::init_from(b)))
                 .collect(),
+            headers: profile.headers.clone(),
             default_tags: profile.default_tags.clone(),
             origin: Some(profile.origin.clone()),
+            previous: Some((profile.uuid, profile.version)),
         }
     }
 }
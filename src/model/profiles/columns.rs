@@ -4,10 +4,15 @@ pub mod time;
 
 use std::mem;
 
-use money::{Expense, Income, Movement, NumberFormat, PosExpense};
+use money::{
+    CurrencyCode, DebitCredit, Expense, Income, Movement, NumberFormat,
+    PosExpense,
+};
 use other::{Description, Special};
 use serde::{Deserialize, Serialize};
-use time::{ExpenseDate, ExpenseDateTime, ExpenseTime};
+use time::{
+    ExpenseDate, ExpenseDateTime, ExpenseTime, SecondaryDate, StandaloneTime,
+};
 use tracing::info;
 
 use crate::model::{
@@ -15,20 +20,33 @@ use crate::model::{
     group::GroupUuid,
     transactions::{
         content_description::ContentDescription,
+        currency::Currency,
         datetime::{Datetime, ModelDatetime},
         movement::ModelMovement,
         properties::TransactionProperties,
     },
 };
 
-use super::error::ProfileError;
+use super::{
+    error::{ColumnError, InColumn, ProfileError},
+    tokenizer::Cell,
+};
+
+/// An item of a row together with the kind of cell it was read from.
+pub type RowCell = (ImportRowItem, Cell);
 
 pub trait Parser<T> {
     fn parse_str(&self, str: &str) -> Result<T, ProfileError>;
+    /// Numbers and dates of a sheet are read as they are, parsers that
+    /// have a format for them override this.
+    fn parse_cell(&self, str: &str, _cell: Cell) -> Result<T, ProfileError> {
+        self.parse_str(str)
+    }
     fn to_property(
         &self,
         group_uuid: GroupUuid,
         str: &str,
+        cell: Cell,
     ) -> Result<TransactionProperties, ProfileError>;
 }
 
@@ -37,6 +55,8 @@ pub enum ExpenseColumn {
     Split((usize, Income), (usize, Expense)),
     Combined(usize, Movement),
     OnlyExpense(usize, PosExpense),
+    /// Unsigned amount with its direction in a separate column.
+    Indicated((usize, Movement), (usize, DebitCredit)),
 }
 
 impl ExpenseColumn {
@@ -61,6 +81,20 @@ impl ExpenseColumn {
     pub fn only_expense(expense: usize, format: &NumberFormat) -> Self {
         Self::OnlyExpense(expense, format.into())
     }
+    pub fn default_indicated() -> Self {
+        Self::Indicated(
+            (usize::default(), NumberFormat::default().into()),
+            (usize::default(), DebitCredit::default()),
+        )
+    }
+    pub fn indicated(
+        amount: usize,
+        indicator: usize,
+        format: &NumberFormat,
+        debit_credit: DebitCredit,
+    ) -> Self {
+        Self::Indicated((amount, format.into()), (indicator, debit_credit))
+    }
 
     pub fn get_from_pos(self, pos: usize) -> Option<ParsableWrapper> {
         self.into_cols()
@@ -70,11 +104,25 @@ impl ExpenseColumn {
     }
     pub fn get_positions(&self) -> Vec<usize> {
         match self {
-            ExpenseColumn::Split((pos1, _), (pos2, _)) => vec![*pos1, *pos2],
+            ExpenseColumn::Split((pos1, _), (pos2, _))
+            | ExpenseColumn::Indicated((pos1, _), (pos2, _)) => {
+                vec![*pos1, *pos2]
+            }
             ExpenseColumn::Combined(pos, _)
             | ExpenseColumn::OnlyExpense(pos, _) => vec![*pos],
         }
     }
+    pub fn map_positions(&mut self, map: impl Fn(usize) -> usize) {
+        match self {
+            ExpenseColumn::Split((pos1, _), (pos2, _))
+            | ExpenseColumn::Indicated((pos1, _), (pos2, _)) => {
+                *pos1 = map(*pos1);
+                *pos2 = map(*pos2);
+            }
+            ExpenseColumn::Combined(pos, _)
+            | ExpenseColumn::OnlyExpense(pos, _) => *pos = map(*pos),
+        }
+    }
     pub fn into_cols(self) -> Vec<(usize, ParsableWrapper)> {
         match self {
             ExpenseColumn::Combined(pos, val) => {
@@ -87,20 +135,34 @@ impl ExpenseColumn {
             ExpenseColumn::OnlyExpense(pos, val) => {
                 vec![(pos, ParsableWrapper::PosExpense(val))]
             }
+            ExpenseColumn::Indicated((pos1, val1), (pos2, val2)) => vec![
+                (pos1, ParsableWrapper::Movement(val1)),
+                (pos2, ParsableWrapper::DebitCredit(val2)),
+            ],
         }
     }
 
     pub fn parse_str(
         &self,
         group_uuid: GroupUuid,
-        value_getter: &mut impl FnMut(usize) -> ImportRowItem,
-    ) -> Result<(ModelMovement, Vec<ImportRowItem>), ProfileError> {
+        value_getter: &mut impl FnMut(usize) -> Result<RowCell, ProfileError>,
+    ) -> Result<(ModelMovement, Vec<ImportRowItem>), ColumnError> {
         match self {
             ExpenseColumn::Split((pos1, income), (pos2, expense)) => {
-                let mut item_1 = value_getter(*pos1);
-                let mut item_2 = value_getter(*pos2);
-                let amount = income.parse_str(&item_1.content)?
-                    + expense.parse_str(&item_2.content)?;
+                let (mut item_1, cell_1) =
+                    value_getter(*pos1).in_column(*pos1, "Income")?;
+                let (mut item_2, cell_2) =
+                    value_getter(*pos2).in_column(*pos2, "Expense")?;
+                let income = income
+                    .parse_cell(&item_1.content, cell_1)
+                    .in_column(*pos1, "Income")?;
+                let expense = expense
+                    .parse_cell(&item_2.content, cell_2)
+                    .in_column(*pos2, "Expense")?;
+                let amount = income
+                    .checked_add(expense)
+                    .ok_or(ProfileError::overflow(income, expense))
+                    .in_column(*pos2, "Expense")?;
 
                 let movement = ModelMovement::init(amount, group_uuid);
                 item_1.set_movement_ref(movement.uuid);
@@ -108,19 +170,46 @@ impl ExpenseColumn {
                 Ok((movement, vec![item_1, item_2]))
             }
             ExpenseColumn::Combined(pos, movement) => {
-                let mut item = value_getter(*pos);
-                let amount = movement.parse_str(&item.content)?;
+                let (mut item, cell) =
+                    value_getter(*pos).in_column(*pos, "Movement")?;
+                let amount = movement
+                    .parse_cell(&item.content, cell)
+                    .in_column(*pos, "Movement")?;
                 let movement = ModelMovement::init(amount, group_uuid);
                 item.set_movement_ref(movement.uuid);
                 Ok((movement, vec![item]))
             }
             ExpenseColumn::OnlyExpense(pos, pos_expense) => {
-                let mut item = value_getter(*pos);
-                let amount = pos_expense.parse_str(&item.content)?;
+                let (mut item, cell) =
+                    value_getter(*pos).in_column(*pos, "PosExpense")?;
+                let amount = pos_expense
+                    .parse_cell(&item.content, cell)
+                    .in_column(*pos, "PosExpense")?;
                 let movement = ModelMovement::init(amount, group_uuid);
                 item.set_movement_ref(movement.uuid);
                 Ok((movement, vec![item]))
             }
+            ExpenseColumn::Indicated((pos1, amount), (pos2, indicator)) => {
+                let (mut item_1, cell_1) =
+                    value_getter(*pos1).in_column(*pos1, "Movement")?;
+                let (mut item_2, cell_2) =
+                    value_getter(*pos2).in_column(*pos2, "DebitCredit")?;
+                let direction = indicator
+                    .parse_cell(&item_2.content, cell_2)
+                    .in_column(*pos2, "DebitCredit")?;
+                let unsigned = amount
+                    .parse_cell(&item_1.content, cell_1)
+                    .in_column(*pos1, "Movement")?;
+                let amount = direction
+                    .apply(unsigned)
+                    .ok_or(ProfileError::number(&item_1.content, &amount.0))
+                    .in_column(*pos1, "Movement")?;
+
+                let movement = ModelMovement::init(amount, group_uuid);
+                item_1.set_movement_ref(movement.uuid);
+                item_2.set_movement_ref(movement.uuid);
+                Ok((movement, vec![item_1, item_2]))
+            }
         }
     }
 }
@@ -131,6 +220,7 @@ impl std::fmt::Display for ExpenseColumn {
             ExpenseColumn::Combined(_, _) => write!(f, "Combined"),
             ExpenseColumn::Split(_, _) => write!(f, "Split"),
             ExpenseColumn::OnlyExpense(_, _) => write!(f, "OnlyExpense"),
+            ExpenseColumn::Indicated(_, _) => write!(f, "DebitCredit"),
         }
     }
 }
@@ -188,6 +278,17 @@ impl DateTimeColumn {
             }
         }
     }
+    pub fn map_positions(&mut self, map: impl Fn(usize) -> usize) {
+        match self {
+            DateTimeColumn::DateTime(pos, _) | DateTimeColumn::Date(pos, _) => {
+                *pos = map(*pos);
+            }
+            DateTimeColumn::DateAndTime((pos1, _), (pos2, _)) => {
+                *pos1 = map(*pos1);
+                *pos2 = map(*pos2);
+            }
+        }
+    }
     pub fn into_cols(self) -> Vec<(usize, ParsableWrapper)> {
         match self {
             DateTimeColumn::DateTime(pos, val) => {
@@ -206,22 +307,26 @@ impl DateTimeColumn {
     pub fn parse_str(
         &self,
         group_uuid: GroupUuid,
-        value_getter: &mut impl FnMut(usize) -> ImportRowItem,
-    ) -> Result<(ModelDatetime, Vec<ImportRowItem>), ProfileError> {
+        value_getter: &mut impl FnMut(usize) -> Result<RowCell, ProfileError>,
+    ) -> Result<(ModelDatetime, Vec<ImportRowItem>), ColumnError> {
         match self {
             DateTimeColumn::DateTime(pos, el) => {
-                let mut item = value_getter(*pos);
+                let (mut item, cell) =
+                    value_getter(*pos).in_column(*pos, "ExpenseDateTime")?;
                 let datetime = Datetime::init_datetime(
-                    el.parse_str(&item.content)?,
+                    el.parse_cell(&item.content, cell)
+                        .in_column(*pos, "ExpenseDateTime")?,
                     group_uuid,
                 );
                 item.set_datetime_ref(datetime.uuid);
                 Ok((datetime, vec![item]))
             }
             DateTimeColumn::Date(pos, el) => {
-                let mut item = value_getter(*pos);
+                let (mut item, cell) =
+                    value_getter(*pos).in_column(*pos, "ExpenseDate")?;
                 let datetime = Datetime::init(
-                    el.parse_str(&item.content)?,
+                    el.parse_cell(&item.content, cell)
+                        .in_column(*pos, "ExpenseDate")?,
                     None,
                     0,
                     group_uuid,
@@ -230,11 +335,17 @@ impl DateTimeColumn {
                 Ok((datetime, vec![item]))
             }
             DateTimeColumn::DateAndTime((pos_1, el_1), (pos_2, el_2)) => {
-                let mut item_1 = value_getter(*pos_1);
-                let mut item_2 = value_getter(*pos_2);
+                let (mut item_1, cell_1) =
+                    value_getter(*pos_1).in_column(*pos_1, "ExpenseDate")?;
+                let (mut item_2, cell_2) =
+                    value_getter(*pos_2).in_column(*pos_2, "ExpenseTime")?;
                 let datetime = Datetime::init(
-                    el_1.parse_str(&item_1.content)?,
-                    Some(el_2.parse_str(&item_2.content)?),
+                    el_1.parse_cell(&item_1.content, cell_1)
+                        .in_column(*pos_1, "ExpenseDate")?,
+                    Some(
+                        el_2.parse_cell(&item_2.content, cell_2)
+                            .in_column(*pos_2, "ExpenseTime")?,
+                    ),
                     0,
                     group_uuid,
                 );
@@ -256,6 +367,77 @@ impl std::fmt::Display for DateTimeColumn {
     }
 }
 
+/// Where the currency of the main amount comes from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CurrencyColumn {
+    /// Every amount of the file is in this currency.
+    Fixed(Currency),
+    Column(usize, CurrencyCode),
+}
+
+impl Default for CurrencyColumn {
+    fn default() -> Self {
+        Self::Fixed(Currency::default())
+    }
+}
+
+impl CurrencyColumn {
+    pub fn default_column() -> Self {
+        Self::Column(usize::default(), CurrencyCode::default())
+    }
+    pub fn get_from_pos(self, pos: usize) -> Option<ParsableWrapper> {
+        self.into_cols()
+            .into_iter()
+            .find(|(col_pos, _)| pos.eq(col_pos))
+            .map(|(_, wrapper)| wrapper)
+    }
+    pub fn get_positions(&self) -> Vec<usize> {
+        match self {
+            CurrencyColumn::Fixed(_) => vec![],
+            CurrencyColumn::Column(pos, _) => vec![*pos],
+        }
+    }
+    pub fn map_positions(&mut self, map: impl Fn(usize) -> usize) {
+        if let CurrencyColumn::Column(pos, _) = self {
+            *pos = map(*pos);
+        }
+    }
+    pub fn into_cols(self) -> Vec<(usize, ParsableWrapper)> {
+        match self {
+            CurrencyColumn::Fixed(_) => vec![],
+            CurrencyColumn::Column(pos, val) => {
+                vec![(pos, ParsableWrapper::CurrencyCode(val))]
+            }
+        }
+    }
+
+    pub fn parse_str(
+        &self,
+        value_getter: &mut impl FnMut(usize) -> Result<RowCell, ProfileError>,
+    ) -> Result<(Currency, Vec<ImportRowItem>), ColumnError> {
+        match self {
+            CurrencyColumn::Fixed(currency) => Ok((currency.clone(), vec![])),
+            CurrencyColumn::Column(pos, code) => {
+                let (item, cell) =
+                    value_getter(*pos).in_column(*pos, "CurrencyCode")?;
+                let currency = code
+                    .parse_cell(&item.content, cell)
+                    .in_column(*pos, "CurrencyCode")?;
+                Ok((currency, vec![item]))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for CurrencyColumn {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CurrencyColumn::Fixed(_) => write!(f, "Fixed"),
+            CurrencyColumn::Column(_, _) => write!(f, "Column"),
+        }
+    }
+}
+
 pub type ModelParsableWrapper = ParsableWrapper;
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -264,9 +446,13 @@ pub enum ParsableWrapper {
     Expense(Expense),
     PosExpense(PosExpense),
     Movement(Movement),
+    DebitCredit(DebitCredit),
+    CurrencyCode(CurrencyCode),
     ExpenseDateTime(ExpenseDateTime),
     ExpenseDate(ExpenseDate),
     ExpenseTime(ExpenseTime),
+    StandaloneTime(StandaloneTime),
+    SecondaryDate(SecondaryDate),
     Description(Description),
     Special(Special),
 }
@@ -276,7 +462,8 @@ impl ParsableWrapper {
         let mut new_val = value.clone();
         match &mut new_val {
             Self::Description(Description(desc))
-            | Self::Special(Special(_, desc)) => {
+            | Self::Special(Special(_, desc))
+            | Self::StandaloneTime(StandaloneTime(_, desc)) => {
                 let new_desc =
                     ContentDescription::init(desc.description.clone());
                 let _ = mem::replace(desc, new_desc);
@@ -291,34 +478,47 @@ impl ParsableWrapper {
         &self,
         group_uuid: GroupUuid,
         str: &str,
+        cell: Cell,
     ) -> Result<TransactionProperties, ProfileError> {
         match self {
             ParsableWrapper::Income(income) => {
-                income.to_property(group_uuid, str)
+                income.to_property(group_uuid, str, cell)
             }
             ParsableWrapper::Expense(expense) => {
-                expense.to_property(group_uuid, str)
+                expense.to_property(group_uuid, str, cell)
             }
             ParsableWrapper::PosExpense(pos_expense) => {
-                pos_expense.to_property(group_uuid, str)
+                pos_expense.to_property(group_uuid, str, cell)
             }
             ParsableWrapper::Movement(movement) => {
-                movement.to_property(group_uuid, str)
+                movement.to_property(group_uuid, str, cell)
+            }
+            ParsableWrapper::DebitCredit(debit_credit) => {
+                debit_credit.to_property(group_uuid, str, cell)
+            }
+            ParsableWrapper::CurrencyCode(currency_code) => {
+                currency_code.to_property(group_uuid, str, cell)
             }
             ParsableWrapper::ExpenseDateTime(expense_date_time) => {
-                expense_date_time.to_property(group_uuid, str)
+                expense_date_time.to_property(group_uuid, str, cell)
             }
             ParsableWrapper::ExpenseDate(expense_date) => {
-                expense_date.to_property(group_uuid, str)
+                expense_date.to_property(group_uuid, str, cell)
             }
             ParsableWrapper::ExpenseTime(expense_time) => {
-                expense_time.to_property(group_uuid, str)
+                expense_time.to_property(group_uuid, str, cell)
+            }
+            ParsableWrapper::StandaloneTime(standalone_time) => {
+                standalone_time.to_property(group_uuid, str, cell)
+            }
+            ParsableWrapper::SecondaryDate(secondary_date) => {
+                secondary_date.to_property(group_uuid, str, cell)
             }
             ParsableWrapper::Description(description) => {
-                description.to_property(group_uuid, str)
+                description.to_property(group_uuid, str, cell)
             }
             ParsableWrapper::Special(special) => {
-                special.to_property(group_uuid, str)
+                special.to_property(group_uuid, str, cell)
             }
         }
     }
@@ -343,6 +543,12 @@ impl ParsableWrapper {
     pub fn expensetime() -> Self {
         Self::ExpenseTime(ExpenseTime::default())
     }
+    pub fn standalonetime() -> Self {
+        Self::StandaloneTime(StandaloneTime::default_init())
+    }
+    pub fn secondarydate() -> Self {
+        Self::SecondaryDate(SecondaryDate::default())
+    }
     pub fn description() -> Self {
         Self::Description(Description::default_init())
     }
@@ -356,6 +562,8 @@ impl ParsableWrapper {
             Self::ExpenseDate(_)
                 | Self::ExpenseDateTime(_)
                 | Self::ExpenseTime(_)
+                | Self::StandaloneTime(_)
+                | Self::SecondaryDate(_)
         )
     }
 
@@ -366,6 +574,8 @@ impl ParsableWrapper {
                 | Self::Expense(_)
                 | Self::PosExpense(_)
                 | Self::Income(_)
+                | Self::DebitCredit(_)
+                | Self::CurrencyCode(_)
         )
     }
 }
@@ -377,9 +587,13 @@ impl std::fmt::Display for ParsableWrapper {
             ParsableWrapper::Expense(_) => write!(f, "Expense"),
             ParsableWrapper::PosExpense(_) => write!(f, "PosExpense"),
             ParsableWrapper::Movement(_) => write!(f, "Movement"),
+            ParsableWrapper::DebitCredit(_) => write!(f, "DebitCredit"),
+            ParsableWrapper::CurrencyCode(_) => write!(f, "CurrencyCode"),
             ParsableWrapper::ExpenseDateTime(_) => write!(f, "ExpenseDateTime"),
             ParsableWrapper::ExpenseDate(_) => write!(f, "ExpenseDate"),
             ParsableWrapper::ExpenseTime(_) => write!(f, "ExpenseTime"),
+            ParsableWrapper::StandaloneTime(_) => write!(f, "StandaloneTime"),
+            ParsableWrapper::SecondaryDate(_) => write!(f, "SecondaryDate"),
             ParsableWrapper::Description(_) => write!(f, "Description"),
             ParsableWrapper::Special(_) => write!(f, "Other"),
         }
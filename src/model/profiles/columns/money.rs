@@ -1,13 +1,13 @@
-use std::{fmt::Display, str::FromStr};
+use std::fmt::Display;
 
-use num_traits::Float;
 use serde::{Deserialize, Serialize};
 
 use crate::model::{
     group::GroupUuid,
-    profiles::error::ProfileError,
+    profiles::{error::ProfileError, tokenizer::Cell},
     transactions::{
-        movement::ModelMovement, properties::TransactionProperties,
+        currency::Currency, movement::ModelMovement,
+        properties::TransactionProperties,
     },
 };
 
@@ -22,31 +22,193 @@ pub enum NumberFormat {
     European,
     /// [,] as thousend separator and [.] as comma separator
     American,
+    /// ['] as thousend separator and [.] as comma separator
+    Swiss,
+    Custom(CustomNumberFormat),
 }
 
-impl NumberFormat {
-    pub fn parse<F: FromStr + Float>(
-        &self,
-        str: &str,
-    ) -> Result<F, ProfileError> {
-        if str.is_empty() {
-            return Ok(F::zero());
+/// Explicit description of how amounts are written in a column.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize)]
+pub struct CustomNumberFormat {
+    pub decimal: char,
+    pub grouping: Option<char>,
+    pub sign: SignConvention,
+    /// Drops currency symbols and codes like `€` or `CHF` before parsing.
+    pub strip_symbols: bool,
+}
+
+impl Default for CustomNumberFormat {
+    fn default() -> Self {
+        NumberFormat::European.spec()
+    }
+}
+
+#[derive(
+    Debug, PartialEq, Eq, Clone, Copy, Serialize, Deserialize, Default,
+)]
+pub enum SignConvention {
+    /// `-12.50`
+    #[default]
+    LeadingMinus,
+    /// `12.50-`
+    TrailingMinus,
+    /// `(12.50)`, a leading minus is accepted as well
+    Parentheses,
+}
+
+impl SignConvention {
+    pub fn values() -> [Self; 3] {
+        [Self::LeadingMinus, Self::TrailingMinus, Self::Parentheses]
+    }
+
+    /// Splits the sign from the digits, `true` marks a negative amount.
+    fn split<'a>(&self, str: &'a str) -> Option<(bool, &'a str)> {
+        let leading = |str: &'a str| match str.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, str.strip_prefix('+').unwrap_or(str)),
+        };
+        match self {
+            SignConvention::LeadingMinus => Some(leading(str)),
+            SignConvention::TrailingMinus => match str.strip_suffix('-') {
+                Some(rest) => Some((true, rest)),
+                None => Some((false, str.strip_suffix('+').unwrap_or(str))),
+            },
+            SignConvention::Parentheses => match str.strip_prefix('(') {
+                Some(rest) => rest.strip_suffix(')').map(|rest| (true, rest)),
+                None => Some(leading(str)),
+            },
+        }
+    }
+}
+
+impl Display for SignConvention {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SignConvention::LeadingMinus => write!(f, "-1.00"),
+            SignConvention::TrailingMinus => write!(f, "1.00-"),
+            SignConvention::Parentheses => write!(f, "(1.00)"),
         }
+    }
+}
 
-        let new_str = match self {
-            Self::European => str.replace(".", "").replace(",", "."),
-            Self::American => str.replace(",", ""),
+impl NumberFormat {
+    pub fn spec(&self) -> CustomNumberFormat {
+        let preset = |decimal, grouping| CustomNumberFormat {
+            decimal,
+            grouping: Some(grouping),
+            sign: SignConvention::LeadingMinus,
+            strip_symbols: true,
         };
+        match self {
+            Self::European => preset(',', '.'),
+            Self::American => preset('.', ','),
+            Self::Swiss => preset('.', '\''),
+            Self::Custom(custom) => *custom,
+        }
+    }
+
+    /// Parses the amount into minor units (cents) without going through a
+    /// float. Whitespace is always ignored, so space separated thousands
+    /// work with every format. An empty cell is a zero amount.
+    pub fn parse_minor_units(&self, str: &str) -> Result<i64, ProfileError> {
+        let CustomNumberFormat {
+            decimal,
+            grouping,
+            sign,
+            strip_symbols,
+        } = self.spec();
+        let error = || ProfileError::number(str, self);
+
+        let mut cleaned = str
+            .chars()
+            .filter(|char| !char.is_whitespace())
+            .collect::<String>();
+        if cleaned.is_empty() {
+            return Ok(0);
+        }
+        if strip_symbols {
+            cleaned.retain(|char| {
+                char.is_ascii_digit()
+                    || char == decimal
+                    || Some(char) == grouping
+                    || matches!(char, '-' | '+' | '(' | ')')
+            });
+            // text like `n/a` or a lone `EUR` is no amount
+            if cleaned.is_empty() {
+                return Err(error());
+            }
+        }
+
+        let (negative, digits) = sign.split(&cleaned).ok_or_else(error)?;
+        let (integer, fraction) =
+            digits.split_once(decimal).unwrap_or((digits, ""));
+        let integer = integer
+            .chars()
+            .filter(|char| Some(*char) != grouping)
+            .collect::<String>();
+
+        let all_digits = |str: &str| str.chars().all(|c| c.is_ascii_digit());
+        if (integer.is_empty() && fraction.is_empty())
+            || !all_digits(&integer)
+            || !all_digits(fraction)
+        {
+            return Err(error());
+        }
+        // more than two decimals are only fine as long as no cent is lost
+        if fraction.chars().skip(2).any(|char| char != '0') {
+            return Err(error());
+        }
+
+        let cents = fraction
+            .chars()
+            .chain(std::iter::repeat('0'))
+            .take(2)
+            .collect::<String>();
+        let units = match integer.is_empty() {
+            true => 0,
+            false => integer.parse::<i64>().or(Err(error()))?,
+        }
+        .checked_mul(100)
+        .and_then(|units| units.checked_add(cents.parse::<i64>().ok()?))
+        .ok_or_else(error)?;
+
+        Ok(if negative { -units } else { units })
+    }
 
-        new_str
-            .parse::<F>()
-            .or(Err(ProfileError::number(str, self)))
+    /// Number cells of a sheet are written like `-1234.5` whatever the
+    /// format of the column says.
+    pub fn parse_cell(
+        &self,
+        str: &str,
+        cell: Cell,
+    ) -> Result<i64, ProfileError> {
+        match cell {
+            Cell::Number => Self::Custom(CustomNumberFormat {
+                decimal: '.',
+                grouping: None,
+                sign: SignConvention::LeadingMinus,
+                strip_symbols: false,
+            })
+            .parse_minor_units(str),
+            _ => self.parse_minor_units(str),
+        }
     }
 }
 
 impl Display for NumberFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{self:?}")
+        match self {
+            Self::Custom(CustomNumberFormat {
+                decimal,
+                grouping,
+                sign,
+                ..
+            }) => write!(
+                f,
+                "Custom(decimal {decimal:?}, grouping {grouping:?}, {sign})"
+            ),
+            _ => write!(f, "{self:?}"),
+        }
     }
 }
 
@@ -71,17 +233,22 @@ impl From<NumberFormat> for Income {
     }
 }
 
-impl Parser<i32> for Income {
-    fn parse_str(&self, str: &str) -> Result<i32, ProfileError> {
-        Ok((self.0.parse::<f64>(str)? * 100.0) as i32)
+impl Parser<i64> for Income {
+    fn parse_str(&self, str: &str) -> Result<i64, ProfileError> {
+        self.0.parse_minor_units(str)
+    }
+
+    fn parse_cell(&self, str: &str, cell: Cell) -> Result<i64, ProfileError> {
+        self.0.parse_cell(str, cell)
     }
 
     fn to_property(
         &self,
         group_uuid: GroupUuid,
         str: &str,
+        cell: Cell,
     ) -> Result<TransactionProperties, ProfileError> {
-        let amount = self.parse_str(str)?;
+        let amount = self.parse_cell(str, cell)?;
         Ok(ModelMovement::init(amount, group_uuid).into())
     }
 }
@@ -107,17 +274,22 @@ impl From<NumberFormat> for Expense {
     }
 }
 
-impl Parser<i32> for Expense {
-    fn parse_str(&self, str: &str) -> Result<i32, ProfileError> {
-        Ok((self.0.parse::<f64>(str)? * 100.0) as i32)
+impl Parser<i64> for Expense {
+    fn parse_str(&self, str: &str) -> Result<i64, ProfileError> {
+        self.0.parse_minor_units(str)
+    }
+
+    fn parse_cell(&self, str: &str, cell: Cell) -> Result<i64, ProfileError> {
+        self.0.parse_cell(str, cell)
     }
 
     fn to_property(
         &self,
         group_uuid: GroupUuid,
         str: &str,
+        cell: Cell,
     ) -> Result<TransactionProperties, ProfileError> {
-        let amount = self.parse_str(str)?;
+        let amount = self.parse_cell(str, cell)?;
         Ok(ModelMovement::init(amount, group_uuid).into())
     }
 }
@@ -143,17 +315,25 @@ impl From<NumberFormat> for PosExpense {
     }
 }
 
-impl Parser<i32> for PosExpense {
-    fn parse_str(&self, str: &str) -> Result<i32, ProfileError> {
-        Ok((self.0.parse::<f64>(str)? * -100.0) as i32)
+impl Parser<i64> for PosExpense {
+    fn parse_str(&self, str: &str) -> Result<i64, ProfileError> {
+        self.parse_cell(str, Cell::Text)
+    }
+
+    fn parse_cell(&self, str: &str, cell: Cell) -> Result<i64, ProfileError> {
+        self.0
+            .parse_cell(str, cell)?
+            .checked_neg()
+            .ok_or(ProfileError::number(str, &self.0))
     }
 
     fn to_property(
         &self,
         group_uuid: GroupUuid,
         str: &str,
+        cell: Cell,
     ) -> Result<TransactionProperties, ProfileError> {
-        let amount = self.parse_str(str)?;
+        let amount = self.parse_cell(str, cell)?;
         Ok(ModelMovement::init(amount, group_uuid).into())
     }
 }
@@ -179,17 +359,137 @@ impl From<NumberFormat> for Movement {
     }
 }
 
-impl Parser<i32> for Movement {
-    fn parse_str(&self, str: &str) -> Result<i32, ProfileError> {
-        Ok((self.0.parse::<f64>(str)? * 100.0) as i32)
+impl Parser<i64> for Movement {
+    fn parse_str(&self, str: &str) -> Result<i64, ProfileError> {
+        self.0.parse_minor_units(str)
+    }
+
+    fn parse_cell(&self, str: &str, cell: Cell) -> Result<i64, ProfileError> {
+        self.0.parse_cell(str, cell)
     }
 
     fn to_property(
         &self,
         group_uuid: GroupUuid,
         str: &str,
+        cell: Cell,
     ) -> Result<TransactionProperties, ProfileError> {
-        let amount = self.parse_str(str)?;
+        let amount = self.parse_cell(str, cell)?;
         Ok(ModelMovement::init(amount, group_uuid).into())
     }
 }
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Debit,
+    Credit,
+}
+
+impl Direction {
+    /// Signs an unsigned amount, debits leave the account. `None` if the
+    /// amount has no absolute value in an `i64`.
+    pub fn apply(&self, amount: i64) -> Option<i64> {
+        let amount = amount.checked_abs()?;
+        match self {
+            Direction::Debit => Some(-amount),
+            Direction::Credit => Some(amount),
+        }
+    }
+}
+
+/// Column that only holds the direction of an unsigned amount, like `S`/`H`
+/// in German exports or `DBIT`/`CRDT` in camt statements. Tokens are
+/// compared case insensitive.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct DebitCredit {
+    pub debit: String,
+    pub credit: String,
+}
+
+impl Default for DebitCredit {
+    fn default() -> Self {
+        Self::new("S", "H")
+    }
+}
+
+impl DebitCredit {
+    pub fn new(debit: &str, credit: &str) -> Self {
+        Self {
+            debit: debit.to_owned(),
+            credit: credit.to_owned(),
+        }
+    }
+
+    pub fn presets() -> [Self; 3] {
+        [
+            Self::new("S", "H"),
+            Self::new("DBIT", "CRDT"),
+            Self::new("Debit", "Credit"),
+        ]
+    }
+}
+
+impl From<DebitCredit> for ParsableWrapper {
+    fn from(value: DebitCredit) -> Self {
+        Self::DebitCredit(value)
+    }
+}
+
+impl Display for DebitCredit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}/{}", self.debit, self.credit)
+    }
+}
+
+impl Parser<Direction> for DebitCredit {
+    fn parse_str(&self, str: &str) -> Result<Direction, ProfileError> {
+        let str = str.trim();
+        if str.eq_ignore_ascii_case(self.debit.trim()) {
+            Ok(Direction::Debit)
+        } else if str.eq_ignore_ascii_case(self.credit.trim()) {
+            Ok(Direction::Credit)
+        } else {
+            Err(ProfileError::indicator(str, self))
+        }
+    }
+
+    fn to_property(
+        &self,
+        _group_uuid: GroupUuid,
+        str: &str,
+        _cell: Cell,
+    ) -> Result<TransactionProperties, ProfileError> {
+        // the direction only means something next to its amount column
+        Err(ProfileError::indicator_without_amount(str))
+    }
+}
+
+/// Column with the ISO 4217 code of the amount, empty cells fall back to
+/// the contained currency.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct CurrencyCode(pub Currency);
+
+impl From<CurrencyCode> for ParsableWrapper {
+    fn from(value: CurrencyCode) -> Self {
+        Self::CurrencyCode(value)
+    }
+}
+
+impl Parser<Currency> for CurrencyCode {
+    fn parse_str(&self, str: &str) -> Result<Currency, ProfileError> {
+        if str.trim().is_empty() {
+            return Ok(self.0.clone());
+        }
+        str.parse().map_err(ProfileError::currency)
+    }
+
+    fn to_property(
+        &self,
+        _group_uuid: GroupUuid,
+        str: &str,
+        _cell: Cell,
+    ) -> Result<TransactionProperties, ProfileError> {
+        // the currency only means something next to its amount column
+        Err(ProfileError::currency_without_amount(str))
+    }
+}
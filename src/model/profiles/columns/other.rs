@@ -1,6 +1,6 @@
 use crate::model::{
     group::GroupUuid,
-    profiles::error::ProfileError,
+    profiles::{error::ProfileError, tokenizer::Cell},
     transactions::{
         content_description::ContentDescription,
         properties::TransactionProperties,
@@ -38,6 +38,7 @@ impl Parser<String> for Description {
         &self,
         group_uuid: GroupUuid,
         str: &str,
+        _cell: Cell,
     ) -> Result<TransactionProperties, ProfileError> {
         Ok(TransactionProperties::Text(TextContent::init(
             str.to_string(),
@@ -77,6 +78,7 @@ impl Parser<String> for Special {
         &self,
         group_uuid: GroupUuid,
         str: &str,
+        _cell: Cell,
     ) -> Result<TransactionProperties, ProfileError> {
         Ok(TransactionProperties::Special(SpecialContent::init(
             str.to_string(),
@@ -1,16 +1,31 @@
 use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, NaiveTime};
 use serde::{Deserialize, Serialize};
 
-use crate::model::{
-    group::GroupUuid,
-    profiles::error::ProfileError,
-    transactions::{
-        datetime::ModelDatetime, properties::TransactionProperties,
+use crate::{
+    db::TIME_FORMAT,
+    model::{
+        group::GroupUuid,
+        profiles::{error::ProfileError, tokenizer::Cell},
+        transactions::{
+            content_description::ContentDescription,
+            datetime::{DateRole, ModelDatetime},
+            properties::TransactionProperties,
+            special_content::{SpecialContent, SpecialType},
+        },
     },
 };
 
 use super::{ParsableWrapper, Parser};
 
+/// An hour that is repeated when the clocks go back is read as its first
+/// occurrence, one that is skipped when they go forward cannot be read.
+fn local(datetime: NaiveDateTime) -> Result<DateTime<Local>, ProfileError> {
+    datetime
+        .and_local_timezone(Local)
+        .earliest()
+        .ok_or_else(|| ProfileError::skipped_time(datetime))
+}
+
 #[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
 pub struct ExpenseDateTime(pub String);
 
@@ -23,16 +38,28 @@ impl From<ExpenseDateTime> for ParsableWrapper {
 impl Parser<DateTime<Local>> for ExpenseDateTime {
     fn parse_str(&self, str: &str) -> Result<DateTime<Local>, ProfileError> {
         NaiveDateTime::parse_from_str(str, &self.0)
-            .map(|dt| dt.and_local_timezone(Local::now().timezone()).unwrap())
             .or(Err(ProfileError::date(str, &self.0)))
+            .and_then(local)
+    }
+
+    fn parse_cell(
+        &self,
+        str: &str,
+        cell: Cell,
+    ) -> Result<DateTime<Local>, ProfileError> {
+        match cell {
+            Cell::DateTime(datetime) => local(datetime),
+            _ => self.parse_str(str),
+        }
     }
 
     fn to_property(
         &self,
         group_uuid: GroupUuid,
         str: &str,
+        cell: Cell,
     ) -> Result<TransactionProperties, ProfileError> {
-        let datetime = self.parse_str(str)?;
+        let datetime = self.parse_cell(str, cell)?;
         Ok(ModelDatetime::init_datetime(datetime, group_uuid).into())
     }
 }
@@ -52,13 +79,62 @@ impl Parser<NaiveDate> for ExpenseDate {
             .or(Err(ProfileError::date(str, &self.0)))
     }
 
+    fn parse_cell(
+        &self,
+        str: &str,
+        cell: Cell,
+    ) -> Result<NaiveDate, ProfileError> {
+        match cell {
+            Cell::DateTime(datetime) => Ok(datetime.date()),
+            _ => self.parse_str(str),
+        }
+    }
+
     fn to_property(
         &self,
         group_uuid: GroupUuid,
         str: &str,
+        cell: Cell,
     ) -> Result<TransactionProperties, ProfileError> {
-        let date = self.parse_str(str)?;
-        Ok(ModelDatetime::init(date, None, 3600, group_uuid).into())
+        let date = self.parse_cell(str, cell)?;
+        Ok(ModelDatetime::init_local(date, None, group_uuid).into())
+    }
+}
+
+/// Date column next to the main datetime, like the value date or the
+/// booking date of a bank statement. Stored as an additional datetime.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct SecondaryDate(pub ExpenseDate, pub DateRole);
+
+impl From<SecondaryDate> for ParsableWrapper {
+    fn from(value: SecondaryDate) -> Self {
+        Self::SecondaryDate(value)
+    }
+}
+
+impl Parser<NaiveDate> for SecondaryDate {
+    fn parse_str(&self, str: &str) -> Result<NaiveDate, ProfileError> {
+        self.0.parse_str(str)
+    }
+
+    fn parse_cell(
+        &self,
+        str: &str,
+        cell: Cell,
+    ) -> Result<NaiveDate, ProfileError> {
+        self.0.parse_cell(str, cell)
+    }
+
+    fn to_property(
+        &self,
+        group_uuid: GroupUuid,
+        str: &str,
+        cell: Cell,
+    ) -> Result<TransactionProperties, ProfileError> {
+        let date = self.parse_cell(str, cell)?;
+        Ok(ModelDatetime::init_local(date, None, group_uuid)
+            .with_role(self.1)
+            .into())
     }
 }
 
@@ -77,12 +153,74 @@ impl Parser<NaiveTime> for ExpenseTime {
             .or(Err(ProfileError::date(str, &self.0)))
     }
 
+    fn parse_cell(
+        &self,
+        str: &str,
+        cell: Cell,
+    ) -> Result<NaiveTime, ProfileError> {
+        match cell {
+            Cell::DateTime(datetime) => Ok(datetime.time()),
+            _ => self.parse_str(str),
+        }
+    }
+
     fn to_property(
         &self,
         _group_uuid: GroupUuid,
-        _str: &str,
+        str: &str,
+        _cell: Cell,
+    ) -> Result<TransactionProperties, ProfileError> {
+        // a time on its own needs a description, see [`StandaloneTime`]
+        Err(ProfileError::time_without_date(str))
+    }
+}
+
+/// Time column that is not part of the main datetime, for example a
+/// booking time next to the value time. Stored as a [`SpecialType::Time`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StandaloneTime(pub ExpenseTime, pub ContentDescription);
+
+impl StandaloneTime {
+    pub fn default_init() -> Self {
+        Self(
+            ExpenseTime::default(),
+            ContentDescription::init(String::default()),
+        )
+    }
+}
+
+impl From<StandaloneTime> for ParsableWrapper {
+    fn from(value: StandaloneTime) -> Self {
+        Self::StandaloneTime(value)
+    }
+}
+
+impl Parser<NaiveTime> for StandaloneTime {
+    fn parse_str(&self, str: &str) -> Result<NaiveTime, ProfileError> {
+        self.0.parse_str(str)
+    }
+
+    fn parse_cell(
+        &self,
+        str: &str,
+        cell: Cell,
+    ) -> Result<NaiveTime, ProfileError> {
+        self.0.parse_cell(str, cell)
+    }
+
+    fn to_property(
+        &self,
+        group_uuid: GroupUuid,
+        str: &str,
+        cell: Cell,
     ) -> Result<TransactionProperties, ProfileError> {
-        // ToDo-[Thomas] - parse other time column as a special
-        todo!()
+        let time = self.parse_cell(str, cell)?;
+        Ok(SpecialContent::init(
+            time.format(TIME_FORMAT).to_string(),
+            self.1.clone(),
+            SpecialType::Time,
+            group_uuid,
+        )
+        .into())
     }
 }
@@ -0,0 +1,192 @@
+use std::{collections::HashMap, fmt::Display, path::Path};
+
+use itertools::Itertools;
+
+use crate::{
+    db::InitUuid,
+    model::{data_import::row_item::ImportRowItem, group::GroupUuid},
+};
+
+use super::{
+    encoding::FileEncoding, error::ProfileError, format::FileFormat,
+    tokenizer::Cell, Profile, ProfileUuid,
+};
+
+/// Rows of the file body that are tried with each profile.
+const SAMPLE_SIZE: usize = 20;
+/// Below this score a profile is never preselected.
+const MIN_CONFIDENCE: f32 = 0.6;
+/// Two profiles closer than this are treated as equally good.
+const AMBIGUOUS_DISTANCE: f32 = 0.1;
+
+/// Outcome of scoring every profile against one file.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Detection {
+    /// The best profile and its confidence.
+    Match(ProfileUuid, f32),
+    /// The best candidates were too close or too unsure to pick one.
+    Ambiguous(Vec<(ProfileUuid, f32)>),
+    NoMatch,
+}
+
+impl Detection {
+    /// Scores all `profiles` against the file at `path`, best first. The
+    /// file is read once for every way the profiles read it, a workbook is
+    /// no text until a profile picks its sheet.
+    pub fn detect(profiles: &[Profile], path: &Path) -> Self {
+        let mut contents = HashMap::new();
+        let scores = profiles
+            .iter()
+            .map(|profile| {
+                let score = contents
+                    .entry(Source::of(profile))
+                    .or_insert_with(|| profile.read_file(path).ok())
+                    .as_deref()
+                    .map_or(0., |content| profile.score(content));
+                (profile.uuid, score)
+            })
+            .filter(|(_, score)| *score > 0.)
+            .sorted_by(|(_, a), (_, b)| b.total_cmp(a))
+            .collect_vec();
+
+        match scores.as_slice() {
+            [] => Self::NoMatch,
+            [(uuid, best), rest @ ..]
+                if *best >= MIN_CONFIDENCE
+                    && rest.first().is_none_or(|(_, second)| {
+                        best - second > AMBIGUOUS_DISTANCE
+                    }) =>
+            {
+                Self::Match(*uuid, *best)
+            }
+            _ => Self::Ambiguous(
+                scores
+                    .into_iter()
+                    .take_while(|(_, score)| *score >= MIN_CONFIDENCE / 2.)
+                    .collect(),
+            ),
+        }
+    }
+}
+
+/// How a profile turns a file into text, profiles with the same source
+/// read the same content.
+#[derive(Debug, PartialEq, Eq, Hash)]
+enum Source<'a> {
+    Text(FileEncoding),
+    Sheet(&'a str),
+}
+
+impl<'a> Source<'a> {
+    fn of(profile: &'a Profile) -> Self {
+        match profile.format {
+            FileFormat::Spreadsheet => Self::Sheet(&profile.sheet),
+            _ => Self::Text(profile.encoding),
+        }
+    }
+}
+
+impl Display for Detection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Detection::Match(_, confidence) => {
+                write!(f, "detected ({:.0}%)", confidence * 100.)
+            }
+            Detection::Ambiguous(candidates) => {
+                write!(f, "{} profiles could fit", candidates.len())
+            }
+            Detection::NoMatch => write!(f, "no profile fits"),
+        }
+    }
+}
+
+impl Profile {
+    /// How well this profile fits the file, from 0 to 1. Weighs whether the
+    /// rows are wide enough for the columns, whether the header row has the
+    /// same width as the body and which share of a sample of rows has a
+    /// parsable amount and datetime. A profile whose bound headers are
+    /// missing in the file does not fit at all.
+    pub fn score(&self, content: &str) -> f32 {
+        match self.resolve_headers(content) {
+            Ok(profile) => profile.score_positions(content),
+            Err(_) => 0.,
+        }
+    }
+
+    fn score_positions(&self, content: &str) -> f32 {
+        let tokenizer = self.tokenizer();
+        let records = tokenizer.records(content);
+        let len = records.len();
+        let margins = self.effective_margins();
+        if len <= margins.0 + margins.1 {
+            return 0.;
+        }
+        let fields = |record: &String| tokenizer.cells(record).ok();
+
+        let body = records
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !self.is_margin(*index, len))
+            .map(|(_, record)| record)
+            .filter(|record| !record.trim().is_empty())
+            .take(SAMPLE_SIZE)
+            .map(fields)
+            .collect_vec();
+        if body.is_empty() {
+            return 0.;
+        }
+        let body_width = body.iter().flatten().map(Vec::len).max();
+
+        let share = |matches: usize| matches as f32 / body.len() as f32;
+
+        let wide_enough = share(
+            body.iter()
+                .flatten()
+                .filter(|fields| fields.len() > self.width)
+                .count(),
+        );
+        if wide_enough == 0. {
+            return 0.;
+        }
+
+        let header_fits = match margins.0.checked_sub(1) {
+            Some(header) => {
+                let header_width =
+                    records.get(header).and_then(fields).map(|f| f.len());
+                if header_width.is_some() && header_width == body_width {
+                    1.
+                } else {
+                    0.
+                }
+            }
+            // without a header there is nothing to compare
+            None => 0.5,
+        };
+
+        let (amounts, datetimes) = body.iter().flatten().fold(
+            (0, 0),
+            |(amounts, datetimes), fields| {
+                let (amount, datetime) = self.sample_parses(fields);
+                (amounts + amount as usize, datetimes + datetime as usize)
+            },
+        );
+
+        0.3 * wide_enough
+            + 0.1 * header_fits
+            + 0.3 * share(amounts)
+            + 0.3 * share(datetimes)
+    }
+
+    /// Tries the amount and datetime columns without touching any state.
+    fn sample_parses(&self, fields: &[(String, Cell)]) -> (bool, bool) {
+        let group_uuid = GroupUuid::init();
+        let mut getter = |pos: usize| -> Result<_, ProfileError> {
+            let (content, cell) = fields.get(pos).cloned().unwrap_or_default();
+            Ok((ImportRowItem::init((pos, content)), cell))
+        };
+        (
+            self.amount.parse_str(group_uuid, &mut getter).is_ok(),
+            self.datetime.parse_str(group_uuid, &mut getter).is_ok(),
+        )
+    }
+}
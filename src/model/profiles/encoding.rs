@@ -0,0 +1,89 @@
+use std::{borrow::Cow, fs, path::Path};
+
+use encoding_rs::{Encoding, ISO_8859_15, UTF_8, WINDOWS_1252};
+use sea_orm::{DeriveActiveEnum, EnumIter};
+use sea_query::StringLen;
+use serde::{Deserialize, Serialize};
+
+use super::error::ProfileError;
+
+/// Encoding of the files a profile parses. Many banks still export
+/// Windows-1252, umlauts in payee names are not valid UTF-8 then.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    DeriveActiveEnum,
+    EnumIter,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(255))")]
+pub enum FileEncoding {
+    /// UTF-8 if the file is valid UTF-8, Windows-1252 otherwise.
+    #[sea_orm(string_value = "Auto")]
+    #[default]
+    Auto,
+    #[sea_orm(string_value = "Utf8")]
+    Utf8,
+    /// Also covers ISO-8859-1, which it is a superset of.
+    #[sea_orm(string_value = "Windows1252")]
+    Windows1252,
+    /// ISO-8859-15, ISO-8859-1 with the euro sign.
+    #[sea_orm(string_value = "Iso885915")]
+    Iso885915,
+}
+
+impl FileEncoding {
+    pub fn values() -> [Self; 4] {
+        [Self::Auto, Self::Utf8, Self::Windows1252, Self::Iso885915]
+    }
+
+    pub fn read(&self, path: &Path) -> Result<String, ProfileError> {
+        let bytes =
+            fs::read(path).map_err(|err| ProfileError::file(path, err))?;
+        self.decode(&bytes).map(Cow::into_owned)
+    }
+
+    /// A byte order mark wins over the declared encoding and is never part
+    /// of the content, so it does not end up in the first header cell.
+    pub fn decode<'a>(
+        &self,
+        bytes: &'a [u8],
+    ) -> Result<Cow<'a, str>, ProfileError> {
+        let (encoding, bytes) = match Encoding::for_bom(bytes) {
+            Some((encoding, bom_len)) => (encoding, &bytes[bom_len..]),
+            None => (self.encoding(bytes), bytes),
+        };
+        encoding
+            .decode_without_bom_handling_and_without_replacement(bytes)
+            .ok_or_else(|| ProfileError::encoding(encoding.name()))
+    }
+
+    fn encoding(&self, bytes: &[u8]) -> &'static Encoding {
+        match self {
+            Self::Auto => match std::str::from_utf8(bytes) {
+                Ok(_) => UTF_8,
+                Err(_) => WINDOWS_1252,
+            },
+            Self::Utf8 => UTF_8,
+            Self::Windows1252 => WINDOWS_1252,
+            Self::Iso885915 => ISO_8859_15,
+        }
+    }
+}
+
+impl std::fmt::Display for FileEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "detect"),
+            Self::Utf8 => write!(f, "UTF-8"),
+            Self::Windows1252 => write!(f, "Windows-1252"),
+            Self::Iso885915 => write!(f, "ISO-8859-15"),
+        }
+    }
+}
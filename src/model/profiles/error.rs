@@ -1,7 +1,12 @@
-use chrono::{DateTime, Local};
+use std::{fmt::Display, path::Path};
+
+use chrono::{DateTime, Local, NaiveDateTime};
+use itertools::Itertools;
 use uuid::Uuid;
 
-use super::columns::money::NumberFormat;
+use crate::model::data_import::row::ImportRow;
+
+use super::columns::money::{DebitCredit, NumberFormat};
 
 #[derive(Debug, Clone)]
 pub enum ProfileError {
@@ -9,6 +14,20 @@ pub enum ProfileError {
     DateParsing(String),
     ColumnWidth(String),
     BuildRecord(String),
+    Tokenizing(String),
+    Inference(String),
+    Portable(String),
+    Encoding(String),
+    /// The file cannot be opened, has no such sheet or nothing to import.
+    File(String),
+    /// The header row of the file does not fit the labels the columns of
+    /// the profile are bound to.
+    Headers(String),
+    /// A booking of a bank statement file (CAMT.053, MT940, OFX, QIF) is
+    /// malformed.
+    Statement(String),
+    /// The saved data the file is compared with could not be loaded.
+    Database(String),
 }
 
 impl ProfileError {
@@ -17,18 +36,115 @@ impl ProfileError {
             "Parsing this string: {str} to this format: {format} failed"
         ))
     }
+    pub fn indicator(str: &str, indicator: &DebitCredit) -> Self {
+        Self::NumberParsing(format!(
+            "This string: {str} is neither the debit nor the credit token of {indicator}"
+        ))
+    }
+    pub fn indicator_without_amount(str: &str) -> Self {
+        Self::NumberParsing(format!(
+            "The indicator {str} can only be parsed together with an amount column"
+        ))
+    }
+    pub fn currency(msg: String) -> Self {
+        Self::NumberParsing(msg)
+    }
+    pub fn currency_without_amount(str: &str) -> Self {
+        Self::NumberParsing(format!(
+            "The currency {str} can only be parsed together with an amount column"
+        ))
+    }
+    pub fn overflow(left: i64, right: i64) -> Self {
+        Self::NumberParsing(format!(
+            "The amounts {left} and {right} are too large to be combined"
+        ))
+    }
     pub fn date(str: &str, format: &str) -> Self {
         Self::DateParsing(format!(
             "This format: {format} does not fit this date string: {str}"
         ))
     }
+    pub fn skipped_time(datetime: NaiveDateTime) -> Self {
+        Self::DateParsing(format!(
+            "The time {datetime} does not exist in the local time zone, the clocks skip it"
+        ))
+    }
+    pub fn invalid_sheet_date(value: f64) -> Self {
+        Self::DateParsing(format!(
+            "The sheet has a date cell with the value {value} that is no valid date"
+        ))
+    }
+    pub fn time_without_date(str: &str) -> Self {
+        Self::DateParsing(format!(
+            "The time {str} can only be parsed together with a date or as a standalone time column"
+        ))
+    }
     pub fn width(expected: usize, actual: usize) -> Self {
         Self::ColumnWidth(format!(
             "The profile expects a minimum width of {expected} but got a width of {actual}"
         ))
     }
+    pub fn missing_column(position: usize) -> Self {
+        Self::ColumnWidth(format!(
+            "The row has no column {position} or it is used by two columns"
+        ))
+    }
+    pub fn unterminated_quote(record: &str) -> Self {
+        Self::Tokenizing(format!(
+            "This record has a quoted field that is never closed: {record}"
+        ))
+    }
+    pub fn statement(reason: impl Display) -> Self {
+        Self::Statement(format!(
+            "This statement entry cannot be read: {reason}"
+        ))
+    }
+    pub fn database(reason: impl Display) -> Self {
+        Self::Database(format!("The saved data cannot be loaded: {reason}"))
+    }
+    pub fn missing_header_row(top_margin: usize) -> Self {
+        Self::Headers(format!(
+            "The profile binds headers but the file has no header row in its top margin of {top_margin}"
+        ))
+    }
+    pub fn missing_headers(labels: &[String]) -> Self {
+        Self::Headers(format!(
+            "These expected headers are missing in the file: {}",
+            labels.join(", ")
+        ))
+    }
+    pub fn header_collision(positions: &[usize]) -> Self {
+        Self::Headers(format!(
+            "The headers of the file move two columns onto these positions: {}",
+            positions.iter().join(", ")
+        ))
+    }
+    pub fn inference(reason: &str) -> Self {
+        Self::Inference(format!(
+            "No profile could be inferred from the file: {reason}"
+        ))
+    }
+    pub fn portable(reason: impl Display) -> Self {
+        Self::Portable(format!("The profile file cannot be read: {reason}"))
+    }
+    pub fn portable_version(found: u32, supported: u32) -> Self {
+        Self::Portable(format!(
+            "The profile file has format version {found} but only versions up to {supported} are supported"
+        ))
+    }
+    pub fn file(path: &Path, reason: impl Display) -> Self {
+        Self::File(format!(
+            "The file {} cannot be read: {reason}",
+            path.display()
+        ))
+    }
+    pub fn encoding(encoding: &str) -> Self {
+        Self::Encoding(format!(
+            "The file is not valid {encoding}, select another encoding in the profile"
+        ))
+    }
     pub fn build(
-        amount: Option<isize>,
+        amount: Option<i64>,
         date: Option<DateTime<Local>>,
         data_import: Option<Uuid>,
     ) -> Self {
@@ -37,3 +153,90 @@ impl ProfileError {
         ))
     }
 }
+
+impl Display for ProfileError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::NumberParsing(msg) => write!(f, "NumberParsing: {msg}"),
+            Self::DateParsing(msg) => write!(f, "DateParsing: {msg}"),
+            Self::ColumnWidth(msg) => write!(f, "ColumnWidth: {msg}"),
+            Self::BuildRecord(msg) => write!(f, "BuildRecord: {msg}"),
+            Self::Tokenizing(msg) => write!(f, "Tokenizing: {msg}"),
+            Self::Inference(msg) => write!(f, "Inference: {msg}"),
+            Self::Portable(msg) => write!(f, "Portable: {msg}"),
+            Self::Encoding(msg) => write!(f, "Encoding: {msg}"),
+            Self::File(msg) => write!(f, "File: {msg}"),
+            Self::Headers(msg) => write!(f, "Headers: {msg}"),
+            Self::Statement(msg) => write!(f, "Statement: {msg}"),
+            Self::Database(msg) => write!(f, "Database: {msg}"),
+        }
+    }
+}
+
+/// A [`ProfileError`] together with the column and parser that caused it.
+#[derive(Debug, Clone)]
+pub struct ColumnError {
+    pub column: usize,
+    pub parser: String,
+    pub error: ProfileError,
+}
+
+pub trait InColumn<T> {
+    fn in_column(
+        self,
+        column: usize,
+        parser: impl Display,
+    ) -> Result<T, ColumnError>;
+}
+
+impl<T> InColumn<T> for Result<T, ProfileError> {
+    fn in_column(
+        self,
+        column: usize,
+        parser: impl Display,
+    ) -> Result<T, ColumnError> {
+        self.map_err(|error| ColumnError {
+            column,
+            parser: parser.to_string(),
+            error,
+        })
+    }
+}
+
+/// Outcome of a single row the profile could not parse.
+#[derive(Debug, Clone)]
+pub struct RowError {
+    pub row_index: usize,
+    pub row_content: String,
+    pub column: Option<usize>,
+    pub parser: Option<String>,
+    pub error: ProfileError,
+}
+
+impl RowError {
+    pub fn new(row: &ImportRow, error: ProfileError) -> Self {
+        Self {
+            row_index: row.row_index,
+            row_content: row.row_content.clone(),
+            column: None,
+            parser: None,
+            error,
+        }
+    }
+    pub fn in_column(
+        row: &ImportRow,
+        ColumnError {
+            column,
+            parser,
+            error,
+        }: ColumnError,
+    ) -> Self {
+        Self {
+            row_index: row.row_index,
+            row_content: row.row_content.clone(),
+            column: Some(column),
+            parser: Some(parser),
+            error,
+        }
+    }
+}
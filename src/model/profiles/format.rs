@@ -0,0 +1,233 @@
+pub mod camt053;
+pub mod mt940;
+pub mod ofx;
+pub mod qif;
+pub mod spreadsheet;
+
+use std::path::Path;
+
+use sea_orm::{DeriveActiveEnum, EnumIter};
+use sea_query::StringLen;
+use serde::{Deserialize, Serialize};
+
+use crate::model::transactions::{
+    currency::Currency, datetime::DateRole, special_content::SpecialType,
+};
+
+use super::{
+    columns::{
+        money::{CurrencyCode, NumberFormat},
+        other::{Description, Special},
+        time::{ExpenseDate, SecondaryDate},
+        CurrencyColumn, DateTimeColumn, ExpenseColumn, ParsableWrapper,
+    },
+    encoding::FileEncoding,
+    error::ProfileError,
+};
+
+/// How a file is split into rows and fields. Statement formats have no
+/// columns of their own, every entry is turned into the fields of a
+/// [`StatementEntry`] which always map to the same transaction fields.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    DeriveActiveEnum,
+    EnumIter,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(255))")]
+pub enum FileFormat {
+    #[sea_orm(string_value = "Csv")]
+    #[default]
+    Csv,
+    /// ISO 20022 bank to customer statement, one row per `<Ntry>`.
+    #[sea_orm(string_value = "Camt053")]
+    Camt053,
+    /// SWIFT customer statement, one row per `:61:` with its `:86:`.
+    #[sea_orm(string_value = "Mt940")]
+    Mt940,
+    /// OFX 1.x in SGML, OFX 2.x in XML and QFX, one row per `<STMTTRN>`.
+    #[sea_orm(string_value = "Ofx")]
+    Ofx,
+    /// Quicken interchange format, one row per record of a bank, cash or
+    /// credit card account.
+    #[sea_orm(string_value = "Qif")]
+    Qif,
+    /// A sheet of an xlsx, xls or ods workbook, read as csv with the cells
+    /// at their position in the sheet.
+    #[sea_orm(string_value = "Spreadsheet")]
+    Spreadsheet,
+}
+
+impl FileFormat {
+    pub fn values() -> [Self; 6] {
+        [
+            Self::Csv,
+            Self::Camt053,
+            Self::Mt940,
+            Self::Ofx,
+            Self::Qif,
+            Self::Spreadsheet,
+        ]
+    }
+
+    /// Only csv files are split by the delimiter and quote of the profile.
+    pub fn is_delimited(&self) -> bool {
+        matches!(self, Self::Csv)
+    }
+
+    /// Statement formats have bookings with known fields instead of
+    /// columns, their profiles need no column setup.
+    pub fn is_statement(&self) -> bool {
+        matches!(self, Self::Camt053 | Self::Mt940 | Self::Ofx | Self::Qif)
+    }
+
+    /// The content of the file as text. Workbooks are binary, their `sheet`
+    /// is rendered as csv instead of being decoded.
+    pub fn read(
+        &self,
+        path: &Path,
+        encoding: FileEncoding,
+        sheet: &str,
+    ) -> Result<String, ProfileError> {
+        match self {
+            Self::Spreadsheet => spreadsheet::read(path, sheet),
+            _ => encoding.read(path),
+        }
+    }
+}
+
+impl std::fmt::Display for FileFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Csv => write!(f, "CSV"),
+            Self::Camt053 => write!(f, "CAMT.053"),
+            Self::Mt940 => write!(f, "MT940"),
+            Self::Ofx => write!(f, "OFX/QFX"),
+            Self::Qif => write!(f, "QIF"),
+            Self::Spreadsheet => write!(f, "Spreadsheet (xlsx/ods)"),
+        }
+    }
+}
+
+/// One booking of a statement file. Amounts are signed and use a `.` as
+/// decimal separator, dates are written as `%Y-%m-%d`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct StatementEntry {
+    pub amount: String,
+    pub currency: String,
+    pub booking_date: String,
+    /// Falls back to the booking date.
+    pub value_date: String,
+    pub reference: String,
+    pub counterparty: String,
+    pub iban: String,
+    pub end_to_end_id: String,
+    pub status: String,
+    pub transaction_code: String,
+    /// Id the bank gives the booking, stays the same across downloads.
+    pub transaction_id: String,
+    /// What the bank calls the kind of booking, like `DAUERAUFTRAG`.
+    pub posting_text: String,
+    /// The whole remittance information when the reference is only a part
+    /// of it.
+    pub purpose: String,
+}
+
+impl StatementEntry {
+    /// The fields in the order of the items of a statement row:
+    ///
+    /// | 0 | 1 | 2 | 3 | 4 | 5 | 6 | 7 | 8 | 9 | 10 | 11 | 12 |
+    /// |---|---|---|---|---|---|---|---|---|---|----|----|----|
+    /// | amount | currency | booking date | value date | reference | counterparty | iban | end to end id | status | transaction code | transaction id | posting text | purpose |
+    pub fn into_fields(self) -> Vec<String> {
+        vec![
+            self.amount,
+            self.currency,
+            self.booking_date,
+            self.value_date,
+            self.reference,
+            self.counterparty,
+            self.iban,
+            self.end_to_end_id,
+            self.status,
+            self.transaction_code,
+            self.transaction_id,
+            self.posting_text,
+            self.purpose,
+        ]
+    }
+}
+
+/// How every statement profile maps the fields of a [`StatementEntry`],
+/// the positions are the ones of [`StatementEntry::into_fields`].
+#[derive(Debug, Clone)]
+pub struct StatementColumns {
+    pub amount: ExpenseColumn,
+    pub datetime: DateTimeColumn,
+    pub currency: CurrencyColumn,
+    pub other_data: Vec<(usize, ParsableWrapper)>,
+}
+
+impl StatementColumns {
+    pub fn new(format: FileFormat) -> Self {
+        // OFX and QIF come from american software, bookings without a
+        // currency are in dollars there
+        let currency = match format {
+            FileFormat::Ofx | FileFormat::Qif => "USD",
+            _ => "EUR",
+        };
+        let description = |desc: &str| -> ParsableWrapper {
+            Description::init(desc.to_owned()).into()
+        };
+        let special = |special_type, desc: &str| -> ParsableWrapper {
+            Special::init(special_type, desc.to_owned()).into()
+        };
+        Self {
+            amount: ExpenseColumn::combined(0, &NumberFormat::American),
+            datetime: DateTimeColumn::date(2, String::from(DATE_FORMAT)),
+            currency: CurrencyColumn::Column(
+                1,
+                CurrencyCode(currency.parse::<Currency>().unwrap()),
+            ),
+            other_data: vec![
+                (
+                    3,
+                    SecondaryDate(
+                        ExpenseDate(String::from(DATE_FORMAT)),
+                        DateRole::Value,
+                    )
+                    .into(),
+                ),
+                (4, description("reference")),
+                (5, description("counterparty")),
+                (6, description("iban")),
+                (7, description("end to end id")),
+                (8, special(SpecialType::TransactionState, "status")),
+                (9, special(SpecialType::TransactionType, "type")),
+                (10, description("transaction id")),
+                (11, description("posting text")),
+                (12, description("purpose")),
+            ],
+        }
+    }
+}
+
+/// Dates of a [`StatementEntry`] are written in this format.
+const DATE_FORMAT: &str = "%Y-%m-%d";
+
+/// Opening and closing balance of one statement in a file, kept with the
+/// import instead of its rows. Amounts are written like the ones of a
+/// [`StatementEntry`], a balance the statement leaves out is empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StatementBalance {
+    pub currency: String,
+    pub opening: String,
+    pub closing: String,
+}
@@ -0,0 +1,168 @@
+use std::borrow::Cow;
+
+use itertools::Itertools;
+use roxmltree::{Document, Node};
+
+use crate::model::profiles::error::ProfileError;
+
+use super::StatementEntry;
+
+/// Stands in for the namespace of a prefix that was declared outside of
+/// the entry, only the local names of the elements are ever looked at.
+const DETACHED_NAMESPACE: &str = "urn:bread-manager:detached";
+
+/// The raw xml of every `<Ntry>` of all statements in the file, in the order
+/// of the file. Content that is no xml has no entries.
+pub fn records(content: &str) -> Vec<String> {
+    let Ok(document) = Document::parse(content) else {
+        return vec![];
+    };
+    document
+        .descendants()
+        .filter(|node| node.tag_name().name() == "Ntry")
+        .map(|node| content[node.range()].to_owned())
+        .collect()
+}
+
+/// Reads one `<Ntry>` as returned by [`records`]. Namespaces are ignored, so
+/// every version of camt.053 works. A batch booking with several
+/// transaction details is one entry with the total amount and the details
+/// of the first transaction.
+pub fn entry(record: &str) -> Result<StatementEntry, ProfileError> {
+    let xml = match prefix(record) {
+        Some(prefix) => Cow::Owned(format!(
+            r#"<Detached xmlns:{prefix}="{DETACHED_NAMESPACE}">{record}</Detached>"#
+        )),
+        None => Cow::Borrowed(record),
+    };
+    let document = Document::parse(&xml).map_err(ProfileError::statement)?;
+    let entry = document
+        .descendants()
+        .find(|node| node.tag_name().name() == "Ntry")
+        .ok_or_else(|| ProfileError::statement("it is no <Ntry> element"))?;
+    let missing =
+        |path: &str| ProfileError::statement(format!("<{path}> is missing"));
+
+    let amount = find(entry, &["Amt"]).ok_or_else(|| missing("Amt"))?;
+    let currency = amount
+        .attribute("Ccy")
+        .ok_or_else(|| missing("Amt Ccy"))?
+        .to_owned();
+    let amount = amount
+        .text()
+        .map(str::trim)
+        .filter(|amount| !amount.is_empty())
+        .ok_or_else(|| missing("Amt"))?;
+    let debit = match text(entry, &["CdtDbtInd"]).as_deref() {
+        Some("DBIT") => true,
+        Some("CRDT") => false,
+        Some(other) => {
+            return Err(ProfileError::statement(format!(
+                "<CdtDbtInd> is {other} instead of CRDT or DBIT"
+            )))
+        }
+        None => return Err(missing("CdtDbtInd")),
+    };
+    let booking_date =
+        date(entry, "BookgDt").ok_or_else(|| missing("BookgDt"))?;
+    let value_date =
+        date(entry, "ValDt").unwrap_or_else(|| booking_date.clone());
+
+    let details = find(entry, &["NtryDtls", "TxDtls"]);
+    let detail = |path: &[&str]| details.and_then(|node| text(node, path));
+    // the other party is the creditor of a debit and the debtor of a credit
+    let (party, account) = match debit {
+        true => ("Cdtr", "CdtrAcct"),
+        false => ("Dbtr", "DbtrAcct"),
+    };
+
+    Ok(StatementEntry {
+        amount: match debit {
+            true => format!("-{amount}"),
+            false => amount.to_owned(),
+        },
+        currency,
+        booking_date,
+        value_date,
+        reference: details
+            .and_then(|node| find(node, &["RmtInf"]))
+            .map(|info| {
+                info.children()
+                    .filter(|node| node.tag_name().name() == "Ustrd")
+                    .filter_map(|node| node.text())
+                    .map(str::trim)
+                    .join(" ")
+            })
+            .filter(|reference| !reference.is_empty())
+            .or_else(|| text(entry, &["AddtlNtryInf"]))
+            .unwrap_or_default(),
+        counterparty: detail(&["RltdPties", party, "Nm"])
+            .or_else(|| detail(&["RltdPties", party, "Pty", "Nm"]))
+            .unwrap_or_default(),
+        iban: detail(&["RltdPties", account, "Id", "IBAN"]).unwrap_or_default(),
+        end_to_end_id: detail(&["Refs", "EndToEndId"]).unwrap_or_default(),
+        status: text(entry, &["Sts"])
+            .or_else(|| text(entry, &["Sts", "Cd"]))
+            .unwrap_or_default(),
+        transaction_code: transaction_code(entry)
+            .or_else(|| details.and_then(transaction_code))
+            .unwrap_or_default(),
+        ..Default::default()
+    })
+}
+
+/// The prefix of the `<Ntry>` element itself, its declaration is lost once
+/// the entry is cut out of the file.
+fn prefix(record: &str) -> Option<&str> {
+    let tag = record.trim_start().strip_prefix('<')?;
+    let (prefix, _) = tag.split_once(':')?;
+    prefix
+        .chars()
+        .all(|char| char.is_alphanumeric() || matches!(char, '_' | '-' | '.'))
+        .then_some(prefix)
+}
+
+/// Follows the local names of `path` through the first matching children.
+fn find<'a, 'input>(
+    node: Node<'a, 'input>,
+    path: &[&str],
+) -> Option<Node<'a, 'input>> {
+    path.iter().try_fold(node, |node, name| {
+        node.children()
+            .find(|child| child.tag_name().name() == *name)
+    })
+}
+
+fn text(node: Node, path: &[&str]) -> Option<String> {
+    find(node, path)?
+        .text()
+        .map(str::trim)
+        .filter(|text| !text.is_empty())
+        .map(str::to_owned)
+}
+
+/// A date is either a `<Dt>` or a `<DtTm>` whose time is dropped.
+fn date(entry: Node, name: &str) -> Option<String> {
+    let date = find(entry, &[name])?;
+    text(date, &["Dt"]).or_else(|| {
+        text(date, &["DtTm"])
+            .map(|datetime| datetime.chars().take(10).collect())
+    })
+}
+
+/// Domain, family and sub family like `PMNT/RCDT/ESCT`, or the proprietary
+/// code of the bank.
+fn transaction_code(node: Node) -> Option<String> {
+    let code = find(node, &["BkTxCd"])?;
+    let domain = || {
+        Some(
+            [
+                text(code, &["Domn", "Cd"])?,
+                text(code, &["Domn", "Fmly", "Cd"])?,
+                text(code, &["Domn", "Fmly", "SubFmlyCd"])?,
+            ]
+            .join("/"),
+        )
+    };
+    domain().or_else(|| text(code, &["Prtry", "Cd"]))
+}
@@ -0,0 +1,398 @@
+use std::{iter, mem};
+
+use chrono::{Datelike, NaiveDate};
+use itertools::Itertools;
+
+use crate::model::profiles::error::ProfileError;
+
+use super::{StatementBalance, StatementEntry};
+
+/// Keys of the SEPA purpose in a `:86:`, a key runs until the next one.
+const SEPA_KEYS: [&str; 8] = [
+    "EREF+", "KREF+", "MREF+", "CRED+", "DEBT+", "SVWZ+", "ABWA+", "ABWE+",
+];
+
+/// Every `:61:` of all statements in the file together with its `:86:`,
+/// preceded by the currency of its statement on a line of its own. The
+/// balance lines are left out, so a booking exported again with another
+/// statement is still an overlap. They are read by [`balances`] instead.
+pub fn records(content: &str) -> Vec<String> {
+    let fields = fields(content);
+    statements(&fields)
+        .into_iter()
+        .flat_map(|statement| {
+            let currency = statement.currency();
+            statement.bookings.into_iter().map(move |booking| {
+                iter::once(currency.as_str())
+                    .chain(
+                        booking
+                            .into_iter()
+                            .flat_map(|field| field.lines.iter().copied()),
+                    )
+                    .join("\n")
+            })
+        })
+        .collect()
+}
+
+/// Opening and closing balance of every statement in the file.
+pub fn balances(content: &str) -> Result<Vec<StatementBalance>, ProfileError> {
+    let fields = fields(content);
+    statements(&fields)
+        .iter()
+        .map(|statement| {
+            let balance = |field: Option<&Field>| {
+                field
+                    .map(|field| Balance::parse(&field.value()))
+                    .transpose()
+            };
+            let opening = balance(statement.opening)?;
+            let closing = balance(statement.closing)?;
+            Ok(StatementBalance {
+                currency: opening
+                    .as_ref()
+                    .or(closing.as_ref())
+                    .map(|balance| balance.currency.clone())
+                    .unwrap_or_default(),
+                opening: opening
+                    .map(|balance| balance.amount)
+                    .unwrap_or_default(),
+                closing: closing
+                    .map(|balance| balance.amount)
+                    .unwrap_or_default(),
+            })
+        })
+        .collect()
+}
+
+/// Reads one booking as returned by [`records`]. The `:86:` is split into
+/// its sub fields when it is structured: `?00` is the posting text, `?20`
+/// to `?29` and `?60` to `?63` the purpose, `?31` the IBAN and `?32` and
+/// `?33` the name of the other party. Each of them is a field of its own,
+/// the reference and end to end id are taken out of the purpose.
+pub fn entry(record: &str) -> Result<StatementEntry, ProfileError> {
+    let (currency, booking) = record.split_once('\n').unwrap_or((record, ""));
+    if currency.is_empty() {
+        return Err(ProfileError::statement(
+            "neither :60F: nor :62F: is there to tell the currency",
+        ));
+    }
+    let fields = fields(booking);
+    let field =
+        |tags: &[&str]| fields.iter().find(|field| tags.contains(&field.tag));
+    let booking = field(&["61"])
+        .ok_or_else(|| ProfileError::statement(":61: is missing"))?;
+    let line = StatementLine::parse(booking.first())?;
+    let details = field(&["86"])
+        .map(|field| Details::parse(&field.value()))
+        .unwrap_or_default();
+    let purpose = details.purpose.trim();
+    let reference = sepa_value(purpose, "SVWZ+").unwrap_or(purpose);
+
+    Ok(StatementEntry {
+        amount: line.amount,
+        currency: currency.to_owned(),
+        booking_date: line.booking_date.format("%Y-%m-%d").to_string(),
+        value_date: line.value_date.format("%Y-%m-%d").to_string(),
+        reference: reference.to_owned(),
+        counterparty: details.name,
+        iban: details.iban,
+        end_to_end_id: sepa_value(&details.purpose, "EREF+")
+            .unwrap_or_default()
+            .to_owned(),
+        status: String::new(),
+        transaction_code: line.kind,
+        transaction_id: String::new(),
+        posting_text: details.posting_text,
+        purpose: match reference == purpose {
+            true => String::new(),
+            false => purpose.to_owned(),
+        },
+    })
+}
+
+/// A tag with its raw lines, the first line starts with the tag.
+struct Field<'a> {
+    tag: &'a str,
+    lines: Vec<&'a str>,
+}
+
+impl Field<'_> {
+    fn first(&self) -> &str {
+        split_tag(self.lines[0]).map_or("", |(_, value)| value)
+    }
+
+    /// Lines are cut at a fixed width, so they are joined without a
+    /// separator.
+    fn value(&self) -> String {
+        iter::once(self.first())
+            .chain(self.lines[1..].iter().copied())
+            .collect()
+    }
+}
+
+fn fields(content: &str) -> Vec<Field<'_>> {
+    let mut fields: Vec<Field> = vec![];
+    for line in content.lines().map(str::trim_end) {
+        match split_tag(line) {
+            Some((tag, _)) => fields.push(Field {
+                tag,
+                lines: vec![line],
+            }),
+            // message trailers and the blocks of a SWIFT envelope
+            None if line.is_empty()
+                || line == "-"
+                || line.starts_with("-}")
+                || line.starts_with('{') => {}
+            None => {
+                if let Some(field) = fields.last_mut() {
+                    field.lines.push(line);
+                }
+            }
+        }
+    }
+    fields
+}
+
+/// Splits `:60F:C250301EUR1520,40` into `60F` and the value.
+fn split_tag(line: &str) -> Option<(&str, &str)> {
+    let (tag, value) = line.strip_prefix(':')?.split_once(':')?;
+    let digits = tag.get(..2)?;
+    (digits.chars().all(|char| char.is_ascii_digit())
+        && tag.len() <= 3
+        && tag[2..].chars().all(|char| char.is_ascii_uppercase()))
+    .then_some((tag, value))
+}
+
+/// The balance lines of a statement and its bookings, each a `:61:` with
+/// the `:86:` that follows it.
+#[derive(Default)]
+struct Statement<'a> {
+    opening: Option<&'a Field<'a>>,
+    closing: Option<&'a Field<'a>>,
+    bookings: Vec<Vec<&'a Field<'a>>>,
+}
+
+impl Statement<'_> {
+    /// Both balances are in the currency of the account.
+    fn currency(&self) -> String {
+        self.opening
+            .or(self.closing)
+            .and_then(|field| Balance::parse(&field.value()).ok())
+            .map(|balance| balance.currency)
+            .unwrap_or_default()
+    }
+
+    fn is_empty(&self) -> bool {
+        self.opening.is_none()
+            && self.closing.is_none()
+            && self.bookings.is_empty()
+    }
+}
+
+fn statements<'a>(fields: &'a [Field<'a>]) -> Vec<Statement<'a>> {
+    let mut statements = vec![];
+    let mut current = Statement::default();
+
+    for field in fields {
+        match field.tag {
+            // a statement without closing balance still has its bookings
+            "20" => statements.push(mem::take(&mut current)),
+            "60F" | "60M" => current.opening = Some(field),
+            "61" => current.bookings.push(vec![field]),
+            "86" => {
+                if let Some(booking) = current
+                    .bookings
+                    .last_mut()
+                    .filter(|booking| booking.len() == 1)
+                {
+                    booking.push(field);
+                }
+            }
+            "62F" | "62M" => {
+                current.closing = Some(field);
+                statements.push(mem::take(&mut current));
+            }
+            _ => {}
+        }
+    }
+    statements.push(current);
+    statements.retain(|statement| !statement.is_empty());
+    statements
+}
+
+/// Signed amounts with a `.` as decimal separator, like every statement
+/// format hands them to the profile.
+fn amount(negative: bool, amount: &str) -> Option<String> {
+    let valid = !amount.is_empty()
+        && amount
+            .chars()
+            .all(|char| char.is_ascii_digit() || char == ',');
+    let amount = amount.replace(',', ".");
+    valid.then(|| match negative {
+        true => format!("-{amount}"),
+        false => amount,
+    })
+}
+
+struct Balance {
+    amount: String,
+    currency: String,
+}
+
+impl Balance {
+    /// `C250301EUR1520,40`: mark, date, currency and amount.
+    fn parse(value: &str) -> Result<Self, ProfileError> {
+        let error =
+            || ProfileError::statement(format!("{value} is no balance"));
+        let negative = match value.get(..1) {
+            Some("C") => false,
+            Some("D") => true,
+            _ => return Err(error()),
+        };
+        let currency = value.get(7..10).ok_or_else(error)?;
+        let amount = value
+            .get(10..)
+            .and_then(|str| amount(negative, str))
+            .ok_or_else(error)?;
+        Ok(Self {
+            amount,
+            currency: currency.to_owned(),
+        })
+    }
+}
+
+struct StatementLine {
+    value_date: NaiveDate,
+    booking_date: NaiveDate,
+    amount: String,
+    /// Transaction type like `NTRF`.
+    kind: String,
+}
+
+impl StatementLine {
+    /// `2503030303CR2450,00NTRFNONREF`: value date, optional booking date,
+    /// debit or credit mark, optional funds code, amount and type.
+    fn parse(line: &str) -> Result<Self, ProfileError> {
+        let error = || {
+            ProfileError::statement(format!(":61:{line} is no statement line"))
+        };
+        let value_date = line
+            .get(..6)
+            .and_then(|date| NaiveDate::parse_from_str(date, "%y%m%d").ok())
+            .ok_or_else(error)?;
+        let mut rest = &line[6..];
+        let mut booking_date = value_date;
+        if let Some(date) = rest
+            .get(..4)
+            .filter(|date| date.chars().all(|char| char.is_ascii_digit()))
+        {
+            booking_date =
+                booking_date_near(value_date, date).ok_or_else(error)?;
+            rest = &rest[4..];
+        }
+        // a reversed credit takes money from the account
+        let (negative, rest) = ["RC", "RD", "C", "D"]
+            .into_iter()
+            .find_map(|mark| {
+                rest.strip_prefix(mark)
+                    .map(|rest| (matches!(mark, "D" | "RC"), rest))
+            })
+            .ok_or_else(error)?;
+        let rest = rest
+            .strip_prefix(|char: char| char.is_ascii_alphabetic())
+            .unwrap_or(rest);
+        let end = rest
+            .find(|char: char| !(char.is_ascii_digit() || char == ','))
+            .unwrap_or(rest.len());
+        let amount = amount(negative, &rest[..end]).ok_or_else(error)?;
+
+        Ok(Self {
+            value_date,
+            booking_date,
+            amount,
+            kind: rest[end..].chars().take(4).collect(),
+        })
+    }
+}
+
+/// The booking date only has month and day, around new year it is in
+/// another year than the value date.
+fn booking_date_near(
+    value_date: NaiveDate,
+    month_day: &str,
+) -> Option<NaiveDate> {
+    let month = month_day[..2].parse().ok()?;
+    let day = month_day[2..].parse().ok()?;
+    let year = value_date.year();
+    [year, year + 1, year - 1]
+        .into_iter()
+        .filter_map(|year| NaiveDate::from_ymd_opt(year, month, day))
+        .min_by_key(|date| (*date - value_date).num_days().abs())
+}
+
+#[derive(Default)]
+struct Details {
+    posting_text: String,
+    purpose: String,
+    name: String,
+    iban: String,
+}
+
+impl Details {
+    /// A structured `:86:` starts with a three digit business code and the
+    /// separator of its sub fields, anything else is free text.
+    fn parse(value: &str) -> Self {
+        let separator = value
+            .get(..3)
+            .filter(|code| code.chars().all(|char| char.is_ascii_digit()))
+            .and_then(|_| value[3..].chars().next())
+            .filter(|char| !char.is_alphanumeric() && !char.is_whitespace());
+        let Some(separator) = separator else {
+            return Self {
+                purpose: value.trim().to_owned(),
+                ..Default::default()
+            };
+        };
+
+        let mut details = Self::default();
+        for sub_field in value[3..].split(separator).skip(1) {
+            let (Some(code), Some(content)) =
+                (sub_field.get(..2), sub_field.get(2..))
+            else {
+                continue;
+            };
+            match code {
+                "00" => details.posting_text.push_str(content),
+                "31" => details.iban.push_str(content),
+                "32" | "33" => details.name.push_str(content),
+                code if ("20"..="29").contains(&code)
+                    || ("60"..="63").contains(&code) =>
+                {
+                    let starts_key =
+                        SEPA_KEYS.iter().any(|key| content.starts_with(key));
+                    if starts_key && !details.purpose.is_empty() {
+                        details.purpose.push(' ');
+                    }
+                    details.purpose.push_str(content)
+                }
+                _ => {}
+            }
+        }
+        details.posting_text = details.posting_text.trim().to_owned();
+        details.name = details.name.trim().to_owned();
+        details.iban = details.iban.trim().to_owned();
+        details
+    }
+}
+
+fn sepa_value<'a>(purpose: &'a str, key: &str) -> Option<&'a str> {
+    let start = purpose.find(key)? + key.len();
+    let rest = &purpose[start..];
+    let end = SEPA_KEYS
+        .iter()
+        .filter_map(|key| rest.find(key))
+        .min()
+        .unwrap_or(rest.len());
+    Some(rest[..end].trim())
+}
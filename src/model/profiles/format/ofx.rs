@@ -0,0 +1,102 @@
+use chrono::NaiveDate;
+
+use crate::model::profiles::error::ProfileError;
+
+use super::StatementEntry;
+
+const OPEN: &str = "<STMTTRN>";
+const CLOSE: &str = "</STMTTRN>";
+
+/// Every `<STMTTRN>` of the bank and credit card statements in the file,
+/// preceded by the `<CURDEF>` of its statement. Both the SGML of OFX 1.x,
+/// which leaves elements with a value unclosed, and the XML of OFX 2.x are
+/// read as text, so the header of either version does not matter.
+pub fn records(content: &str) -> Vec<String> {
+    let mut records = vec![];
+    let mut offset = 0;
+    while let Some(start) = content[offset..].find(OPEN) {
+        let start = offset + start;
+        let Some(end) = content[start..].find(CLOSE) else {
+            break;
+        };
+        let end = start + end + CLOSE.len();
+        let transaction = &content[start..end];
+        records.push(match value(&content[..start], "CURDEF", true) {
+            Some(currency) => format!("<CURDEF>{currency}\n{transaction}"),
+            None => transaction.to_owned(),
+        });
+        offset = end;
+    }
+    records
+}
+
+/// Reads one transaction as returned by [`records`]. An amount in another
+/// currency than the statement has its own `<CURRENCY>`.
+pub fn entry(record: &str) -> Result<StatementEntry, ProfileError> {
+    let required = |tag: &str| {
+        value(record, tag, false).ok_or_else(|| {
+            ProfileError::statement(format!("<{tag}> is missing"))
+        })
+    };
+    let booking_date = date(&required("DTPOSTED")?)?;
+    let value_date = value(record, "DTAVAIL", false)
+        .map(|available| date(&available))
+        .transpose()?
+        .unwrap_or_else(|| booking_date.clone());
+    let currency = match record.contains("<CURRENCY>") {
+        true => value(record, "CURSYM", false),
+        false => value(record, "CURDEF", false),
+    };
+
+    Ok(StatementEntry {
+        // decimal commas are allowed, grouping is not
+        amount: required("TRNAMT")?.replace(',', "."),
+        currency: currency.unwrap_or_default(),
+        booking_date,
+        value_date,
+        reference: value(record, "MEMO", false).unwrap_or_default(),
+        counterparty: value(record, "NAME", false).unwrap_or_default(),
+        transaction_code: value(record, "TRNTYPE", false).unwrap_or_default(),
+        transaction_id: required("FITID")?,
+        ..Default::default()
+    })
+}
+
+/// The `<FITID>` a bank keeps for a transaction across downloads.
+pub fn fitid(record: &str) -> Option<String> {
+    value(record, "FITID", false)
+}
+
+/// Text after `<TAG>` up to the next tag, which is the closing tag in XML
+/// and the next element in SGML. `last` takes the last occurrence.
+fn value(content: &str, tag: &str, last: bool) -> Option<String> {
+    let open = format!("<{tag}>");
+    let start = match last {
+        true => content.rfind(&open)?,
+        false => content.find(&open)?,
+    } + open.len();
+    let rest = &content[start..];
+    let value = rest[..rest.find('<').unwrap_or(rest.len())].trim();
+    (!value.is_empty()).then(|| unescape(value))
+}
+
+fn unescape(value: &str) -> String {
+    value
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&nbsp;", " ")
+        .replace("&amp;", "&")
+}
+
+/// `20250303120000.000[-5:EST]`, only the date is kept.
+fn date(datetime: &str) -> Result<String, ProfileError> {
+    datetime
+        .get(..8)
+        .and_then(|date| NaiveDate::parse_from_str(date, "%Y%m%d").ok())
+        .map(|date| date.format("%Y-%m-%d").to_string())
+        .ok_or_else(|| {
+            ProfileError::statement(format!("{datetime} is no date"))
+        })
+}
@@ -0,0 +1,152 @@
+use chrono::NaiveDate;
+use itertools::Itertools;
+
+use crate::model::profiles::error::ProfileError;
+
+use super::StatementEntry;
+
+/// Account types whose records are bookings. Lists like categories and
+/// memorized transactions, and investment accounts, are left out.
+const BOOKING_TYPES: [&str; 5] = ["Bank", "Cash", "CCard", "Oth A", "Oth L"];
+
+/// Every record of a booking account, without its closing `^`.
+pub fn records(content: &str) -> Vec<String> {
+    let mut records = vec![];
+    let mut bookings = false;
+    let mut record = vec![];
+    for line in content.lines().map(str::trim_end) {
+        if let Some(header) = line.strip_prefix('!') {
+            if let Some(kind) = header.strip_prefix("Type:") {
+                bookings = BOOKING_TYPES
+                    .iter()
+                    .any(|booking| booking.eq_ignore_ascii_case(kind.trim()));
+            } else if header.eq_ignore_ascii_case("Account") {
+                bookings = false;
+            }
+            record.clear();
+        } else if line == "^" {
+            if bookings && !record.is_empty() {
+                records.push(record.join("\n"));
+            }
+            record.clear();
+        } else if !line.is_empty() {
+            record.push(line);
+        }
+    }
+    records
+}
+
+/// Reads one record as returned by [`records`]. Split lines and the
+/// category are left out, QIF has no currency so the profile falls back to
+/// the default currency of its currency column.
+pub fn entry(record: &str) -> Result<StatementEntry, ProfileError> {
+    let mut entry = StatementEntry::default();
+    let mut date = None;
+    let mut amount = None;
+    for line in record.lines() {
+        let mut chars = line.chars();
+        let Some(code) = chars.next() else {
+            continue;
+        };
+        let value = chars.as_str().trim();
+        match code {
+            'D' => date = Some(value),
+            // newer Quicken versions repeat the amount as U
+            'T' | 'U' => {
+                amount.get_or_insert(value);
+            }
+            'P' => entry.counterparty = value.to_owned(),
+            'M' => entry.reference = value.to_owned(),
+            'N' => entry.transaction_code = value.to_owned(),
+            'C' => {
+                entry.status = match value {
+                    "*" | "c" => String::from("cleared"),
+                    "X" | "R" => String::from("reconciled"),
+                    other => other.to_owned(),
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let date =
+        date.ok_or_else(|| ProfileError::statement("the D line is missing"))?;
+    entry.booking_date = parse_date(date)
+        .ok_or_else(|| ProfileError::statement(format!("{date} is no date")))?
+        .format("%Y-%m-%d")
+        .to_string();
+    entry.value_date = entry.booking_date.clone();
+    let amount = amount
+        .ok_or_else(|| ProfileError::statement("the T line is missing"))?;
+    entry.amount = decimal_amount(amount).ok_or_else(|| {
+        ProfileError::statement(format!("{amount} is no amount"))
+    })?;
+    Ok(entry)
+}
+
+/// Quicken writes `3/15'25` or `03/15/1998`, other programs `15.03.2025`
+/// or `2025-03-15`. Slashes are read month first unless the first number
+/// cannot be a month. Two digit years after an apostrophe are in this
+/// century, otherwise up to 69 as well.
+fn parse_date(date: &str) -> Option<NaiveDate> {
+    let parts = date
+        .split(['/', '\'', '.', '-'])
+        .map(str::trim)
+        .collect_vec();
+    let [first, second, third] = parts.as_slice() else {
+        return None;
+    };
+    let (year, month, day) = if first.len() == 4 {
+        (first, second, third)
+    } else if date.contains('.') || first.parse::<u32>().ok()? > 12 {
+        (third, second, first)
+    } else {
+        (third, first, second)
+    };
+
+    let mut year = year.parse::<i32>().ok()?;
+    if year < 100 {
+        year += match date.contains('\'') || year < 70 {
+            true => 2000,
+            false => 1900,
+        };
+    }
+    NaiveDate::from_ymd_opt(year, month.parse().ok()?, day.parse().ok()?)
+}
+
+/// `-1,234.56` or `-1.234,56` as `-1234.56`. The last separator is the
+/// decimal one when one or two digits follow it, every other separator
+/// groups thousands.
+fn decimal_amount(amount: &str) -> Option<String> {
+    let amount = amount
+        .chars()
+        .filter(|char| !char.is_whitespace())
+        .collect::<String>();
+    let (sign, digits) = match amount.strip_prefix('-') {
+        Some(digits) => ("-", digits),
+        None => ("", amount.strip_prefix('+').unwrap_or(&amount)),
+    };
+    let (integer, fraction) = match digits
+        .rfind([',', '.'])
+        .filter(|pos| (1..=2).contains(&(digits.len() - pos - 1)))
+    {
+        Some(pos) => (&digits[..pos], &digits[pos + 1..]),
+        None => (digits, ""),
+    };
+    let integer = integer
+        .chars()
+        .filter(|char| !matches!(char, ',' | '.'))
+        .collect::<String>();
+
+    let all_digits = |str: &str| str.chars().all(|char| char.is_ascii_digit());
+    if (integer.is_empty() && fraction.is_empty())
+        || !all_digits(&integer)
+        || !all_digits(fraction)
+    {
+        return None;
+    }
+    Some(match fraction.is_empty() {
+        true => format!("{sign}{integer}"),
+        false => format!("{sign}{integer}.{fraction}"),
+    })
+}
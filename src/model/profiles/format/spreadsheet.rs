@@ -0,0 +1,137 @@
+use std::path::Path;
+
+use calamine::{open_workbook_auto, Data, Reader};
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use itertools::Itertools;
+
+use crate::model::profiles::{error::ProfileError, tokenizer::Cell};
+
+/// A read sheet is csv with these, whatever the profile has set.
+pub const DELIMITER: char = ',';
+pub const QUOTE: char = '"';
+
+const DATE: &str = "%Y-%m-%d";
+const DATETIME: &str = "%Y-%m-%d %H:%M:%S";
+/// Marks a date cell without a valid date, followed by its raw value.
+const INVALID_DATE: &str = "#DATE";
+
+/// Reads `sheet` of an xlsx, xls or ods workbook as csv, the first sheet if
+/// `sheet` is empty. Empty rows and columns in front of the used cells are
+/// kept, so positions and margins are the ones of the sheet. Text cells are
+/// quoted, number and date cells are not, so [`cell`] can tell them apart
+/// again. Numbers are written like `-1234.5` and dates like `2025-03-01` or
+/// `2025-03-01 12:30:00`, a date cell without a valid date makes the row
+/// fail in [`cell`].
+pub fn read(path: &Path, sheet: &str) -> Result<String, ProfileError> {
+    let error = |err| ProfileError::file(path, err);
+    let mut workbook = open_workbook_auto(path).map_err(error)?;
+    let names = workbook.sheet_names();
+    let name = match sheet.is_empty() {
+        true => names.first(),
+        false => names.iter().find(|name| name.as_str() == sheet),
+    }
+    .ok_or_else(|| {
+        ProfileError::file(
+            path,
+            format!(
+                "it has no sheet {sheet}, only {}",
+                names.iter().join(", ")
+            ),
+        )
+    })?
+    .clone();
+    let range = workbook.worksheet_range(&name).map_err(error)?;
+
+    let (top, left) = range.start().unwrap_or_default();
+    let csv = (0..top)
+        .map(|_| String::new())
+        .chain(range.rows().map(|row| {
+            (0..left as usize)
+                .map(|_| String::new())
+                .chain(row.iter().map(render))
+                .join(&DELIMITER.to_string())
+        }))
+        .join("\n");
+    Ok(csv)
+}
+
+/// What an unquoted field of [`read`] holds, an empty one is an empty cell.
+pub fn cell(field: &str) -> Result<Cell, ProfileError> {
+    if field.is_empty() {
+        return Ok(Cell::Text);
+    }
+    if let Some(value) = field.strip_prefix(INVALID_DATE) {
+        let value = value.parse().unwrap_or(f64::NAN);
+        return Err(ProfileError::invalid_sheet_date(value));
+    }
+    Ok(NaiveDateTime::parse_from_str(field, DATETIME)
+        .ok()
+        .or_else(|| {
+            NaiveDate::parse_from_str(field, DATE)
+                .ok()
+                .map(|date| date.and_time(NaiveTime::MIN))
+        })
+        .map_or(Cell::Number, Cell::DateTime))
+}
+
+fn render(cell: &Data) -> String {
+    if let Some(datetime) = datetime(cell) {
+        return match datetime.time() == NaiveTime::MIN {
+            true => datetime.format(DATE).to_string(),
+            false => datetime.format(DATETIME).to_string(),
+        };
+    }
+    match cell {
+        Data::Int(value) => value.to_string(),
+        Data::Float(value) => decimal(*value),
+        Data::String(value)
+        | Data::DateTimeIso(value)
+        | Data::DurationIso(value) => quote(value),
+        Data::Bool(value) => quote(&value.to_string()),
+        Data::Error(error) => quote(&error.to_string()),
+        Data::DateTime(datetime) => {
+            format!("{INVALID_DATE}{}", datetime.as_f64())
+        }
+        Data::Empty => String::new(),
+    }
+}
+
+/// Excel dates are numbers with a date format, ods dates are iso strings.
+fn datetime(cell: &Data) -> Option<NaiveDateTime> {
+    match cell {
+        Data::DateTime(datetime) => datetime.as_datetime(),
+        Data::DateTimeIso(iso) => {
+            iso.parse::<NaiveDateTime>().ok().or_else(|| {
+                iso.parse::<NaiveDate>()
+                    .ok()
+                    .map(|date| date.and_time(NaiveTime::MIN))
+            })
+        }
+        _ => None,
+    }
+}
+
+/// Without an exponent and rounded to the 15 significant digits a float
+/// holds, so `0.1 + 0.2` is written as `0.3` and not cut to cents.
+fn decimal(value: f64) -> String {
+    // the logarithm of zero has no digits to count
+    if value == 0. {
+        return "0".to_owned();
+    }
+    let decimals = (14 - value.abs().log10().floor() as i32).max(0) as usize;
+    let rendered = format!("{value:.decimals$}");
+    match rendered.contains('.') {
+        true => rendered
+            .trim_end_matches('0')
+            .trim_end_matches('.')
+            .to_owned(),
+        false => rendered,
+    }
+}
+
+fn quote(field: &str) -> String {
+    format!(
+        "{QUOTE}{}{QUOTE}",
+        field.replace(QUOTE, &format!("{QUOTE}{QUOTE}"))
+    )
+}
@@ -0,0 +1,166 @@
+use std::collections::BTreeSet;
+
+use itertools::Itertools;
+
+use super::{
+    columns::{
+        other::{Description, Special},
+        time::StandaloneTime,
+        ParsableWrapper,
+    },
+    Profile,
+};
+
+/// A setting that differs between two versions of a profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProfileChange {
+    pub setting: String,
+    pub before: String,
+    pub after: String,
+}
+
+impl ProfileChange {
+    fn new(setting: impl Into<String>, before: String, after: String) -> Self {
+        Self {
+            setting: setting.into(),
+            before,
+            after,
+        }
+    }
+}
+
+impl Profile {
+    /// Every version of this profile, oldest first. Follows the links to
+    /// the previous versions through `profiles` in both directions.
+    pub fn versions<'a>(&'a self, profiles: &'a [Profile]) -> Vec<&'a Profile> {
+        let mut oldest = self;
+        while let Some(previous) = oldest
+            .previous
+            .and_then(|uuid| profiles.iter().find(|p| p.uuid == uuid))
+        {
+            oldest = previous;
+        }
+
+        let mut versions = vec![oldest];
+        while let Some(next) = profiles
+            .iter()
+            .find(|p| p.previous == Some(versions[versions.len() - 1].uuid))
+        {
+            versions.push(next);
+        }
+        versions
+    }
+
+    /// The settings of this version which differ from `previous`. The uuids
+    /// of descriptions change with every version and are not compared.
+    pub fn changes_since(&self, previous: &Profile) -> Vec<ProfileChange> {
+        let mut changes = vec![];
+        let mut compare = |setting: &str, before: String, after: String| {
+            if before != after {
+                changes.push(ProfileChange::new(setting, before, after));
+            }
+        };
+
+        compare("name", previous.name.clone(), self.name.clone());
+        compare(
+            "margins",
+            format!("{:?}", previous.margins),
+            format!("{:?}", self.margins),
+        );
+        compare(
+            "delimiter",
+            previous.delimiter.to_string(),
+            self.delimiter.to_string(),
+        );
+        compare(
+            "quote",
+            previous.quote.map(String::from).unwrap_or_default(),
+            self.quote.map(String::from).unwrap_or_default(),
+        );
+        compare(
+            "encoding",
+            previous.encoding.to_string(),
+            self.encoding.to_string(),
+        );
+        compare(
+            "format",
+            previous.format.to_string(),
+            self.format.to_string(),
+        );
+        compare("sheet", previous.sheet.clone(), self.sheet.clone());
+        compare(
+            "amount",
+            format!("{:?}", previous.amount),
+            format!("{:?}", self.amount),
+        );
+        compare(
+            "datetime",
+            format!("{:?}", previous.datetime),
+            format!("{:?}", self.datetime),
+        );
+        compare(
+            "currency",
+            format!("{:?}", previous.currency),
+            format!("{:?}", self.currency),
+        );
+
+        let positions = previous
+            .other_data
+            .keys()
+            .chain(self.other_data.keys())
+            .collect::<BTreeSet<_>>();
+        for pos in positions {
+            compare(
+                &format!("column {pos}"),
+                previous
+                    .other_data
+                    .get(pos)
+                    .map(summary)
+                    .unwrap_or_default(),
+                self.other_data.get(pos).map(summary).unwrap_or_default(),
+            );
+        }
+
+        let headers = |profile: &Profile| {
+            profile
+                .headers
+                .iter()
+                .sorted()
+                .map(|(pos, label)| format!("{pos} {label}"))
+                .join(", ")
+        };
+        compare("headers", headers(previous), headers(self));
+
+        let tags = |profile: &Profile| {
+            profile
+                .default_tags
+                .iter()
+                .map(|t| t.tag.as_str())
+                .sorted()
+                .join(", ")
+        };
+        compare("default tags", tags(previous), tags(self));
+        compare(
+            "origin",
+            previous.origin.name.clone(),
+            self.origin.name.clone(),
+        );
+
+        changes
+    }
+}
+
+fn summary(wrapper: &ParsableWrapper) -> String {
+    match wrapper {
+        ParsableWrapper::Description(Description(desc)) => {
+            format!("Description {}", desc.description)
+        }
+        ParsableWrapper::Special(Special(special_type, desc)) => {
+            format!("{special_type:?} {}", desc.description)
+        }
+        ParsableWrapper::StandaloneTime(StandaloneTime(time, desc)) => {
+            format!("{time:?} {}", desc.description)
+        }
+        wrapper => format!("{wrapper:?}"),
+    }
+}
@@ -0,0 +1,321 @@
+use std::fmt::Display;
+
+use chrono::{NaiveDate, NaiveDateTime};
+use itertools::Itertools;
+
+use crate::model::transactions::{currency::Currency, datetime::DateRole};
+
+use super::{
+    builder::IntermediateProfileState,
+    columns::{
+        money::{CurrencyCode, NumberFormat},
+        other::Description,
+        time::{ExpenseDate, SecondaryDate},
+        CurrencyColumn, DateTimeColumn, ExpenseColumn, ParsableWrapper,
+    },
+    error::ProfileError,
+    tokenizer::Tokenizer,
+};
+
+const DELIMITERS: [char; 4] = [';', ',', '\t', '|'];
+const DATE_FORMATS: [&str; 8] = [
+    "%d.%m.%Y", "%d.%m.%y", "%Y-%m-%d", "%d/%m/%Y", "%m/%d/%Y", "%d-%m-%Y",
+    "%Y/%m/%d", "%Y%m%d",
+];
+const DATETIME_FORMATS: [&str; 5] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%dT%H:%M:%S",
+    "%d.%m.%Y %H:%M:%S",
+    "%d.%m.%Y %H:%M",
+    "%d/%m/%Y %H:%M:%S",
+];
+/// Rows of the body that are looked at to classify the columns.
+const SAMPLE_SIZE: usize = 50;
+
+/// What a column of the sample file most likely holds.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnGuess {
+    Date(String),
+    DateTime(String),
+    Amount(NumberFormat),
+    Currency,
+    Text,
+    Empty,
+}
+
+impl Display for ColumnGuess {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ColumnGuess::Date(format) => write!(f, "date {format}"),
+            ColumnGuess::DateTime(format) => write!(f, "datetime {format}"),
+            ColumnGuess::Amount(format) => write!(f, "amount {format}"),
+            ColumnGuess::Currency => write!(f, "currency"),
+            ColumnGuess::Text => write!(f, "text"),
+            ColumnGuess::Empty => write!(f, "empty"),
+        }
+    }
+}
+
+/// Profile settings guessed from a sample file, meant to be confirmed by
+/// the user before a profile is created from them.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Inference {
+    pub delimiter: char,
+    pub quote: Option<char>,
+    pub margins: (usize, usize),
+    pub header: Option<Vec<String>>,
+    pub columns: Vec<ColumnGuess>,
+}
+
+impl Inference {
+    pub fn infer(content: &str) -> Result<Self, ProfileError> {
+        let quote = content.contains('"').then_some('"');
+        let (delimiter, width, records) = guess_delimiter(content, quote)
+            .ok_or_else(|| ProfileError::inference("no delimiter found"))?;
+
+        // the body is the longest run of rows with the common width
+        let (start, end) = records
+            .iter()
+            .enumerate()
+            .chunk_by(|(_, fields)| fields.len() == width)
+            .into_iter()
+            .filter(|(has_width, _)| *has_width)
+            .map(|(_, run)| {
+                let run = run.map(|(index, _)| index).collect_vec();
+                (run[0], run[run.len() - 1] + 1)
+            })
+            .max_by_key(|(start, end)| end - start)
+            .ok_or_else(|| ProfileError::inference("no rows found"))?;
+        if end - start < 2 {
+            return Err(ProfileError::inference("less than two rows found"));
+        }
+
+        let classify = |from: usize| {
+            (0..width)
+                .map(|pos| {
+                    let cells = records[from..end]
+                        .iter()
+                        .take(SAMPLE_SIZE)
+                        .map(|fields| fields[pos].trim())
+                        .collect_vec();
+                    guess_column(&cells)
+                })
+                .collect_vec()
+        };
+
+        // a first row that does not fit the typed columns is the header
+        let columns = classify(start + 1);
+        let first = &records[start];
+        let is_header = columns.iter().enumerate().any(|(pos, guess)| {
+            !matches!(guess, ColumnGuess::Text | ColumnGuess::Empty)
+                && guess_column(&[first[pos].trim()]) != *guess
+        });
+        let (top, header, columns) = if is_header {
+            let header = first.iter().map(|name| name.trim().to_owned());
+            (start + 1, Some(header.collect()), columns)
+        } else {
+            (start, None, classify(start))
+        };
+
+        if !columns.iter().any(|c| matches!(c, ColumnGuess::Amount(_))) {
+            return Err(ProfileError::inference("no amount column found"));
+        }
+        if !columns.iter().any(|c| {
+            matches!(c, ColumnGuess::Date(_) | ColumnGuess::DateTime(_))
+        }) {
+            return Err(ProfileError::inference("no date column found"));
+        }
+
+        Ok(Self {
+            delimiter,
+            quote,
+            margins: (top, records.len() - end),
+            header,
+            columns,
+        })
+    }
+
+    pub fn header_name(&self, pos: usize) -> Option<&str> {
+        self.header.as_ref()?.get(pos).map(String::as_str)
+    }
+
+    /// Assigns the first date and amount column as the main columns, further
+    /// dates become value dates, further amounts additional movements and
+    /// text columns descriptions named after their header.
+    pub fn apply_to(&self, state: &mut IntermediateProfileState) {
+        state.delimiter = self.delimiter.to_string();
+        state.quote = self.quote.map(String::from).unwrap_or_default();
+        state.margin_top = self.margins.0;
+        state.margin_btm = self.margins.1;
+        state.expense_col = None;
+        state.datetime_col = None;
+        state.currency_col = CurrencyColumn::default();
+        state.other_cols = vec![];
+        state.headers.clear();
+
+        for (pos, guess) in self.columns.iter().enumerate() {
+            match guess {
+                ColumnGuess::Date(format) if state.datetime_col.is_none() => {
+                    state.datetime_col =
+                        Some(DateTimeColumn::date(pos, format.clone()));
+                }
+                ColumnGuess::DateTime(format)
+                    if state.datetime_col.is_none() =>
+                {
+                    state.datetime_col =
+                        Some(DateTimeColumn::datetime(pos, format.clone()));
+                }
+                ColumnGuess::Date(format) | ColumnGuess::DateTime(format) => {
+                    state.other_cols.push((
+                        pos,
+                        SecondaryDate(
+                            ExpenseDate(format.clone()),
+                            DateRole::Value,
+                        )
+                        .into(),
+                    ));
+                }
+                ColumnGuess::Amount(format) if state.expense_col.is_none() => {
+                    state.expense_col =
+                        Some(ExpenseColumn::combined(pos, format));
+                }
+                ColumnGuess::Amount(format) => {
+                    state
+                        .other_cols
+                        .push((pos, ParsableWrapper::Movement(format.into())));
+                }
+                ColumnGuess::Currency
+                    if state.currency_col == CurrencyColumn::default() =>
+                {
+                    state.currency_col = CurrencyColumn::Column(
+                        pos,
+                        CurrencyCode(Currency::default()),
+                    );
+                }
+                ColumnGuess::Currency | ColumnGuess::Text => {
+                    let name = self
+                        .header_name(pos)
+                        .map_or(format!("column {pos}"), str::to_owned);
+                    state
+                        .other_cols
+                        .push((pos, Description::init(name).into()));
+                }
+                ColumnGuess::Empty => {}
+            }
+        }
+    }
+}
+
+/// Picks the delimiter that splits the most rows into the same number of
+/// fields, returning that width and the split rows.
+fn guess_delimiter(
+    content: &str,
+    quote: Option<char>,
+) -> Option<(char, usize, Vec<Vec<String>>)> {
+    DELIMITERS
+        .into_iter()
+        .filter_map(|delimiter| {
+            let tokenizer = Tokenizer::new(delimiter, quote);
+            let records = tokenizer
+                .records(content)
+                .iter()
+                .map(|record| {
+                    tokenizer
+                        .fields(record)
+                        .unwrap_or_else(|_| vec![record.clone()])
+                })
+                .collect_vec();
+            let (width, count) = records
+                .iter()
+                .map(Vec::len)
+                .filter(|len| *len > 1)
+                .counts()
+                .into_iter()
+                .max_by_key(|(width, count)| (*count, *width))?;
+            Some((count, delimiter, width, records))
+        })
+        // the first delimiter wins a tie
+        .rev()
+        .max_by_key(|(count, ..)| *count)
+        .map(|(_, delimiter, width, records)| (delimiter, width, records))
+}
+
+fn guess_column(cells: &[&str]) -> ColumnGuess {
+    let cells = cells
+        .iter()
+        .copied()
+        .filter(|cell| !cell.is_empty())
+        .collect_vec();
+    if cells.is_empty() {
+        return ColumnGuess::Empty;
+    }
+
+    if let Some(format) = DATE_FORMATS.iter().find(|format| {
+        cells.iter().all(|cell| {
+            NaiveDate::parse_from_str(cell, format)
+                .is_ok_and(|date| date.format(format).to_string().eq(cell))
+        })
+    }) {
+        return ColumnGuess::Date(format.to_string());
+    }
+    if let Some(format) = DATETIME_FORMATS.iter().find(|format| {
+        cells.iter().all(|cell| {
+            NaiveDateTime::parse_from_str(cell, format).is_ok_and(|datetime| {
+                datetime.format(format).to_string().eq(cell)
+            })
+        })
+    }) {
+        return ColumnGuess::DateTime(format.to_string());
+    }
+
+    if cells.iter().all(|cell| looks_numeric(cell)) {
+        let format = [
+            NumberFormat::European,
+            NumberFormat::American,
+            NumberFormat::Swiss,
+        ]
+        .into_iter()
+        .filter(|format| {
+            cells
+                .iter()
+                .all(|cell| format.parse_minor_units(cell).is_ok())
+        })
+        .map(|format| {
+            let decimal = format.spec().decimal;
+            let with_cents =
+                cells.iter().filter(|cell| has_cents(cell, decimal)).count();
+            (format, with_cents)
+        })
+        .filter(|(_, with_cents)| *with_cents > 0)
+        .max_by_key(|(_, with_cents)| *with_cents);
+        if let Some((format, _)) = format {
+            return ColumnGuess::Amount(format);
+        }
+    }
+
+    if cells.iter().all(|cell| cell.parse::<Currency>().is_ok()) {
+        return ColumnGuess::Currency;
+    }
+    ColumnGuess::Text
+}
+
+fn looks_numeric(cell: &str) -> bool {
+    cell.chars().any(|c| c.is_ascii_digit())
+        && cell.chars().all(|c| {
+            c.is_ascii_digit() || c.is_whitespace() || ".,'-+()€$£".contains(c)
+        })
+}
+
+/// Whether the amount ends with the decimal separator and two digits.
+fn has_cents(cell: &str, decimal: char) -> bool {
+    let digits = cell
+        .trim_end_matches(|c: char| !c.is_ascii_digit())
+        .chars()
+        .rev()
+        .take(3)
+        .collect::<String>();
+    digits.len() == 3
+        && digits.starts_with(|c: char| c.is_ascii_digit())
+        && digits.ends_with(decimal)
+        && digits.chars().nth(1).is_some_and(|c| c.is_ascii_digit())
+}
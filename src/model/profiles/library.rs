@@ -0,0 +1,147 @@
+use std::slice;
+
+use itertools::Itertools;
+
+use crate::model::{data_import::row::ImportRow, origins::Origin, tags::Tag};
+
+use super::{
+    error::ProfileError,
+    portable::{ImportedProfile, PortableFormat, PortableProfile},
+    ParseResult,
+};
+
+/// Profiles for common bank exports that ship with the application. The
+/// definitions are portable toml files, every one comes with an anonymized
+/// sample export it has to parse completely.
+pub const LIBRARY: [BundledProfile; 10] = [
+    BundledProfile::new(
+        "DKB",
+        include_str!("library/dkb.toml"),
+        include_str!("library/dkb.csv"),
+    ),
+    BundledProfile::new(
+        "ING",
+        include_str!("library/ing.toml"),
+        include_str!("library/ing.csv"),
+    ),
+    BundledProfile::new(
+        "N26",
+        include_str!("library/n26.toml"),
+        include_str!("library/n26.csv"),
+    ),
+    BundledProfile::new(
+        "Revolut",
+        include_str!("library/revolut.toml"),
+        include_str!("library/revolut.csv"),
+    ),
+    BundledProfile::new(
+        "PayPal",
+        include_str!("library/paypal.toml"),
+        include_str!("library/paypal.csv"),
+    ),
+    BundledProfile::new(
+        "Sparkasse CAMT-CSV",
+        include_str!("library/sparkasse_camt.toml"),
+        include_str!("library/sparkasse_camt.csv"),
+    ),
+    BundledProfile::new(
+        "CAMT.053 Statement",
+        include_str!("library/camt053.toml"),
+        include_str!("library/camt053.xml"),
+    ),
+    BundledProfile::new(
+        "MT940 Statement",
+        include_str!("library/mt940.toml"),
+        include_str!("library/mt940.sta"),
+    ),
+    BundledProfile::new(
+        "OFX/QFX Statement",
+        include_str!("library/ofx.toml"),
+        include_str!("library/ofx.ofx"),
+    ),
+    BundledProfile::new(
+        "QIF Account",
+        include_str!("library/qif.toml"),
+        include_str!("library/qif.qif"),
+    ),
+];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BundledProfile {
+    pub name: &'static str,
+    definition: &'static str,
+    fixture: &'static str,
+}
+
+impl BundledProfile {
+    const fn new(
+        name: &'static str,
+        definition: &'static str,
+        fixture: &'static str,
+    ) -> Self {
+        Self {
+            name,
+            definition,
+            fixture,
+        }
+    }
+
+    pub fn portable(&self) -> Result<PortableProfile, ProfileError> {
+        PortableProfile::parse(self.definition, PortableFormat::Toml)
+    }
+
+    /// A new profile from this definition that belongs to `origin`. Default
+    /// tags are looked up by name in `tags`.
+    pub fn instantiate(
+        &self,
+        origin: &Origin,
+        tags: &[Tag],
+    ) -> Result<ImportedProfile, ProfileError> {
+        let mut portable = self.portable()?;
+        portable.origin = origin.name.clone();
+        portable.into_profile(tags, slice::from_ref(origin))
+    }
+
+    /// Parses the sample export the same way a dropped file is parsed.
+    pub fn parse_fixture(&self) -> Result<ParseResult, ProfileError> {
+        let origin = Origin::init(self.name.to_owned(), String::new());
+        let profile = self
+            .instantiate(&origin, &[])?
+            .profile
+            .resolve_headers(self.fixture)?;
+
+        let records = profile.tokenizer().records(self.fixture);
+        let len = records.len();
+        let rows = records
+            .into_iter()
+            .enumerate()
+            .filter(|(index, _)| !profile.is_margin(*index, len))
+            .map(|(index, record)| ImportRow::init(record, index))
+            .collect_vec();
+        if rows.is_empty() {
+            return Ok(ParseResult::new(vec![], vec![], vec![]));
+        }
+        Ok(profile.parse_file(rows))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::LIBRARY;
+
+    #[test]
+    fn every_fixture_parses_completely() {
+        for bundled in LIBRARY {
+            let parsed = bundled.parse_fixture().unwrap_or_else(|err| {
+                panic!("{} cannot be parsed: {err}", bundled.name)
+            });
+            assert!(
+                parsed.row_errors.is_empty(),
+                "{} has row errors: {:?}",
+                bundled.name,
+                parsed.row_errors
+            );
+            assert!(!parsed.rows.is_empty(), "{} has no rows", bundled.name);
+        }
+    }
+}
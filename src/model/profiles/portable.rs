@@ -0,0 +1,342 @@
+use std::{collections::HashMap, path::Path};
+
+use itertools::Itertools;
+use serde::{Deserialize, Serialize};
+
+use crate::model::{
+    origins::Origin,
+    tags::Tag,
+    transactions::{
+        content_description::ContentDescription, special_content::SpecialType,
+    },
+};
+
+use super::{
+    builder::CreateProfileBuilder,
+    columns::{
+        money::{
+            CurrencyCode, DebitCredit, Expense, Income, Movement, PosExpense,
+        },
+        other::{Description, Special},
+        time::{
+            ExpenseDate, ExpenseDateTime, ExpenseTime, SecondaryDate,
+            StandaloneTime,
+        },
+        CurrencyColumn, DateTimeColumn, ExpenseColumn, ParsableWrapper,
+    },
+    encoding::FileEncoding,
+    error::ProfileError,
+    format::FileFormat,
+    Profile,
+};
+
+/// Raised whenever the layout of [`PortableProfile`] changes, files with a
+/// higher version are rejected.
+pub const PORTABLE_VERSION: u32 = 1;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PortableFormat {
+    Json,
+    Toml,
+}
+
+impl PortableFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()?.to_lowercase().as_str() {
+            "json" => Some(Self::Json),
+            "toml" => Some(Self::Toml),
+            _ => None,
+        }
+    }
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Toml => "toml",
+        }
+    }
+}
+
+/// A profile as it is shared between installations. Uuids are left out,
+/// descriptions, tags and the origin are referenced by their names.
+/// Statement formats have no columns, they always map the fields of their
+/// entries the same way.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortableProfile {
+    pub format_version: u32,
+    pub name: String,
+    pub margins: (usize, usize),
+    pub delimiter: char,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quote: Option<char>,
+    #[serde(default)]
+    pub encoding: FileEncoding,
+    #[serde(default)]
+    pub format: FileFormat,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub sheet: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub amount: Option<ExpenseColumn>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub datetime: Option<DateTimeColumn>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub currency: Option<CurrencyColumn>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub columns: Vec<PortableColumn>,
+    #[serde(default)]
+    pub headers: Vec<PortableHeader>,
+    #[serde(default)]
+    pub default_tags: Vec<String>,
+    pub origin: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortableColumn {
+    pub position: usize,
+    pub parser: PortableParser,
+}
+
+/// Header labels are kept as a list, toml only allows string keys.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PortableHeader {
+    pub position: usize,
+    pub label: String,
+}
+
+/// [`ParsableWrapper`] with the text of a description instead of the
+/// description itself.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum PortableParser {
+    Income(Income),
+    Expense(Expense),
+    PosExpense(PosExpense),
+    Movement(Movement),
+    DebitCredit(DebitCredit),
+    CurrencyCode(CurrencyCode),
+    ExpenseDateTime(ExpenseDateTime),
+    ExpenseDate(ExpenseDate),
+    ExpenseTime(ExpenseTime),
+    StandaloneTime(ExpenseTime, String),
+    SecondaryDate(SecondaryDate),
+    Description(String),
+    Special(SpecialType, String),
+}
+
+impl From<&ParsableWrapper> for PortableParser {
+    fn from(value: &ParsableWrapper) -> Self {
+        match value.clone() {
+            ParsableWrapper::Income(v) => Self::Income(v),
+            ParsableWrapper::Expense(v) => Self::Expense(v),
+            ParsableWrapper::PosExpense(v) => Self::PosExpense(v),
+            ParsableWrapper::Movement(v) => Self::Movement(v),
+            ParsableWrapper::DebitCredit(v) => Self::DebitCredit(v),
+            ParsableWrapper::CurrencyCode(v) => Self::CurrencyCode(v),
+            ParsableWrapper::ExpenseDateTime(v) => Self::ExpenseDateTime(v),
+            ParsableWrapper::ExpenseDate(v) => Self::ExpenseDate(v),
+            ParsableWrapper::ExpenseTime(v) => Self::ExpenseTime(v),
+            ParsableWrapper::StandaloneTime(StandaloneTime(time, desc)) => {
+                Self::StandaloneTime(time, desc.description)
+            }
+            ParsableWrapper::SecondaryDate(v) => Self::SecondaryDate(v),
+            ParsableWrapper::Description(Description(desc)) => {
+                Self::Description(desc.description)
+            }
+            ParsableWrapper::Special(Special(special_type, desc)) => {
+                Self::Special(special_type, desc.description)
+            }
+        }
+    }
+}
+
+impl From<PortableParser> for ParsableWrapper {
+    fn from(value: PortableParser) -> Self {
+        match value {
+            PortableParser::Income(v) => Self::Income(v),
+            PortableParser::Expense(v) => Self::Expense(v),
+            PortableParser::PosExpense(v) => Self::PosExpense(v),
+            PortableParser::Movement(v) => Self::Movement(v),
+            PortableParser::DebitCredit(v) => Self::DebitCredit(v),
+            PortableParser::CurrencyCode(v) => Self::CurrencyCode(v),
+            PortableParser::ExpenseDateTime(v) => Self::ExpenseDateTime(v),
+            PortableParser::ExpenseDate(v) => Self::ExpenseDate(v),
+            PortableParser::ExpenseTime(v) => Self::ExpenseTime(v),
+            PortableParser::StandaloneTime(time, desc) => Self::StandaloneTime(
+                StandaloneTime(time, ContentDescription::init(desc)),
+            ),
+            PortableParser::SecondaryDate(v) => Self::SecondaryDate(v),
+            PortableParser::Description(desc) => {
+                Self::Description(Description::init(desc))
+            }
+            PortableParser::Special(special_type, desc) => {
+                Self::Special(Special::init(special_type, desc))
+            }
+        }
+    }
+}
+
+/// A profile read from a file together with the tags and the origin it
+/// names which do not exist yet.
+#[derive(Debug, Clone)]
+pub struct ImportedProfile {
+    pub profile: Profile,
+    pub new_tags: Vec<Tag>,
+    pub new_origin: Option<Origin>,
+}
+
+impl From<&Profile> for PortableProfile {
+    fn from(value: &Profile) -> Self {
+        let columns = !value.format.is_statement();
+        Self {
+            format_version: PORTABLE_VERSION,
+            name: value.name.clone(),
+            margins: value.margins,
+            delimiter: value.delimiter,
+            quote: value.quote,
+            encoding: value.encoding,
+            format: value.format,
+            sheet: value.sheet.clone(),
+            amount: columns.then(|| value.amount.clone()),
+            datetime: columns.then(|| value.datetime.clone()),
+            currency: columns.then(|| value.currency.clone()),
+            columns: value
+                .other_data
+                .iter()
+                .filter(|_| columns)
+                .sorted_by_key(|(pos, _)| **pos)
+                .map(|(pos, wrapper)| PortableColumn {
+                    position: *pos,
+                    parser: wrapper.into(),
+                })
+                .collect(),
+            headers: value
+                .headers
+                .iter()
+                .sorted()
+                .map(|(pos, label)| PortableHeader {
+                    position: *pos,
+                    label: label.clone(),
+                })
+                .collect(),
+            default_tags: value
+                .default_tags
+                .iter()
+                .map(|tag| tag.tag.clone())
+                .collect(),
+            origin: value.origin.name.clone(),
+        }
+    }
+}
+
+impl PortableProfile {
+    pub fn parse(
+        content: &str,
+        format: PortableFormat,
+    ) -> Result<Self, ProfileError> {
+        let portable: Self = match format {
+            PortableFormat::Json => {
+                serde_json::from_str(content).map_err(ProfileError::portable)?
+            }
+            PortableFormat::Toml => {
+                toml::from_str(content).map_err(ProfileError::portable)?
+            }
+        };
+        if portable.format_version > PORTABLE_VERSION {
+            return Err(ProfileError::portable_version(
+                portable.format_version,
+                PORTABLE_VERSION,
+            ));
+        }
+        Ok(portable)
+    }
+
+    pub fn write(
+        &self,
+        format: PortableFormat,
+    ) -> Result<String, ProfileError> {
+        match format {
+            PortableFormat::Json => serde_json::to_string_pretty(self)
+                .map_err(ProfileError::portable),
+            PortableFormat::Toml => {
+                toml::to_string_pretty(self).map_err(ProfileError::portable)
+            }
+        }
+    }
+
+    /// Builds a new profile, tags and the origin are looked up by name in
+    /// `tags` and `origins` and created when they are missing.
+    pub fn into_profile(
+        self,
+        tags: &[Tag],
+        origins: &[Origin],
+    ) -> Result<ImportedProfile, ProfileError> {
+        let invalid = || ProfileError::portable("the columns overlap");
+
+        let mut new_tags = vec![];
+        let default_tags = self
+            .default_tags
+            .into_iter()
+            .map(|name| match tags.iter().find(|tag| tag.tag == name) {
+                Some(tag) => tag.clone(),
+                None => {
+                    let tag = Tag::init(name, String::new());
+                    new_tags.push(tag.clone());
+                    tag
+                }
+            })
+            .collect_vec();
+
+        let (origin, new_origin) =
+            match origins.iter().find(|origin| origin.name == self.origin) {
+                Some(origin) => (origin.clone(), None),
+                None => {
+                    let origin = Origin::init(self.origin, String::new());
+                    (origin.clone(), Some(origin))
+                }
+            };
+
+        let mut builder = CreateProfileBuilder::default()
+            .name(self.name)
+            .margins(self.margins.0, self.margins.1)
+            .delimiter(self.delimiter)
+            .quote(self.quote)
+            .encoding(self.encoding)
+            .format(self.format)
+            .sheet(self.sheet)
+            .headers(
+                self.headers
+                    .into_iter()
+                    .map(|header| (header.position, header.label))
+                    .collect::<HashMap<_, _>>(),
+            )
+            .default_tags(default_tags);
+        builder.origin(origin);
+        if self.format.is_statement() {
+            builder.statement_columns().map_err(|_| invalid())?;
+        } else {
+            let (Some(amount), Some(datetime)) = (self.amount, self.datetime)
+            else {
+                return Err(ProfileError::portable(
+                    "the amount or the datetime column is missing",
+                ));
+            };
+            builder.expense_col(amount).map_err(|_| invalid())?;
+            builder.datetime_col(datetime).map_err(|_| invalid())?;
+            builder
+                .currency_col(self.currency.unwrap_or_default())
+                .map_err(|_| invalid())?;
+            builder
+                .other_cols(
+                    self.columns
+                        .into_iter()
+                        .map(|column| (column.position, column.parser.into()))
+                        .collect(),
+                )
+                .map_err(|_| invalid())?;
+        }
+
+        Ok(ImportedProfile {
+            profile: builder.build().map_err(|_| invalid())?,
+            new_tags,
+            new_origin,
+        })
+    }
+}
@@ -0,0 +1,290 @@
+use std::{borrow::Cow, mem};
+
+use chrono::NaiveDateTime;
+
+use super::{
+    error::ProfileError,
+    format::{
+        camt053, mt940, ofx, qif, spreadsheet, FileFormat, StatementBalance,
+        StatementEntry,
+    },
+};
+
+/// Splits csv content into logical records and records into fields,
+/// following RFC 4180. Without a quote char every line is a record and
+/// fields are split on the bare delimiter. Statement formats have a record
+/// per booking and always the fields of a [`StatementEntry`]. Sheets are
+/// read as csv with a delimiter and quote of their own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Tokenizer {
+    delimiter: char,
+    quote: Option<char>,
+    format: FileFormat,
+}
+
+/// What a field holds according to the file. Only sheets know numbers and
+/// dates, those are handed to the parsers as they are instead of being read
+/// in the format of the column.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Cell {
+    #[default]
+    Text,
+    /// Written like `-1234.5`.
+    Number,
+    DateTime(NaiveDateTime),
+}
+
+impl Tokenizer {
+    pub fn new(delimiter: char, quote: Option<char>) -> Self {
+        Self {
+            delimiter,
+            quote,
+            format: FileFormat::Csv,
+        }
+    }
+
+    pub fn with_format(mut self, format: FileFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    pub fn records(&self, content: &str) -> Vec<String> {
+        match self.format {
+            FileFormat::Csv => self.csv_records(content),
+            FileFormat::Camt053 => camt053::records(content),
+            FileFormat::Mt940 => mt940::records(content),
+            FileFormat::Ofx => ofx::records(content),
+            FileFormat::Qif => qif::records(content),
+            FileFormat::Spreadsheet => Self::sheet().csv_records(content),
+        }
+    }
+
+    pub fn fields(&self, record: &str) -> Result<Vec<String>, ProfileError> {
+        match self.format {
+            FileFormat::Csv => self.csv_fields(record),
+            FileFormat::Camt053 => {
+                camt053::entry(record).map(StatementEntry::into_fields)
+            }
+            FileFormat::Mt940 => {
+                mt940::entry(record).map(StatementEntry::into_fields)
+            }
+            FileFormat::Ofx => {
+                ofx::entry(record).map(StatementEntry::into_fields)
+            }
+            FileFormat::Qif => {
+                qif::entry(record).map(StatementEntry::into_fields)
+            }
+            FileFormat::Spreadsheet => Self::sheet().csv_fields(record),
+        }
+    }
+
+    /// The balances of the statements in `content`, stored with the import
+    /// since they belong to no single row. Only MT940 has them so far.
+    pub fn balances(
+        &self,
+        content: &str,
+    ) -> Result<Vec<StatementBalance>, ProfileError> {
+        match self.format {
+            FileFormat::Mt940 => mt940::balances(content),
+            _ => Ok(vec![]),
+        }
+    }
+
+    /// The fields of `record` with what they hold.
+    pub fn cells(
+        &self,
+        record: &str,
+    ) -> Result<Vec<(String, Cell)>, ProfileError> {
+        match self.format {
+            FileFormat::Spreadsheet => Self::sheet()
+                .csv_quoted_fields(record)?
+                .into_iter()
+                .map(|(field, quoted)| {
+                    let cell = match quoted {
+                        true => Cell::Text,
+                        false => spreadsheet::cell(&field)?,
+                    };
+                    Ok((field, cell))
+                })
+                .collect(),
+            _ => Ok(self
+                .fields(record)?
+                .into_iter()
+                .map(|field| (field, Cell::Text))
+                .collect()),
+        }
+    }
+
+    fn sheet() -> Self {
+        Self::new(spreadsheet::DELIMITER, Some(spreadsheet::QUOTE))
+    }
+
+    /// The part of a record that stays the same when the booking is
+    /// exported again, overlaps are found by its hash. Only OFX has an id
+    /// for that, other records are their own identity.
+    pub fn identity<'a>(&self, record: &'a str) -> Cow<'a, str> {
+        match self.format {
+            FileFormat::Ofx => ofx::fitid(record)
+                .map_or(Cow::Borrowed(record), |id| {
+                    Cow::Owned(format!("FITID:{id}"))
+                }),
+            _ => Cow::Borrowed(record),
+        }
+    }
+
+    /// Joins physical lines into logical records. A line break inside of
+    /// a quoted field is kept as a `\n` in the resulting record.
+    fn csv_records(&self, content: &str) -> Vec<String> {
+        let Some(quote) = self.quote else {
+            return content.lines().map(str::to_owned).collect();
+        };
+
+        let mut records = vec![];
+        let mut current: Option<String> = None;
+        let mut in_quotes = false;
+
+        for line in content.lines() {
+            // an escaped quote toggles twice, so counting is enough here
+            let quotes = line.chars().filter(|c| quote.eq(c)).count();
+            match current.as_mut() {
+                Some(record) => {
+                    record.push('\n');
+                    record.push_str(line);
+                }
+                None => current = Some(line.to_owned()),
+            }
+            in_quotes ^= quotes % 2 == 1;
+            if !in_quotes {
+                records.extend(current.take());
+            }
+        }
+        // unterminated quote at the end of the file, let `fields` report it
+        records.extend(current);
+        records
+    }
+
+    fn csv_fields(&self, record: &str) -> Result<Vec<String>, ProfileError> {
+        Ok(self
+            .csv_quoted_fields(record)?
+            .into_iter()
+            .map(|(field, _)| field)
+            .collect())
+    }
+
+    /// The fields of `record`, each with whether it was quoted.
+    fn csv_quoted_fields(
+        &self,
+        record: &str,
+    ) -> Result<Vec<(String, bool)>, ProfileError> {
+        let Some(quote) = self.quote else {
+            return Ok(record
+                .split(self.delimiter)
+                .map(|field| (field.to_owned(), false))
+                .collect());
+        };
+
+        let mut fields = vec![];
+        let mut field = String::new();
+        let mut quoted = false;
+        let mut in_quotes = false;
+        let mut at_field_start = true;
+        let mut chars = record.chars().peekable();
+
+        while let Some(char) = chars.next() {
+            if in_quotes {
+                if char != quote {
+                    field.push(char);
+                } else if chars.next_if_eq(&quote).is_some() {
+                    field.push(quote);
+                } else {
+                    in_quotes = false;
+                }
+            } else if char == self.delimiter {
+                fields.push((mem::take(&mut field), mem::take(&mut quoted)));
+                at_field_start = true;
+                continue;
+            } else if char == quote && at_field_start {
+                in_quotes = true;
+                quoted = true;
+            } else {
+                field.push(char);
+            }
+            at_field_start = false;
+        }
+
+        if in_quotes {
+            return Err(ProfileError::unterminated_quote(record));
+        }
+        fields.push((field, quoted));
+        Ok(fields)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Tokenizer;
+
+    fn csv() -> Tokenizer {
+        Tokenizer::new(';', Some('"'))
+    }
+
+    #[test]
+    fn quoted_delimiter_stays_in_field() {
+        let fields = csv().fields(r#"a;"b;c";d"#).unwrap();
+        assert_eq!(fields, ["a", "b;c", "d"]);
+    }
+
+    #[test]
+    fn doubled_quote_is_one_quote() {
+        let fields = csv().fields(r#""say ""hi""";"""";x"y"#).unwrap();
+        assert_eq!(fields, [r#"say "hi""#, r#"""#, r#"x"y"#]);
+    }
+
+    #[test]
+    fn empty_fields_are_kept() {
+        let fields = csv().fields(r#";"";"#).unwrap();
+        assert_eq!(fields, ["", "", ""]);
+    }
+
+    #[test]
+    fn quoted_line_break_joins_records() {
+        let content = "a;\"first\nsecond\";b\n\"x\"\"\ny\";c\nlast;1";
+        let records = csv().records(content);
+        assert_eq!(
+            records,
+            ["a;\"first\nsecond\";b", "\"x\"\"\ny\";c", "last;1"]
+        );
+        assert_eq!(
+            csv().fields(&records[0]).unwrap(),
+            ["a", "first\nsecond", "b"]
+        );
+        assert_eq!(csv().fields(&records[1]).unwrap(), ["x\"\ny", "c"]);
+    }
+
+    #[test]
+    fn unterminated_quote_is_an_error() {
+        let records = csv().records("a;\"open\nb;c");
+        assert_eq!(records, ["a;\"open\nb;c"]);
+        assert!(csv().fields(&records[0]).is_err());
+    }
+
+    #[test]
+    fn without_quote_every_delimiter_splits() {
+        let tokenizer = Tokenizer::new(',', None);
+        assert_eq!(tokenizer.records("\"a\n,b\""), ["\"a", ",b\""]);
+        assert_eq!(tokenizer.fields("\"a,b\",c").unwrap(), ["\"a", "b\"", "c"]);
+    }
+
+    #[test]
+    fn quoted_fields_are_marked() {
+        let fields = csv().csv_quoted_fields(r#"1;"2";"#).unwrap();
+        assert_eq!(
+            fields,
+            [
+                ("1".to_owned(), false),
+                ("2".to_owned(), true),
+                (String::new(), false)
+            ]
+        );
+    }
+}
@@ -1,15 +1,19 @@
 pub mod content_description;
+pub mod currency;
 pub mod datetime;
+pub mod duplicates;
 pub mod movement;
 pub mod properties;
 pub mod special_content;
 pub mod text_content;
 
-use std::cmp::Ordering;
+use std::{cmp::Ordering, collections::BTreeMap};
 
 use chrono::{DateTime, Local};
-use datetime::Datetime;
-use movement::Movement;
+use currency::Currency;
+use datetime::{DateRole, Datetime};
+use itertools::Itertools;
+use movement::{checked_sum, Movement};
 use properties::TransactionProperties;
 use sea_orm::entity::prelude::*;
 
@@ -35,10 +39,53 @@ impl Transaction {
         &self.datetime.datetime
     }
 
+    pub fn datetime_by_role(&self, role: DateRole) -> Option<&Datetime> {
+        self.properties.iter().find_map(|property| match property {
+            TransactionProperties::Datetime(datetime)
+                if datetime.role == Some(role) =>
+            {
+                Some(datetime)
+            }
+            _ => None,
+        })
+    }
+
+    /// Datetime of the given role, falls back to the primary datetime when
+    /// no role is given or the transaction has no such datetime.
+    pub fn datetime_of(&self, role: Option<DateRole>) -> &DateTime<Local> {
+        role.and_then(|role| self.datetime_by_role(role))
+            .map_or(self.datetime(), |datetime| &datetime.datetime)
+    }
+
     pub fn amount(&self) -> f64 {
         self.movement.amount as f64 / 100f64
     }
 
+    pub fn currency(&self) -> &Currency {
+        &self.movement.currency
+    }
+
+    pub fn amount_text(&self) -> String {
+        format!("{:.2} {}", self.amount(), self.currency())
+    }
+
+    /// Sums the amounts in cents, amounts of different currencies are never
+    /// added together. A currency whose sum overflows is `None`.
+    pub fn totals<'a>(
+        transactions: impl IntoIterator<Item = &'a Self>,
+    ) -> BTreeMap<Currency, Option<i64>> {
+        transactions
+            .into_iter()
+            .into_group_map_by(|trx| trx.currency().clone())
+            .into_iter()
+            .map(|(currency, trxs)| {
+                let sum =
+                    checked_sum(trxs.iter().map(|trx| trx.movement.amount));
+                (currency, sum)
+            })
+            .collect()
+    }
+
     pub fn sorting_fn() -> impl FnMut(&Self, &Self) -> Ordering {
         |a, b| {
             a.datetime()
@@ -0,0 +1,78 @@
+use std::{fmt::Display, str::FromStr};
+
+use sea_orm::{DbErr, DeriveValueType, TryFromU64};
+use serde::{Deserialize, Serialize};
+
+/// ISO 4217 currency code like `EUR`, always stored in upper case.
+#[derive(
+    Debug,
+    Clone,
+    PartialEq,
+    Eq,
+    PartialOrd,
+    Ord,
+    Hash,
+    Serialize,
+    Deserialize,
+    DeriveValueType,
+)]
+#[serde(try_from = "String", into = "String")]
+pub struct Currency(String);
+
+impl Currency {
+    /// Currencies offered in the ui, any other valid code can still be used.
+    pub fn common() -> [Self; 6] {
+        ["EUR", "CHF", "USD", "GBP", "JPY", "SEK"].map(|code| Self(code.into()))
+    }
+
+    pub fn code(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Amounts were stored without a currency before, those are euros.
+impl Default for Currency {
+    fn default() -> Self {
+        Self("EUR".into())
+    }
+}
+
+impl FromStr for Currency {
+    type Err = String;
+
+    fn from_str(str: &str) -> Result<Self, Self::Err> {
+        let code = str.trim();
+        if code.len() == 3 && code.chars().all(|c| c.is_ascii_alphabetic()) {
+            Ok(Self(code.to_ascii_uppercase()))
+        } else {
+            Err(format!("{str} is not an ISO 4217 currency code"))
+        }
+    }
+}
+
+impl TryFrom<String> for Currency {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Currency> for String {
+    fn from(value: Currency) -> Self {
+        value.0
+    }
+}
+
+/// Needed to be part of the primary key of the exchange rates.
+impl TryFromU64 for Currency {
+    fn try_from_u64(_: u64) -> Result<Self, DbErr> {
+        Err(DbErr::ConvertFromU64("Currency"))
+    }
+}
+
+impl Display for Currency {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
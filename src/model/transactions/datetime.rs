@@ -1,6 +1,8 @@
 use std::cmp::Ordering;
 
-use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveTime};
+use chrono::{DateTime, FixedOffset, Local, NaiveDate, NaiveTime, TimeZone};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
 
 use crate::{db::InitUuid, model::group::GroupUuid, uuid_impls};
 
@@ -16,11 +18,54 @@ pub struct Datetime {
     pub time: Option<NaiveTime>,
     pub timezone: i32,
     pub group_uuid: GroupUuid,
+    pub role: Option<DateRole>,
     pub datetime: DateTime<Local>,
 }
 
 uuid_impls!(DatetimeUuid);
 
+/// Meaning of an additional datetime of a transaction. The primary
+/// datetime has no role.
+#[derive(
+    Copy,
+    Clone,
+    Debug,
+    PartialEq,
+    Eq,
+    Hash,
+    DeriveActiveEnum,
+    EnumIter,
+    Default,
+    Serialize,
+    Deserialize,
+)]
+#[sea_orm(rs_type = "String", db_type = "String(StringLen::N(255))")]
+pub enum DateRole {
+    #[sea_orm(string_value = "Value")]
+    #[default]
+    Value,
+    #[sea_orm(string_value = "Booking")]
+    Booking,
+    #[sea_orm(string_value = "Completed")]
+    Completed,
+}
+
+impl DateRole {
+    pub fn values() -> [Self; 3] {
+        [Self::Value, Self::Booking, Self::Completed]
+    }
+}
+
+impl std::fmt::Display for DateRole {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DateRole::Value => write!(f, "value date"),
+            DateRole::Booking => write!(f, "booking date"),
+            DateRole::Completed => write!(f, "completed date"),
+        }
+    }
+}
+
 impl Datetime {
     pub fn init(
         date: NaiveDate,
@@ -35,10 +80,26 @@ impl Datetime {
             time,
             timezone,
             group_uuid,
+            role: None,
             datetime: Self::compute_datetime(date, time, timezone),
         }
     }
 
+    /// Uses the offset the local timezone had at the given date and time,
+    /// so imported dates keep their day across daylight saving changes.
+    pub fn init_local(
+        date: NaiveDate,
+        time: Option<NaiveTime>,
+        group_uuid: GroupUuid,
+    ) -> Self {
+        Self::init(date, time, Self::local_offset(date, time), group_uuid)
+    }
+
+    pub fn with_role(mut self, role: DateRole) -> Self {
+        self.role = Some(role);
+        self
+    }
+
     pub fn init_datetime(
         datetime: DateTime<Local>,
         group_uuid: GroupUuid,
@@ -58,6 +119,7 @@ impl Datetime {
         time: Option<NaiveTime>,
         timezone: i32,
         group_uuid: GroupUuid,
+        role: Option<DateRole>,
     ) -> Self {
         let datetime = Self::compute_datetime(date, time, timezone);
         Self {
@@ -67,10 +129,21 @@ impl Datetime {
             time,
             timezone,
             group_uuid,
+            role,
             datetime,
         }
     }
 
+    fn local_offset(date: NaiveDate, time: Option<NaiveTime>) -> i32 {
+        Local
+            .offset_from_local_datetime(
+                &date.and_time(time.unwrap_or_default()),
+            )
+            .earliest()
+            .map(|offset| offset.local_minus_utc())
+            .unwrap_or_default()
+    }
+
     fn compute_datetime(
         date: NaiveDate,
         time: Option<NaiveTime>,
@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use itertools::Itertools;
+
+use super::{properties::TransactionProperties, State, Transaction};
+
+/// Days the dates of the same booking may differ between two exports, banks
+/// move the booking date around weekends and holidays.
+pub const DATE_TOLERANCE_DAYS: i64 = 3;
+
+/// Share of description words two transactions need in common.
+pub const MIN_TEXT_SIMILARITY: f64 = 0.5;
+
+/// A parsed transaction that is probably already saved, even though its raw
+/// row differs from every stored row.
+#[derive(Debug, Clone)]
+pub struct SemanticDuplicate {
+    /// Index into the parsed transactions.
+    pub new: usize,
+    pub existing: Transaction,
+    pub similarity: f64,
+    /// Duplicates are left out of the import unless included by hand.
+    pub include: bool,
+}
+
+impl SemanticDuplicate {
+    /// Pairs every parsed transaction with the most similar existing one of
+    /// the same amount and currency whose date is within
+    /// [`DATE_TOLERANCE_DAYS`]. An existing transaction is paired at most
+    /// once, so a booking that happens twice a day is only reported twice
+    /// when it was saved twice.
+    pub fn find(new: &[Transaction], existing: &[Transaction]) -> Vec<Self> {
+        let existing = existing
+            .iter()
+            .filter(|trx| trx.state != State::Deleted)
+            .map(|trx| (trx, words(trx)))
+            .collect_vec();
+        let mut paired = HashSet::new();
+
+        new.iter()
+            .enumerate()
+            .filter_map(|(index, trx)| {
+                let new_words = words(trx);
+                let (existing, similarity) = existing
+                    .iter()
+                    .filter(|(other, _)| {
+                        !paired.contains(&other.uuid)
+                            && other.movement.amount == trx.movement.amount
+                            && other.currency() == trx.currency()
+                            && (other.datetime().date_naive()
+                                - trx.datetime().date_naive())
+                            .num_days()
+                            .abs()
+                                <= DATE_TOLERANCE_DAYS
+                    })
+                    .map(|(other, other_words)| {
+                        (*other, similarity(&new_words, other_words))
+                    })
+                    .filter(|(_, similarity)| {
+                        *similarity >= MIN_TEXT_SIMILARITY
+                    })
+                    .max_by(|a, b| a.1.total_cmp(&b.1))?;
+
+                paired.insert(existing.uuid);
+                Some(Self {
+                    new: index,
+                    existing: existing.clone(),
+                    similarity,
+                    include: false,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Lowercase words of the text properties, so changed whitespace, casing
+/// or punctuation does not matter.
+fn words(trx: &Transaction) -> HashSet<String> {
+    trx.properties
+        .iter()
+        .filter_map(|property| match property {
+            TransactionProperties::Text(text) => Some(text.content.as_str()),
+            _ => None,
+        })
+        .flat_map(|text| text.split(|c: char| !c.is_alphanumeric()))
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Jaccard index of the words. A transaction without any text has nothing
+/// to compare, so it is never similar, not even to another one without
+/// text.
+fn similarity(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.;
+    }
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::similarity;
+
+    fn words(text: &str) -> HashSet<String> {
+        text.split_whitespace().map(str::to_string).collect()
+    }
+
+    #[test]
+    fn without_text_is_not_similar() {
+        assert_eq!(similarity(&words(""), &words("")), 0.);
+        assert_eq!(similarity(&words("rent"), &words("")), 0.);
+        assert_eq!(similarity(&words(""), &words("rent")), 0.);
+    }
+
+    #[test]
+    fn shared_words_of_all_words() {
+        assert_eq!(similarity(&words("rent may"), &words("rent may")), 1.);
+        assert_eq!(
+            similarity(&words("rent may"), &words("rent june")),
+            1. / 3.
+        );
+    }
+}
@@ -1,6 +1,6 @@
 use crate::{db::InitUuid, model::group::GroupUuid, uuid_impls};
 
-use super::properties::OriginType;
+use super::{currency::Currency, properties::OriginType};
 
 pub type ModelMovement = Movement;
 
@@ -8,33 +8,50 @@ pub type ModelMovement = Movement;
 pub struct Movement {
     pub uuid: MovementUuid,
     pub origin_type: OriginType,
-    pub amount: i32,
+    /// In the minor unit of the currency, usually cents.
+    pub amount: i64,
+    pub currency: Currency,
     pub group_uuid: GroupUuid,
 }
 
 uuid_impls!(MovementUuid);
 
 impl Movement {
-    pub fn init(amount: i32, group_uuid: GroupUuid) -> Self {
+    pub fn init(amount: i64, group_uuid: GroupUuid) -> Self {
         Self {
             uuid: MovementUuid::init(),
             origin_type: OriginType::CsvImport,
             amount,
+            currency: Currency::default(),
             group_uuid,
         }
     }
 
+    pub fn with_currency(mut self, currency: Currency) -> Self {
+        self.currency = currency;
+        self
+    }
+
     pub fn new(
         uuid: MovementUuid,
         origin_type: OriginType,
-        amount: i32,
+        amount: i64,
+        currency: Currency,
         group_uuid: GroupUuid,
     ) -> Self {
         Self {
             uuid,
             origin_type,
             amount,
+            currency,
             group_uuid,
         }
     }
 }
+
+/// Sums amounts of one currency, `None` if the sum does not fit.
+pub fn checked_sum(amounts: impl IntoIterator<Item = i64>) -> Option<i64> {
+    amounts
+        .into_iter()
+        .try_fold(0i64, |sum, amount| sum.checked_add(amount))
+}
@@ -45,13 +45,18 @@ pub enum SpecialType {
     AccountBalance,
     #[sea_orm(string_value = "CompletedDate")]
     CompletedDate,
+    #[sea_orm(string_value = "Time")]
+    Time,
     #[sea_orm(string_value = "Unknown")]
     #[default]
     Unknown,
 }
 
 impl SpecialType {
-    pub fn values() -> [Self; 8] {
+    /// Types that can be picked for a plain special column, [`Self::Time`]
+    /// is only produced by the standalone time column. [`Self::CompletedDate`]
+    /// is kept for stored contents, new profiles use a secondary date column.
+    pub fn values() -> [Self; 7] {
         [
             Self::CurrencyExchangeRate,
             Self::OriginalCurrency,
@@ -59,7 +64,6 @@ impl SpecialType {
             Self::TransactionState,
             Self::TransactionType,
             Self::AccountBalance,
-            Self::CompletedDate,
             Self::Unknown,
         ]
     }